@@ -0,0 +1,11 @@
+#![no_main]
+#![allow(deprecated)]
+
+use libfuzzer_sys::fuzz_target;
+
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::RootEntry;
+
+fuzz_target!(|s: &str| {
+    let _ = serde_json::from_str::<Body>(&Body::pre_filter(s));
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model_fuzz::parse;
+
+fuzz_target!(|data: &[u8]| parse::<SystemPopulated>(data));
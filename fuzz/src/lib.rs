@@ -0,0 +1,13 @@
+use edsm_dumps_model::model::RootEntry;
+
+/// Parses `data` as a dump record of `T`, which may fail but must not panic.
+///
+/// Accepted records are written back to JSON.
+pub fn parse<T: RootEntry>(data: &[u8]) {
+    if let Ok(entry) = T::parse_dump_json(data) {
+        serde_json::to_vec(&entry).unwrap();
+    }
+    if let Ok(entry) = T::parse_dump_json_mut(&mut data.to_vec()) {
+        serde_json::to_vec(&entry).unwrap();
+    }
+}
@@ -0,0 +1,683 @@
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow_array::builder::{
+    BooleanBuilder, Float32Builder, StringBuilder, StringDictionaryBuilder, TimestampSecondBuilder,
+    UInt64Builder,
+};
+use arrow_array::types::Int32Type;
+use arrow_array::{Array, ArrayRef, ListArray, RecordBatch, StructArray};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef};
+use chrono::{DateTime, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::{WriterProperties, WriterPropertiesBuilder};
+use strum::IntoEnumIterator;
+
+use crate::model::bgs::{ControllingFaction, PendingState, RecoveringState};
+use crate::model::body::{
+    AtmosphereCompositionKey, Belt, Body, MaterialsKey, Parent, Planet, Ring, Star,
+};
+use crate::model::powerplay::PowerPlay;
+use crate::model::station::{Commodity, Outfitting, Ship, Station, StationBody, UpdateTime};
+use crate::model::system::{Coords, SystemWithCoordinates};
+use crate::model::system_populated::{FactionInPopulated, StationInPopulated, SystemPopulated};
+use crate::model::util::snake_case;
+
+const DEFAULT_ROW_GROUP_SIZE: usize = 64 * 1024;
+
+/// Entry convertible to Arrow record batches.
+///
+/// The columns are declared once in [`columns`](ArrowEntry::columns), from which both
+/// the schema and the batches are built:
+///
+/// * nested structs such as [`Coords`] are flattened into `coords_x`, `coords_y` and `coords_z`
+/// * enums are dictionary-encoded strings holding their `Display` form
+/// * maps keyed by an enum, such as `Materials`, have one column per key, e.g. `materials_iron`
+/// * lists of structs, such as `rings`, are lists of Arrow structs, or of values if the items have a single column
+/// * timestamps are seconds in UTC
+pub trait ArrowEntry: Sized {
+    fn columns<'a>(cols: &mut Columns<'a, Self>);
+
+    fn schema() -> SchemaRef {
+        let mut cols = Columns::new(Vec::new());
+        Self::columns(&mut cols);
+        Arc::new(Schema::new(cols.fields))
+    }
+
+    fn to_record_batch(entries: &[Self]) -> Result<RecordBatch> {
+        let mut cols = Columns::new(entries.iter().map(Some).collect());
+        Self::columns(&mut cols);
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(cols.fields)), cols.arrays)?;
+        Ok(batch)
+    }
+}
+
+/// Column values of a batch of entries, filled by [`ArrowEntry::columns`].
+pub struct Columns<'a, T> {
+    /// `None` for the rows of a flattened struct which is absent.
+    rows: Vec<Option<&'a T>>,
+    nullable: bool,
+    fields: Vec<Field>,
+    arrays: Vec<ArrayRef>,
+}
+
+/// Value of a column, which is nullable if it is an `Option`.
+pub trait Cell<V> {
+    const NULLABLE: bool;
+
+    fn into_cell(self) -> Option<V>;
+}
+
+impl<V> Cell<V> for V {
+    const NULLABLE: bool = false;
+
+    fn into_cell(self) -> Option<V> {
+        Some(self)
+    }
+}
+
+impl<V> Cell<V> for Option<V> {
+    const NULLABLE: bool = true;
+
+    fn into_cell(self) -> Option<V> {
+        self
+    }
+}
+
+impl<'a, T> Columns<'a, T> {
+    fn new(rows: Vec<Option<&'a T>>) -> Columns<'a, T> {
+        Columns {
+            rows,
+            nullable: false,
+            fields: Vec::new(),
+            arrays: Vec::new(),
+        }
+    }
+
+    fn values<V, C: Cell<V>>(&self, f: impl Fn(&'a T) -> C) -> Vec<Option<V>> {
+        self.rows
+            .iter()
+            .map(|row| row.and_then(|x| f(x).into_cell()))
+            .collect()
+    }
+
+    fn push<C: Cell<V>, V>(&mut self, name: &str, data_type: DataType, array: ArrayRef) {
+        let nullable = self.nullable || C::NULLABLE;
+        self.fields.push(Field::new(name, data_type, nullable));
+        self.arrays.push(array);
+    }
+
+    pub fn u64<C: Cell<u64>>(&mut self, name: &str, f: impl Fn(&'a T) -> C) {
+        let mut array = UInt64Builder::new();
+        array.extend(self.values(f));
+        self.push::<C, _>(name, DataType::UInt64, Arc::new(array.finish()));
+    }
+
+    pub fn f32<C: Cell<f32>>(&mut self, name: &str, f: impl Fn(&'a T) -> C) {
+        let mut array = Float32Builder::new();
+        array.extend(self.values(f));
+        self.push::<C, _>(name, DataType::Float32, Arc::new(array.finish()));
+    }
+
+    pub fn bool<C: Cell<bool>>(&mut self, name: &str, f: impl Fn(&'a T) -> C) {
+        let mut array = BooleanBuilder::new();
+        array.extend(self.values(f));
+        self.push::<C, _>(name, DataType::Boolean, Arc::new(array.finish()));
+    }
+
+    pub fn str<C: Cell<&'a str>>(&mut self, name: &str, f: impl Fn(&'a T) -> C) {
+        let mut array = StringBuilder::new();
+        array.extend(self.values(f));
+        self.push::<C, _>(name, DataType::Utf8, Arc::new(array.finish()));
+    }
+
+    pub fn timestamp<C: Cell<DateTime<Utc>>>(&mut self, name: &str, f: impl Fn(&'a T) -> C) {
+        let mut array = TimestampSecondBuilder::new().with_timezone("UTC");
+        array.extend(self.values(f).into_iter().map(|v| v.map(|d| d.timestamp())));
+        let array = array.finish();
+        let data_type = array.data_type().clone();
+        self.push::<C, _>(name, data_type, Arc::new(array));
+    }
+
+    /// Dictionary-encoded column of the `Display` form of values.
+    pub fn dict<E, C>(&mut self, name: &str, f: impl Fn(&'a T) -> C)
+    where
+        E: fmt::Display + ?Sized + 'a,
+        C: Cell<&'a E>,
+    {
+        let mut array = StringDictionaryBuilder::<Int32Type>::new();
+        let mut buf = String::new();
+        for v in self.values(f) {
+            match v {
+                Some(v) => {
+                    buf.clear();
+                    write!(buf, "{}", v).expect("a Display implementation returned an error");
+                    array.append_value(&buf);
+                }
+                None => array.append_null(),
+            }
+        }
+        self.push::<C, _>(name, dict_type(), Arc::new(array.finish()));
+    }
+
+    /// List of dictionary-encoded strings.
+    pub fn dict_list<E, C>(&mut self, name: &str, f: impl Fn(&'a T) -> C)
+    where
+        E: fmt::Display + 'a,
+        C: Cell<&'a [E]>,
+    {
+        self.list(name, f, |cols| cols.dict("item", |x: &E| x));
+    }
+
+    /// Flattens the columns of a nested struct, prefixing their names with `prefix_`.
+    pub fn flatten<U: 'a, C: Cell<&'a U>>(
+        &mut self,
+        prefix: &str,
+        f: impl Fn(&'a T) -> C,
+        columns: impl FnOnce(&mut Columns<'a, U>),
+    ) {
+        let mut nested = Columns::new(self.values(f));
+        nested.nullable = self.nullable || C::NULLABLE;
+        columns(&mut nested);
+        for (field, array) in nested.fields.into_iter().zip(nested.arrays) {
+            let name = format!("{}_{}", prefix, field.name());
+            self.fields.push(field.with_name(name));
+            self.arrays.push(array);
+        }
+    }
+
+    /// List of structs, with the columns declared by `columns`.
+    ///
+    /// Items having a single column are stored as the values of that column.
+    pub fn list<U: 'a, C: Cell<&'a [U]>>(
+        &mut self,
+        name: &str,
+        f: impl Fn(&'a T) -> C,
+        columns: impl FnOnce(&mut Columns<'a, U>),
+    ) {
+        let lists: Vec<Option<&'a [U]>> = self.values(f);
+        let mut items = Columns::new(
+            lists
+                .iter()
+                .flatten()
+                .flat_map(|xs| xs.iter().map(Some))
+                .collect(),
+        );
+        columns(&mut items);
+
+        let (item, values): (Field, ArrayRef) = if items.fields.len() == 1 {
+            let field = items.fields.pop().expect("one field");
+            let values = items.arrays.pop().expect("one array per field");
+            (field.with_name("item"), values)
+        } else {
+            let values = StructArray::try_new(Fields::from(items.fields), items.arrays, None)
+                .expect("columns have the same length");
+            (
+                Field::new("item", values.data_type().clone(), false),
+                Arc::new(values),
+            )
+        };
+        let offsets = OffsetBuffer::from_lengths(lists.iter().map(|xs| xs.map_or(0, <[U]>::len)));
+        let nulls = if lists.iter().all(Option::is_some) {
+            None
+        } else {
+            Some(NullBuffer::from(
+                lists.iter().map(Option::is_some).collect::<Vec<_>>(),
+            ))
+        };
+        let array = ListArray::new(Arc::new(item), offsets, values, nulls);
+
+        let data_type = array.data_type().clone();
+        self.push::<C, _>(name, data_type, Arc::new(array));
+    }
+}
+
+fn dict_type() -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+}
+
+/// Writer of entries to a Parquet file, in row groups of a fixed number of entries.
+pub struct ParquetWriter<W: Write + Send, T> {
+    inner: ArrowWriter<W>,
+    buf: Vec<T>,
+    row_group_size: usize,
+    count: u64,
+}
+
+impl<W: Write + Send, T: ArrowEntry> ParquetWriter<W, T> {
+    /// Creates a writer compressing pages with Snappy.
+    pub fn new(inner: W) -> Result<ParquetWriter<W, T>> {
+        let props = WriterProperties::builder().set_compression(Compression::SNAPPY);
+        ParquetWriter::with_properties(inner, props)
+    }
+
+    /// Creates a writer with the given properties, except for the row group size.
+    pub fn with_properties(
+        inner: W,
+        props: WriterPropertiesBuilder,
+    ) -> Result<ParquetWriter<W, T>> {
+        // Row groups are cut by `flush`, so the limit of the properties must not split them.
+        let props = props.set_max_row_group_size(usize::MAX).build();
+        let inner = ArrowWriter::try_new(inner, T::schema(), Some(props))?;
+        Ok(ParquetWriter {
+            inner,
+            buf: Vec::new(),
+            row_group_size: DEFAULT_ROW_GROUP_SIZE,
+            count: 0,
+        })
+    }
+
+    /// Sets the number of entries per row group.
+    pub fn row_group_size(mut self, row_group_size: usize) -> ParquetWriter<W, T> {
+        self.row_group_size = row_group_size.max(1);
+        self
+    }
+
+    pub fn write(&mut self, entry: T) -> Result<()> {
+        self.buf.push(entry);
+        self.count += 1;
+        if self.buf.len() >= self.row_group_size {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    /// Number of entries written.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    fn flush_row_group(&mut self) -> Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let batch = T::to_record_batch(&self.buf)?;
+        self.inner.write(&batch)?;
+        self.inner.flush()?;
+        self.buf.clear();
+        Ok(())
+    }
+
+    /// Writes the last row group and the footer.
+    pub fn finish(mut self) -> Result<W> {
+        self.flush_row_group()?;
+        let mut inner = self.inner.into_inner()?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<T: ArrowEntry> ParquetWriter<BufWriter<File>, T> {
+    /// Creates a Parquet file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<ParquetWriter<BufWriter<File>, T>> {
+        let path = path.as_ref();
+        let f = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        ParquetWriter::new(BufWriter::new(f))
+    }
+}
+
+// Field Type
+
+fn coords(cols: &mut Columns<Coords>) {
+    cols.f32("x", |c| c.x);
+    cols.f32("y", |c| c.y);
+    cols.f32("z", |c| c.z);
+}
+
+fn controlling_faction<'a>(cols: &mut Columns<'a, ControllingFaction>) {
+    cols.u64("id", |x| x.id);
+    cols.dict("allegiance", |x| x.allegiance.as_ref());
+    cols.dict("government", |x| x.government.as_ref());
+    cols.bool("is_player", |x| x.is_player);
+    cols.str("name", |x| x.name.as_deref());
+}
+
+fn station_body<'a>(cols: &mut Columns<'a, StationBody>) {
+    cols.u64("id", |x| x.id);
+    cols.f32("latitude", |x| x.latitude);
+    cols.f32("longitude", |x| x.longitude);
+    cols.str("name", |x| x.name.as_str());
+}
+
+fn update_time<'a>(cols: &mut Columns<'a, UpdateTime>) {
+    cols.timestamp("information", |x| x.information);
+    cols.timestamp("market", |x| x.market);
+    cols.timestamp("outfitting", |x| x.outfitting);
+    cols.timestamp("shipyard", |x| x.shipyard);
+}
+
+fn belt<'a>(cols: &mut Columns<'a, Belt>) {
+    cols.f32("inner_radius", |x| x.inner_radius);
+    cols.f32("mass", |x| x.mass);
+    cols.str("name", |x| x.name.as_str());
+    cols.f32("outer_radius", |x| x.outer_radius);
+    cols.dict("type", |x| x.typ.as_ref());
+}
+
+fn ring<'a>(cols: &mut Columns<'a, Ring>) {
+    cols.f32("inner_radius", |x| x.inner_radius);
+    cols.f32("mass", |x| x.mass);
+    cols.str("name", |x| x.name.as_str());
+    cols.f32("outer_radius", |x| x.outer_radius);
+    cols.dict("type", |x| x.typ.as_ref());
+}
+
+fn parent<'a>(cols: &mut Columns<'a, Parent>) {
+    cols.dict("type", |x| match x {
+        Parent::Null(_) => "Null",
+        Parent::Planet(_) => "Planet",
+        Parent::Star(_) => "Star",
+    });
+    cols.u64("body_id", |x| match x {
+        Parent::Null(id) | Parent::Planet(id) | Parent::Star(id) => *id,
+    });
+}
+
+fn pending_state<'a>(cols: &mut Columns<'a, PendingState>) {
+    cols.dict("state", |x| &x.state);
+    cols.u64("trend", |x| u64::from(x.trend));
+}
+
+fn recovering_state<'a>(cols: &mut Columns<'a, RecoveringState>) {
+    cols.dict("state", |x| &x.state);
+    cols.u64("trend", |x| u64::from(x.trend));
+}
+
+// Main Type
+
+impl ArrowEntry for SystemWithCoordinates {
+    fn columns<'a>(cols: &mut Columns<'a, Self>) {
+        cols.u64("id", |x| x.id);
+        cols.flatten("coords", |x| &x.coords, coords);
+        cols.u64("id64", |x| x.id64);
+        cols.str("name", |x| x.name.as_str());
+        cols.timestamp("date", |x| x.date);
+    }
+}
+
+impl ArrowEntry for PowerPlay {
+    fn columns<'a>(cols: &mut Columns<'a, Self>) {
+        cols.u64("id", |x| x.id);
+        cols.dict("allegiance", |x| x.allegiance.as_ref());
+        cols.flatten("coords", |x| &x.coords, coords);
+        cols.dict("government", |x| x.government.as_ref());
+        cols.u64("id64", |x| x.id64);
+        cols.str("name", |x| x.name.as_str());
+        cols.dict("power", |x| &x.power);
+        cols.dict("power_state", |x| &x.power_state);
+        cols.dict("state", |x| x.state.as_ref());
+        cols.timestamp("date", |x| x.date);
+    }
+}
+
+impl ArrowEntry for Station {
+    fn columns<'a>(cols: &mut Columns<'a, Self>) {
+        cols.u64("id", |x| x.id);
+        cols.dict("allegiance", |x| x.allegiance.as_ref());
+        cols.flatten("body", |x| x.body.as_ref(), station_body);
+        cols.list(
+            "commodities",
+            |x| x.commodities.as_deref(),
+            |cols: &mut Columns<Commodity>| {
+                cols.str("id", |x| x.id.as_deref());
+                cols.str("name", |x| x.name.as_str());
+                cols.u64("buy_price", |x| x.buy_price);
+                cols.u64("demand", |x| x.demand);
+                cols.u64("sell_price", |x| x.sell_price);
+                cols.u64("stock", |x| x.stock);
+                cols.u64("stock_bracket", |x| x.stock_bracket);
+            },
+        );
+        cols.flatten(
+            "controlling_faction",
+            |x| x.controlling_faction.as_ref(),
+            controlling_faction,
+        );
+        cols.f32("distance_to_arrival", |x| x.distance_to_arrival);
+        cols.dict("economy", |x| x.economy.as_ref());
+        cols.dict("government", |x| x.government.as_ref());
+        cols.bool("have_market", |x| x.have_market);
+        cols.bool("have_outfitting", |x| x.have_outfitting);
+        cols.bool("have_shipyard", |x| x.have_shipyard);
+        cols.u64("market_id", |x| x.market_id);
+        cols.str("name", |x| x.name.as_str());
+        cols.dict_list("other_services", |x| x.other_services.as_slice());
+        cols.list(
+            "outfitting",
+            |x| x.outfitting.as_deref(),
+            |cols: &mut Columns<Outfitting>| {
+                cols.str("id", |x| x.id.as_deref());
+                cols.str("name", |x| x.name.as_str());
+            },
+        );
+        cols.dict("second_economy", |x| x.second_economy.as_ref());
+        cols.list(
+            "ships",
+            |x| x.ships.as_deref(),
+            |cols: &mut Columns<Ship>| {
+                cols.u64("id", |x| x.id);
+                cols.dict("name", |x| &x.name);
+            },
+        );
+        cols.u64("system_id", |x| x.system_id);
+        cols.u64("system_id64", |x| x.system_id64);
+        cols.str("system_name", |x| x.system_name.as_deref());
+        cols.dict("type", |x| x.typ.as_ref());
+        cols.flatten("update_time", |x| &x.update_time, update_time);
+    }
+}
+
+impl ArrowEntry for Planet {
+    fn columns<'a>(cols: &mut Columns<'a, Self>) {
+        cols.u64("id", |x| x.id);
+        cols.f32("arg_of_periapsis", |x| x.arg_of_periapsis);
+        for key in AtmosphereCompositionKey::iter() {
+            let name = format!(
+                "atmosphere_composition_{}",
+                snake_case(&format!("{:?}", key))
+            );
+            cols.f32(&name, move |x| {
+                x.atmosphere_composition.as_ref().and_then(|c| c.get(key))
+            });
+        }
+        cols.dict("atmosphere_type", |x| x.atmosphere_type.as_ref());
+        cols.f32("axial_tilt", |x| x.axial_tilt);
+        cols.list("belts", |x| x.belts.as_deref(), belt);
+        cols.u64("body_id", |x| x.body_id);
+        cols.str("discovery_commander", |x| {
+            x.discovery.as_ref().map(|d| d.commander.as_str())
+        });
+        cols.timestamp("discovery_date", |x| x.discovery.as_ref().map(|d| d.date));
+        cols.u64("distance_to_arrival", |x| x.distance_to_arrival);
+        cols.f32("earth_masses", |x| x.earth_masses);
+        cols.f32("gravity", |x| x.gravity);
+        cols.u64("id64", |x| x.id64);
+        cols.bool("is_landable", |x| x.is_landable);
+        for key in MaterialsKey::iter() {
+            let name = format!("materials_{}", snake_case(&format!("{:?}", key)));
+            cols.f32(&name, move |x| {
+                x.materials.as_ref().and_then(|m| m.get(key))
+            });
+        }
+        cols.str("name", |x| x.name.as_str());
+        cols.f32("orbital_eccentricity", |x| x.orbital_eccentricity);
+        cols.f32("orbital_inclination", |x| x.orbital_inclination);
+        cols.f32("orbital_period", |x| x.orbital_period);
+        cols.list("parents", |x| x.parents.as_deref(), parent);
+        cols.f32("radius", |x| x.radius);
+        cols.dict("reserve_level", |x| x.reserve_level.as_ref());
+        cols.list("rings", |x| x.rings.as_deref(), ring);
+        cols.f32("rotational_period", |x| x.rotational_period);
+        cols.bool("rotational_period_tidally_locked", |x| {
+            x.rotational_period_tidally_locked
+        });
+        cols.f32("semi_major_axis", |x| x.semi_major_axis);
+        cols.f32("solid_composition_ice", |x| {
+            x.solid_composition.as_ref().map(|c| c.ice)
+        });
+        cols.f32("solid_composition_metal", |x| {
+            x.solid_composition.as_ref().map(|c| c.metal)
+        });
+        cols.f32("solid_composition_rock", |x| {
+            x.solid_composition.as_ref().map(|c| c.rock)
+        });
+        cols.dict("sub_type", |x| &x.sub_type);
+        cols.f32("surface_pressure", |x| x.surface_pressure);
+        cols.u64("surface_temperature", |x| x.surface_temperature);
+        cols.u64("system_id", |x| x.system_id);
+        cols.u64("system_id64", |x| x.system_id64);
+        cols.str("system_name", |x| x.system_name.as_deref());
+        cols.dict("terraforming_state", |x| x.terraforming_state.as_ref());
+        cols.dict("volcanism_type", |x| x.volcanism_type.as_ref());
+        cols.timestamp("update_time", |x| x.update_time);
+    }
+}
+
+impl ArrowEntry for Star {
+    fn columns<'a>(cols: &mut Columns<'a, Self>) {
+        cols.u64("id", |x| x.id);
+        cols.f32("absolute_magnitude", |x| x.absolute_magnitude);
+        cols.u64("age", |x| x.age);
+        cols.f32("arg_of_periapsis", |x| x.arg_of_periapsis);
+        cols.f32("axial_tilt", |x| x.axial_tilt);
+        cols.list("belts", |x| x.belts.as_deref(), belt);
+        cols.u64("body_id", |x| x.body_id);
+        cols.str("discovery_commander", |x| {
+            x.discovery.as_ref().map(|d| d.commander.as_str())
+        });
+        cols.timestamp("discovery_date", |x| x.discovery.as_ref().map(|d| d.date));
+        cols.u64("distance_to_arrival", |x| x.distance_to_arrival);
+        cols.u64("id64", |x| x.id64);
+        cols.bool("is_main_star", |x| x.is_main_star);
+        cols.bool("is_scoopable", |x| x.is_scoopable);
+        cols.dict("luminosity", |x| x.luminosity.as_ref());
+        cols.str("name", |x| x.name.as_str());
+        cols.f32("orbital_eccentricity", |x| x.orbital_eccentricity);
+        cols.f32("orbital_inclination", |x| x.orbital_inclination);
+        cols.f32("orbital_period", |x| x.orbital_period);
+        cols.list("parents", |x| x.parents.as_deref(), parent);
+        cols.dict("reserve_level", |x| x.reserve_level.as_ref());
+        cols.list("rings", |x| x.rings.as_deref(), ring);
+        cols.f32("rotational_period", |x| x.rotational_period);
+        cols.bool("rotational_period_tidally_locked", |x| {
+            x.rotational_period_tidally_locked
+        });
+        cols.f32("semi_major_axis", |x| x.semi_major_axis);
+        cols.f32("solar_masses", |x| x.solar_masses);
+        cols.f32("solar_radius", |x| x.solar_radius);
+        cols.dict("spectral_class", |x| x.spectral_class.as_ref());
+        cols.dict("sub_type", |x| &x.sub_type);
+        cols.u64("surface_temperature", |x| x.surface_temperature);
+        cols.u64("system_id", |x| x.system_id);
+        cols.u64("system_id64", |x| x.system_id64);
+        cols.str("system_name", |x| x.system_name.as_deref());
+        cols.timestamp("update_time", |x| x.update_time);
+    }
+}
+
+/// Bodies and stations are summarized; the `Planet`, `Star` and `Station` exports have the details.
+impl ArrowEntry for SystemPopulated {
+    fn columns<'a>(cols: &mut Columns<'a, Self>) {
+        cols.u64("id", |x| x.id);
+        cols.dict("allegiance", |x| x.allegiance.as_ref());
+        cols.list(
+            "bodies",
+            |x| x.bodies.as_slice(),
+            |cols: &mut Columns<Body>| {
+                use crate::model::body::BodyT;
+
+                cols.u64("id", |x| x.id());
+                cols.u64("id64", |x| x.id64());
+                cols.u64("body_id", |x| x.body_id());
+                cols.str("name", |x| x.name());
+                cols.dict("type", |x| match x {
+                    Body::Planet(_) => Some("Planet"),
+                    Body::Star(_) => Some("Star"),
+                    Body::Unknown(_) => None,
+                });
+                cols.dict("sub_type", |x| match x {
+                    Body::Planet(x) => Some(&x.sub_type as &dyn fmt::Display),
+                    Body::Star(x) => Some(&x.sub_type as &dyn fmt::Display),
+                    Body::Unknown(_) => None,
+                });
+                cols.u64("distance_to_arrival", |x| x.distance_to_arrival());
+            },
+        );
+        cols.flatten(
+            "controlling_faction",
+            |x| &x.controlling_faction,
+            controlling_faction,
+        );
+        cols.flatten("coords", |x| &x.coords, coords);
+        cols.dict("economy", |x| x.economy.as_ref());
+        cols.list(
+            "factions",
+            |x| x.factions.as_deref(),
+            |cols: &mut Columns<FactionInPopulated>| {
+                cols.u64("id", |x| x.id);
+                cols.list(
+                    "active_states",
+                    |x| x.active_states.as_slice(),
+                    |cols| cols.dict("state", |x| &x.state),
+                );
+                cols.dict("allegiance", |x| x.allegiance.as_ref());
+                cols.dict("government", |x| x.government.as_ref());
+                cols.dict("happiness", |x| x.happiness.as_ref());
+                cols.f32("influence", |x| x.influence);
+                cols.bool("is_player", |x| x.is_player);
+                cols.str("name", |x| x.name.as_str());
+                cols.list(
+                    "pending_states",
+                    |x| x.pending_states.as_slice(),
+                    pending_state,
+                );
+                cols.list(
+                    "recovering_states",
+                    |x| x.recovering_states.as_slice(),
+                    recovering_state,
+                );
+                cols.dict("state", |x| x.state.as_ref());
+                cols.timestamp("last_update", |x| x.last_update);
+            },
+        );
+        cols.dict("government", |x| x.government.as_ref());
+        cols.u64("id64", |x| x.id64);
+        cols.str("name", |x| x.name.as_str());
+        cols.u64("population", |x| x.population);
+        cols.dict("security", |x| &x.security);
+        cols.dict("state", |x| x.state.as_ref());
+        cols.list(
+            "stations",
+            |x| x.stations.as_slice(),
+            |cols: &mut Columns<StationInPopulated>| {
+                cols.u64("id", |x| x.id);
+                cols.dict("allegiance", |x| x.allegiance.as_ref());
+                cols.flatten("body", |x| x.body.as_ref(), station_body);
+                cols.flatten(
+                    "controlling_faction",
+                    |x| x.controlling_faction.as_ref(),
+                    controlling_faction,
+                );
+                cols.f32("distance_to_arrival", |x| x.distance_to_arrival);
+                cols.dict("economy", |x| x.economy.as_ref());
+                cols.dict("government", |x| x.government.as_ref());
+                cols.bool("have_market", |x| x.have_market);
+                cols.bool("have_outfitting", |x| x.have_outfitting);
+                cols.bool("have_shipyard", |x| x.have_shipyard);
+                cols.u64("market_id", |x| x.market_id);
+                cols.str("name", |x| x.name.as_str());
+                cols.dict_list("other_services", |x| x.other_services.as_slice());
+                cols.dict("second_economy", |x| x.second_economy.as_ref());
+                cols.dict("type", |x| x.st_type.as_ref());
+                cols.flatten("update_time", |x| &x.update_time, update_time);
+            },
+        );
+        cols.timestamp("date", |x| x.date);
+    }
+}
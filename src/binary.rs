@@ -0,0 +1,162 @@
+use std::fmt;
+use std::io::{BufRead, Read, Write};
+use std::marker::PhantomData;
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::model::RootEntry;
+
+/// Compact binary encoding of entries, for caching parsed dumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Bincode,
+    MessagePack,
+}
+
+impl Encoding {
+    /// Name of the cargo feature required to use this encoding.
+    pub fn feature(self) -> &'static str {
+        match self {
+            Encoding::Bincode => "bincode",
+            Encoding::MessagePack => "msgpack",
+        }
+    }
+
+    pub fn is_supported(self) -> bool {
+        match self {
+            Encoding::Bincode => cfg!(feature = "bincode"),
+            Encoding::MessagePack => cfg!(feature = "msgpack"),
+        }
+    }
+
+    /// Writes `value` to `w`.
+    #[allow(unused_variables)]
+    pub fn encode<T: Serialize, W: Write>(self, w: &mut W, value: &T) -> Result<()> {
+        check_supported(self)?;
+        match self {
+            #[cfg(feature = "bincode")]
+            Encoding::Bincode => Ok(bincode::serialize_into(w, value)?),
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => Ok(rmp_serde::encode::write(w, value)?),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("{} is not supported", self),
+        }
+    }
+
+    /// Reads a value written by [`encode`](Encoding::encode) from `r`.
+    #[allow(unused_variables)]
+    pub fn decode<T: DeserializeOwned, R: Read>(self, r: &mut R) -> Result<T> {
+        check_supported(self)?;
+        match self {
+            #[cfg(feature = "bincode")]
+            Encoding::Bincode => Ok(bincode::deserialize_from(r)?),
+            #[cfg(feature = "msgpack")]
+            Encoding::MessagePack => Ok(rmp_serde::decode::from_read(r)?),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("{} is not supported", self),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Encoding::Bincode => "bincode",
+            Encoding::MessagePack => "MessagePack",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+pub(crate) fn check_supported(encoding: Encoding) -> Result<()> {
+    if !encoding.is_supported() {
+        bail!(
+            "{} encoding requires the `{}` feature",
+            encoding,
+            encoding.feature()
+        );
+    }
+    Ok(())
+}
+
+/// Writer of entries encoded one after another.
+pub struct BinaryWriter<W, T> {
+    encoding: Encoding,
+    inner: W,
+    count: u64,
+    _entry: PhantomData<fn(T)>,
+}
+
+impl<W: Write, T: RootEntry> BinaryWriter<W, T> {
+    pub fn new(encoding: Encoding, inner: W) -> Result<BinaryWriter<W, T>> {
+        check_supported(encoding)?;
+        Ok(BinaryWriter {
+            encoding,
+            inner,
+            count: 0,
+            _entry: PhantomData,
+        })
+    }
+
+    pub fn write(&mut self, entry: &T) -> Result<()> {
+        self.encoding
+            .encode(&mut self.inner, entry)
+            .with_context(|| format!("encoding {} entry", T::type_name()))?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Number of entries written.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Flushes the output.
+    pub fn finish(mut self) -> Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Iterator over the entries written by a [`BinaryWriter`].
+pub struct BinaryReader<R, T> {
+    encoding: Encoding,
+    inner: R,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: RootEntry> BinaryReader<R, T> {
+    pub fn new(encoding: Encoding, inner: R) -> Result<BinaryReader<R, T>> {
+        check_supported(encoding)?;
+        Ok(BinaryReader {
+            encoding,
+            inner,
+            _entry: PhantomData,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn read_entry(&mut self) -> Result<Option<T>> {
+        if self.inner.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let entry = self
+            .encoding
+            .decode(&mut self.inner)
+            .with_context(|| format!("decoding {} entry", T::type_name()))?;
+        Ok(Some(entry))
+    }
+}
+
+impl<R: BufRead, T: RootEntry> Iterator for BinaryReader<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        self.read_entry().transpose()
+    }
+}
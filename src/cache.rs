@@ -0,0 +1,287 @@
+use std::error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Error, Result};
+use type_hash::TypeHash;
+
+use crate::binary::{BinaryReader, BinaryWriter, Encoding};
+use crate::model::RootEntry;
+
+const MAGIC: &[u8; 8] = b"EDSMDMC\0";
+
+/// Version of this crate, written to cache headers.
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Header at the start of a cache file, identifying the model the entries were encoded with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheHeader {
+    pub encoding: Encoding,
+    /// [`TypeHash`] of the entry type.
+    pub type_hash: u64,
+    /// Version of this crate.
+    pub version: String,
+}
+
+impl CacheHeader {
+    /// Header of a cache of `T` written by this build.
+    pub fn current<T: TypeHash>(encoding: Encoding) -> CacheHeader {
+        CacheHeader {
+            encoding,
+            type_hash: T::type_hash(),
+            version: CRATE_VERSION.to_owned(),
+        }
+    }
+
+    /// Whether entries written with `self` can be read as entries with `other`.
+    pub fn is_compatible(&self, other: &CacheHeader) -> bool {
+        self.type_hash == other.type_hash && self.version == other.version
+    }
+
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<()> {
+        let version = self.version.as_bytes();
+        if version.len() > u8::MAX as usize {
+            bail!("version too long: {}", self.version);
+        }
+        w.write_all(MAGIC)?;
+        w.write_all(&[encoding_tag(self.encoding)])?;
+        w.write_all(&self.type_hash.to_le_bytes())?;
+        w.write_all(&[version.len() as u8])?;
+        w.write_all(version)?;
+        Ok(())
+    }
+
+    /// Reads a header, failing with [`InvalidCacheHeader`] if `r` does not start with one.
+    pub fn read_from<R: Read>(r: &mut R) -> Result<CacheHeader> {
+        let mut magic = [0; 8];
+        read_header_bytes(r, &mut magic)?;
+        if &magic != MAGIC {
+            return Err(InvalidCacheHeader("not a cache file".to_owned()).into());
+        }
+        let mut tag = [0; 1];
+        read_header_bytes(r, &mut tag)?;
+        let encoding = match tag[0] {
+            0 => Encoding::Bincode,
+            1 => Encoding::MessagePack,
+            x => return Err(InvalidCacheHeader(format!("unknown cache encoding {}", x)).into()),
+        };
+        let mut type_hash = [0; 8];
+        read_header_bytes(r, &mut type_hash)?;
+        let mut len = [0; 1];
+        read_header_bytes(r, &mut len)?;
+        let mut version = vec![0; len[0] as usize];
+        read_header_bytes(r, &mut version)?;
+        let version = String::from_utf8(version)
+            .map_err(|_| InvalidCacheHeader("cache version is not UTF-8".to_owned()))?;
+        Ok(CacheHeader {
+            encoding,
+            type_hash: u64::from_le_bytes(type_hash),
+            version,
+        })
+    }
+}
+
+/// Fills `buf`, failing with [`InvalidCacheHeader`] if the input ends first.
+fn read_header_bytes<R: Read>(r: &mut R, buf: &mut [u8]) -> Result<()> {
+    match r.read_exact(buf) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+            Err(InvalidCacheHeader("truncated header".to_owned()).into())
+        }
+        Err(e) => Err(Error::new(e).context("reading cache header")),
+    }
+}
+
+fn encoding_tag(encoding: Encoding) -> u8 {
+    match encoding {
+        Encoding::Bincode => 0,
+        Encoding::MessagePack => 1,
+    }
+}
+
+/// Cache written by a different version of the crate or of the model.
+///
+/// Returned inside the `anyhow::Error` of [`CacheReader::new`],
+/// and can be recovered with `anyhow::Error::downcast_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncompatibleCache {
+    /// [`RootEntry::type_name`] of the expected entries.
+    pub type_name: &'static str,
+    pub expected: CacheHeader,
+    pub found: CacheHeader,
+}
+
+impl fmt::Display for IncompatibleCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "incompatible {} cache: written by version {} with type hash {:016x}, expected version {} with type hash {:016x}",
+            self.type_name,
+            self.found.version,
+            self.found.type_hash,
+            self.expected.version,
+            self.expected.type_hash
+        )
+    }
+}
+
+impl error::Error for IncompatibleCache {}
+
+/// File that does not start with a [`CacheHeader`], such as a truncated cache.
+///
+/// Returned inside the `anyhow::Error` of [`CacheHeader::read_from`] and [`CacheReader::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCacheHeader(pub String);
+
+impl fmt::Display for InvalidCacheHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid cache header: {}", self.0)
+    }
+}
+
+impl error::Error for InvalidCacheHeader {}
+
+/// Writer of a cache file: a [`CacheHeader`] followed by the entries of a [`BinaryWriter`].
+pub struct CacheWriter<W: Write, T> {
+    inner: BinaryWriter<W, T>,
+}
+
+impl<W: Write, T: RootEntry + TypeHash> CacheWriter<W, T> {
+    pub fn new(encoding: Encoding, mut inner: W) -> Result<CacheWriter<W, T>> {
+        crate::binary::check_supported(encoding)?;
+        CacheHeader::current::<T>(encoding).write_to(&mut inner)?;
+        Ok(CacheWriter {
+            inner: BinaryWriter::new(encoding, inner)?,
+        })
+    }
+
+    pub fn write(&mut self, entry: &T) -> Result<()> {
+        self.inner.write(entry)
+    }
+
+    /// Number of entries written.
+    pub fn count(&self) -> u64 {
+        self.inner.count()
+    }
+
+    /// Flushes the output.
+    pub fn finish(self) -> Result<W> {
+        self.inner.finish()
+    }
+}
+
+impl<T: RootEntry + TypeHash> CacheWriter<BufWriter<File>, T> {
+    pub fn create<P: AsRef<Path>>(
+        encoding: Encoding,
+        path: P,
+    ) -> Result<CacheWriter<BufWriter<File>, T>> {
+        let path = path.as_ref();
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        CacheWriter::new(encoding, BufWriter::new(file))
+    }
+}
+
+/// Iterator over the entries of a cache file written by a [`CacheWriter`].
+pub struct CacheReader<R, T> {
+    header: CacheHeader,
+    inner: BinaryReader<R, T>,
+}
+
+impl<R: BufRead, T: RootEntry + TypeHash> CacheReader<R, T> {
+    /// Reads the header, failing with [`IncompatibleCache`] if the cache
+    /// was not written by this version of the crate and model.
+    pub fn new(mut inner: R) -> Result<CacheReader<R, T>> {
+        let header = CacheHeader::read_from(&mut inner)?;
+        let expected = CacheHeader::current::<T>(header.encoding);
+        if !header.is_compatible(&expected) {
+            return Err(IncompatibleCache {
+                type_name: T::type_name(),
+                expected,
+                found: header,
+            }
+            .into());
+        }
+        Ok(CacheReader {
+            inner: BinaryReader::new(header.encoding, inner)?,
+            header,
+        })
+    }
+
+    pub fn header(&self) -> &CacheHeader {
+        &self.header
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<T: RootEntry + TypeHash> CacheReader<BufReader<File>, T> {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<CacheReader<BufReader<File>, T>> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+        CacheReader::new(BufReader::new(file))
+            .with_context(|| format!("reading {}", path.display()))
+    }
+
+    /// Opens the cache at `path`, first writing it with the entries of `rebuild`
+    /// if it does not exist, is incompatible or has an invalid header.
+    ///
+    /// The cache is written to a temporary file next to `path`, which replaces it once complete
+    /// and is removed if the rebuild fails.
+    pub fn open_or_rebuild<P, F, I>(
+        encoding: Encoding,
+        path: P,
+        rebuild: F,
+    ) -> Result<CacheReader<BufReader<File>, T>>
+    where
+        P: AsRef<Path>,
+        F: FnOnce() -> Result<I>,
+        I: IntoIterator<Item = Result<T>>,
+    {
+        let path = path.as_ref();
+        match CacheReader::open(path) {
+            Ok(reader) => return Ok(reader),
+            Err(e) if is_stale(&e) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = Path::new(&tmp);
+        let written = CacheWriter::create(encoding, tmp).and_then(|mut w| {
+            for entry in rebuild()? {
+                w.write(&entry?)?;
+            }
+            w.finish()
+        });
+        if let Err(e) = written {
+            let _ = fs::remove_file(tmp);
+            return Err(e);
+        }
+        fs::rename(tmp, path)
+            .with_context(|| format!("renaming {} to {}", tmp.display(), path.display()))?;
+        CacheReader::open(path)
+    }
+}
+
+/// Whether `e` is caused by a missing or incompatible cache, or one with an invalid header.
+fn is_stale(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause.is::<IncompatibleCache>()
+            || cause.is::<InvalidCacheHeader>()
+            || cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == ErrorKind::NotFound)
+    })
+}
+
+impl<R: BufRead, T: RootEntry + TypeHash> Iterator for CacheReader<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        self.inner.next()
+    }
+}
@@ -0,0 +1,339 @@
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+/// Compression format of a dump file, detected from its magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    None,
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    pub fn detect(head: &[u8]) -> Compression {
+        if head.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if head.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if head.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Name of the cargo feature required to decode this format.
+    pub fn feature(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gzip"),
+            Compression::Bzip2 => Some("bzip2"),
+            Compression::Zstd => Some("zstd"),
+        }
+    }
+
+    pub fn is_supported(self) -> bool {
+        match self {
+            Compression::None => true,
+            Compression::Gzip => cfg!(feature = "gzip"),
+            Compression::Bzip2 => cfg!(feature = "bzip2"),
+            Compression::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Compression::None => "uncompressed",
+            Compression::Gzip => "gzip",
+            Compression::Bzip2 => "bzip2",
+            Compression::Zstd => "zstd",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Compressed stream ended before the end of its compressed data.
+#[derive(Debug)]
+pub struct TruncatedStream {
+    pub compression: Compression,
+    source: io::Error,
+}
+
+impl fmt::Display for TruncatedStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "truncated {} stream", self.compression)
+    }
+}
+
+impl error::Error for TruncatedStream {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Opens a dump file, decompressing it if needed.
+pub fn open<P: AsRef<Path>>(path: P) -> Result<Box<dyn BufRead + Send>> {
+    let path = path.as_ref();
+    let f = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    decompress(BufReader::new(f)).with_context(|| format!("opening {}", path.display()))
+}
+
+/// Wraps `r` with a decoder for the compression format detected from its first bytes.
+pub fn decompress<R: 'static + BufRead + Send>(mut r: R) -> Result<Box<dyn BufRead + Send>> {
+    let compression = Compression::detect(r.fill_buf().context("reading magic bytes")?);
+    check_supported(compression)?;
+
+    match compression {
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(truncation_checked(
+            compression,
+            flate2::bufread::MultiGzDecoder::new(r),
+        )),
+        #[cfg(feature = "bzip2")]
+        Compression::Bzip2 => Ok(truncation_checked(
+            compression,
+            bzip2::bufread::MultiBzDecoder::new(r),
+        )),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(truncation_checked(
+            compression,
+            zstd::stream::read::Decoder::with_buffer(r).context("initializing zstd decoder")?,
+        )),
+        _ => Ok(Box::new(r)),
+    }
+}
+
+pub(crate) fn check_supported(compression: Compression) -> Result<()> {
+    if !compression.is_supported() {
+        bail!(
+            "{} compressed input requires the `{}` feature",
+            compression,
+            compression.feature().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+fn truncation_checked<R: 'static + io::Read + Send>(
+    compression: Compression,
+    r: R,
+) -> Box<dyn BufRead + Send> {
+    Box::new(BufReader::new(TruncationCheck {
+        compression,
+        inner: r,
+    }))
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+struct TruncationCheck<R> {
+    compression: Compression,
+    inner: R,
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R: io::Read> io::Read for TruncationCheck<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf).map_err(|e| {
+            if e.kind() == io::ErrorKind::UnexpectedEof {
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    TruncatedStream {
+                        compression: self.compression,
+                        source: e,
+                    },
+                )
+            } else {
+                e
+            }
+        })
+    }
+}
+
+/// Converts an I/O error, surfacing [`TruncatedStream`] as the error itself.
+pub(crate) fn io_error(e: io::Error) -> anyhow::Error {
+    if e.get_ref()
+        .is_some_and(|inner| inner.is::<TruncatedStream>())
+    {
+        if let Some(Ok(truncated)) = e
+            .into_inner()
+            .map(|inner| inner.downcast::<TruncatedStream>())
+        {
+            return anyhow::Error::new(*truncated);
+        }
+        unreachable!("inner error is TruncatedStream");
+    }
+    anyhow::Error::new(e)
+}
+
+/// Location in a compressed file where decoding can start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SeekPoint {
+    /// Offset of the start of a gzip member, bzip2 stream or zstd frame.
+    pub compressed: u64,
+    /// Offset in the decompressed data.
+    pub uncompressed: u64,
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+/// `BufRead` counting the consumed bytes.
+pub(crate) struct Counting<R> {
+    inner: R,
+    count: u64,
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R> Counting<R> {
+    pub(crate) fn new(inner: R, count: u64) -> Counting<R> {
+        Counting { inner, count }
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R: BufRead> io::Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R: BufRead> BufRead for Counting<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.count += amt as u64;
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+/// Decoder of concatenated compressed members, recording a [`SeekPoint`] at the start of each.
+pub(crate) struct Members<R> {
+    compression: Compression,
+    inner: Option<Counting<R>>,
+    decoder: Option<Member<R>>,
+    uncompressed: u64,
+    seek_points: Vec<SeekPoint>,
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+enum Member<R> {
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::bufread::GzDecoder<Counting<R>>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(bzip2::bufread::BzDecoder<Counting<R>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, Counting<R>>),
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R: BufRead> Members<R> {
+    /// Starts decoding `inner`, which is at offset `point.compressed` of the file.
+    pub(crate) fn new(compression: Compression, inner: R, point: SeekPoint) -> Result<Members<R>> {
+        check_supported(compression)?;
+        if compression == Compression::None {
+            bail!("input is not compressed");
+        }
+        Ok(Members {
+            compression,
+            inner: Some(Counting::new(inner, point.compressed)),
+            decoder: None,
+            uncompressed: point.uncompressed,
+            seek_points: Vec::new(),
+        })
+    }
+
+    pub(crate) fn seek_points(&self) -> &[SeekPoint] {
+        &self.seek_points
+    }
+
+    fn start_member(&mut self, inner: Counting<R>) -> io::Result<Member<R>> {
+        match self.compression {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Member::Gzip(flate2::bufread::GzDecoder::new(inner))),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => Ok(Member::Bzip2(bzip2::bufread::BzDecoder::new(inner))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(Member::Zstd(
+                zstd::stream::read::Decoder::with_buffer(inner)?.single_frame(),
+            )),
+            _ => unreachable!("checked in Members::new"),
+        }
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R: BufRead> Member<R> {
+    fn into_inner(self) -> Counting<R> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Member::Gzip(x) => x.into_inner(),
+            #[cfg(feature = "bzip2")]
+            Member::Bzip2(x) => x.into_inner(),
+            #[cfg(feature = "zstd")]
+            Member::Zstd(x) => x.finish(),
+        }
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R: BufRead> io::Read for Member<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Member::Gzip(x) => x.read(buf),
+            #[cfg(feature = "bzip2")]
+            Member::Bzip2(x) => x.read(buf),
+            #[cfg(feature = "zstd")]
+            Member::Zstd(x) => x.read(buf),
+        }
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+impl<R: BufRead> io::Read for Members<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let decoder = match &mut self.decoder {
+                Some(decoder) => decoder,
+                None => {
+                    let mut inner = match self.inner.take() {
+                        Some(inner) => inner,
+                        None => return Ok(0),
+                    };
+                    if inner.fill_buf()?.is_empty() {
+                        self.inner = Some(inner);
+                        return Ok(0);
+                    }
+                    self.seek_points.push(SeekPoint {
+                        compressed: inner.count,
+                        uncompressed: self.uncompressed,
+                    });
+                    let decoder = self.start_member(inner)?;
+                    self.decoder.insert(decoder)
+                }
+            };
+
+            let n = decoder.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                self.uncompressed += n as u64;
+                return Ok(n);
+            }
+            if let Some(decoder) = self.decoder.take() {
+                self.inner = Some(decoder.into_inner());
+            }
+        }
+    }
+}
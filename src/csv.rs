@@ -0,0 +1,495 @@
+use std::fmt::{self, Display, Write as _};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use strum::IntoEnumIterator;
+
+use crate::model::bgs::ControllingFaction;
+use crate::model::body::{AtmosphereCompositionKey, Body, BodyT, MaterialsKey, Planet, Star};
+use crate::model::dec::date_format::FORMAT as DATE_FORMAT;
+use crate::model::powerplay::PowerPlay;
+use crate::model::station::{Station, StationBody, UpdateTime};
+use crate::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use crate::model::system_populated::SystemPopulated;
+use crate::model::util::snake_case;
+use crate::model::RootEntry;
+
+/// Entry having a defined set of CSV columns.
+///
+/// Nested values are flattened:
+///
+/// * structs such as `Coords` have one column per field, e.g. `coords_x`, `coords_y` and `coords_z`
+/// * `Materials` and `AtmosphereComposition` have one column per key, e.g. `materials_iron`
+/// * `parents` is written as `Star:3;Null:1`, and lists of enums such as `other_services` are joined by `;`
+/// * other lists, such as `rings`, are counted in a `_count` column, e.g. `rings_count`
+///
+/// Absent values are empty cells.
+pub trait CsvEntry: RootEntry {
+    fn columns() -> Vec<Column<Self>>;
+
+    fn column_names() -> Vec<String> {
+        Self::columns().into_iter().map(|c| c.name).collect()
+    }
+}
+
+type WriteCell<T> = Box<dyn Fn(&T, &mut String) -> fmt::Result + Send + Sync>;
+
+/// CSV column of entries of type `T`.
+pub struct Column<T> {
+    name: String,
+    value: WriteCell<T>,
+}
+
+impl<T> Column<T> {
+    /// Column of the `Display` form of the value returned by `f`, or empty for `None`.
+    pub fn new<N, V, F>(name: N, f: F) -> Column<T>
+    where
+        N: Into<String>,
+        V: Display,
+        F: Fn(&T) -> Option<V> + Send + Sync + 'static,
+    {
+        Column {
+            name: name.into(),
+            value: Box::new(move |x, out| match f(x) {
+                Some(v) => write!(out, "{}", v),
+                None => Ok(()),
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn write(&self, entry: &T, out: &mut String) -> fmt::Result {
+        (self.value)(entry, out)
+    }
+}
+
+/// Writer of entries as CSV with a header line.
+pub struct CsvWriter<W: Write, T> {
+    inner: csv::Writer<W>,
+    columns: Vec<Column<T>>,
+    cells: Vec<String>,
+    count: u64,
+}
+
+impl<W: Write, T: CsvEntry> CsvWriter<W, T> {
+    /// Creates a writer of all columns of `T`.
+    pub fn new(inner: W) -> Result<CsvWriter<W, T>> {
+        CsvWriter::with_columns_of(inner, T::columns())
+    }
+
+    /// Creates a writer of the named columns, in the given order.
+    pub fn with_columns<S: AsRef<str>>(inner: W, names: &[S]) -> Result<CsvWriter<W, T>> {
+        let mut all = T::columns().into_iter().map(Some).collect::<Vec<_>>();
+        let mut columns = Vec::with_capacity(names.len());
+        for name in names {
+            let name = name.as_ref();
+            match all
+                .iter_mut()
+                .find(|c| c.as_ref().map(Column::name) == Some(name))
+            {
+                Some(c) => columns.push(c.take().expect("column is found once")),
+                None => bail!("unknown or duplicated {} column: {}", T::type_name(), name),
+            }
+        }
+        CsvWriter::with_columns_of(inner, columns)
+    }
+
+    fn with_columns_of(inner: W, columns: Vec<Column<T>>) -> Result<CsvWriter<W, T>> {
+        let mut inner = csv::Writer::from_writer(inner);
+        inner.write_record(columns.iter().map(Column::name))?;
+        Ok(CsvWriter {
+            inner,
+            cells: vec![String::new(); columns.len()],
+            columns,
+            count: 0,
+        })
+    }
+
+    pub fn write(&mut self, entry: &T) -> Result<()> {
+        for (column, cell) in self.columns.iter().zip(&mut self.cells) {
+            cell.clear();
+            column
+                .write(entry, cell)
+                .with_context(|| format!("formatting {} column", column.name()))?;
+        }
+        self.inner.write_record(&self.cells)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Number of entries written.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Flushes the output.
+    pub fn finish(self) -> Result<W> {
+        let mut inner = self.inner.into_inner().map_err(|e| e.into_error())?;
+        inner.flush()?;
+        Ok(inner)
+    }
+}
+
+impl<T: CsvEntry> CsvWriter<BufWriter<File>, T> {
+    /// Creates a CSV file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<CsvWriter<BufWriter<File>, T>> {
+        let path = path.as_ref();
+        let f = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        CsvWriter::new(BufWriter::new(f))
+    }
+}
+
+/// Values joined by `;`.
+struct Joined<'a, T>(&'a [T]);
+
+impl<'a, T: Display> Display for Joined<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, x) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(";")?;
+            }
+            write!(f, "{}", x)?;
+        }
+        Ok(())
+    }
+}
+
+// Field Type
+
+fn controlling_faction<T>(
+    f: impl Fn(&T) -> Option<&ControllingFaction> + Copy + Send + Sync + 'static,
+) -> Vec<Column<T>> {
+    vec![
+        Column::new("controlling_faction_id", move |x| f(x)?.id),
+        Column::new("controlling_faction_allegiance", move |x| {
+            f(x)?.allegiance.clone()
+        }),
+        Column::new("controlling_faction_government", move |x| {
+            f(x)?.government.clone()
+        }),
+        Column::new("controlling_faction_is_player", move |x| f(x)?.is_player),
+        Column::new("controlling_faction_name", move |x| f(x)?.name.clone()),
+    ]
+}
+
+fn station_body<T>(
+    f: impl Fn(&T) -> Option<&StationBody> + Copy + Send + Sync + 'static,
+) -> Vec<Column<T>> {
+    vec![
+        Column::new("body_id", move |x| f(x).map(|b| b.id)),
+        Column::new("body_latitude", move |x| f(x)?.latitude),
+        Column::new("body_longitude", move |x| f(x)?.longitude),
+        Column::new("body_name", move |x| f(x).map(|b| b.name.clone())),
+    ]
+}
+
+fn update_time<T>(f: impl Fn(&T) -> &UpdateTime + Copy + Send + Sync + 'static) -> Vec<Column<T>> {
+    vec![
+        Column::new("update_time_information", move |x| {
+            Some(f(x).information.format(DATE_FORMAT))
+        }),
+        Column::new("update_time_market", move |x| {
+            f(x).market.map(|d| d.format(DATE_FORMAT))
+        }),
+        Column::new("update_time_outfitting", move |x| {
+            f(x).outfitting.map(|d| d.format(DATE_FORMAT))
+        }),
+        Column::new("update_time_shipyard", move |x| {
+            f(x).shipyard.map(|d| d.format(DATE_FORMAT))
+        }),
+    ]
+}
+
+fn planet<V: Display>(
+    name: &str,
+    f: impl Fn(&Planet) -> Option<V> + Send + Sync + 'static,
+) -> Column<Body> {
+    Column::new(name, move |x| match x {
+        Body::Planet(x) => f(x),
+        _ => None,
+    })
+}
+
+fn star<V: Display>(
+    name: &str,
+    f: impl Fn(&Star) -> Option<V> + Send + Sync + 'static,
+) -> Column<Body> {
+    Column::new(name, move |x| match x {
+        Body::Star(x) => f(x),
+        _ => None,
+    })
+}
+
+/// Column of a field of both planets and stars.
+fn planet_or_star<V: Display>(
+    name: &str,
+    planet: impl Fn(&Planet) -> Option<V> + Send + Sync + 'static,
+    star: impl Fn(&Star) -> Option<V> + Send + Sync + 'static,
+) -> Column<Body> {
+    Column::new(name, move |x| match x {
+        Body::Planet(x) => planet(x),
+        Body::Star(x) => star(x),
+        Body::Unknown(_) => None,
+    })
+}
+
+// Main Type
+
+impl CsvEntry for Body {
+    #[cfg_attr(not(feature = "unknown_variants"), allow(clippy::clone_on_copy))]
+    fn columns() -> Vec<Column<Self>> {
+        let mut columns = vec![
+            Column::new("id", |x: &Body| Some(x.id())),
+            Column::new("id64", |x: &Body| x.id64()),
+            Column::new("body_id", |x: &Body| x.body_id()),
+            Column::new("name", |x: &Body| Some(x.name().to_owned())),
+            Column::new("type", |x: &Body| match x {
+                Body::Planet(_) => Some("Planet"),
+                Body::Star(_) => Some("Star"),
+                Body::Unknown(_) => None,
+            }),
+            planet_or_star(
+                "sub_type",
+                |x| Some(x.sub_type.to_string()),
+                |x| Some(x.sub_type.to_string()),
+            ),
+            planet_or_star(
+                "arg_of_periapsis",
+                |x| x.arg_of_periapsis,
+                |x| x.arg_of_periapsis,
+            ),
+            Column::new("axial_tilt", |x: &Body| x.axial_tilt()),
+            planet_or_star(
+                "belts_count",
+                |x| x.belts.as_ref().map(Vec::len),
+                |x| x.belts.as_ref().map(Vec::len),
+            ),
+            planet_or_star(
+                "discovery_commander",
+                |x| x.discovery.as_ref().map(|d| d.commander.clone()),
+                |x| x.discovery.as_ref().map(|d| d.commander.clone()),
+            ),
+            planet_or_star(
+                "discovery_date",
+                |x| x.discovery.as_ref().map(|d| d.date.format(DATE_FORMAT)),
+                |x| x.discovery.as_ref().map(|d| d.date.format(DATE_FORMAT)),
+            ),
+            Column::new("distance_to_arrival", |x: &Body| x.distance_to_arrival()),
+            Column::new("orbital_eccentricity", |x: &Body| x.orbital_eccentricity()),
+            Column::new("orbital_inclination", |x: &Body| x.orbital_inclination()),
+            Column::new("orbital_period", |x: &Body| x.orbital_period()),
+            Column::new("parents", |x: &Body| {
+                x.parents().map(|p| Joined(p).to_string())
+            }),
+            planet_or_star(
+                "reserve_level",
+                |x| x.reserve_level.as_ref().map(ToString::to_string),
+                |x| x.reserve_level.as_ref().map(ToString::to_string),
+            ),
+            planet_or_star(
+                "rings_count",
+                |x| x.rings.as_ref().map(Vec::len),
+                |x| x.rings.as_ref().map(Vec::len),
+            ),
+            Column::new("rotational_period", |x: &Body| x.rotational_period()),
+            Column::new("rotational_period_tidally_locked", |x: &Body| {
+                x.rotational_period_tidally_locked()
+            }),
+            Column::new("semi_major_axis", |x: &Body| x.semi_major_axis()),
+            Column::new("surface_temperature", |x: &Body| x.surface_temperature()),
+            Column::new("system_id", |x: &Body| x.system_id()),
+            Column::new("system_id64", |x: &Body| x.system_id64()),
+            Column::new("system_name", |x: &Body| x.system_name().map(str::to_owned)),
+            // Planet
+            planet("atmosphere_type", |x| x.atmosphere_type.clone()),
+            planet("earth_masses", |x| Some(x.earth_masses)),
+            planet("gravity", |x| x.gravity),
+            planet("is_landable", |x| Some(x.is_landable)),
+            planet("radius", |x| Some(x.radius)),
+            planet("solid_composition_ice", |x| {
+                x.solid_composition.as_ref().map(|c| c.ice)
+            }),
+            planet("solid_composition_metal", |x| {
+                x.solid_composition.as_ref().map(|c| c.metal)
+            }),
+            planet("solid_composition_rock", |x| {
+                x.solid_composition.as_ref().map(|c| c.rock)
+            }),
+            planet("surface_pressure", |x| x.surface_pressure),
+            planet("terraforming_state", |x| x.terraforming_state.clone()),
+            planet("volcanism_type", |x| x.volcanism_type.clone()),
+        ];
+        for key in AtmosphereCompositionKey::iter() {
+            let name = format!(
+                "atmosphere_composition_{}",
+                snake_case(&format!("{:?}", key))
+            );
+            columns.push(planet(&name, move |x| {
+                x.atmosphere_composition.as_ref()?.get(key)
+            }));
+        }
+        for key in MaterialsKey::iter() {
+            let name = format!("materials_{}", snake_case(&format!("{:?}", key)));
+            columns.push(planet(&name, move |x| x.materials.as_ref()?.get(key)));
+        }
+        columns.extend(vec![
+            // Star
+            star("absolute_magnitude", |x| x.absolute_magnitude),
+            star("age", |x| Some(x.age)),
+            star("is_main_star", |x| Some(x.is_main_star)),
+            star("is_scoopable", |x| Some(x.is_scoopable)),
+            star("luminosity", |x| x.luminosity),
+            star("solar_masses", |x| Some(x.solar_masses)),
+            star("solar_radius", |x| Some(x.solar_radius)),
+            star("spectral_class", |x| x.spectral_class),
+            // Metadata
+            Column::new("update_time", |x: &Body| {
+                Some(x.update_time().format(DATE_FORMAT))
+            }),
+        ]);
+        columns
+    }
+}
+
+impl CsvEntry for PowerPlay {
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::new("id", |x: &PowerPlay| Some(x.id)),
+            Column::new("allegiance", |x: &PowerPlay| x.allegiance.clone()),
+            Column::new("coords_x", |x: &PowerPlay| Some(x.coords.x)),
+            Column::new("coords_y", |x: &PowerPlay| Some(x.coords.y)),
+            Column::new("coords_z", |x: &PowerPlay| Some(x.coords.z)),
+            Column::new("government", |x: &PowerPlay| x.government.clone()),
+            Column::new("id64", |x: &PowerPlay| Some(x.id64)),
+            Column::new("name", |x: &PowerPlay| Some(x.name.clone())),
+            Column::new("power", |x: &PowerPlay| Some(x.power.clone())),
+            Column::new("power_state", |x: &PowerPlay| Some(x.power_state.clone())),
+            Column::new("state", |x: &PowerPlay| x.state.clone()),
+            Column::new("date", |x: &PowerPlay| Some(x.date.format(DATE_FORMAT))),
+        ]
+    }
+}
+
+impl CsvEntry for Station {
+    fn columns() -> Vec<Column<Self>> {
+        let mut columns = vec![
+            Column::new("id", |x: &Station| Some(x.id)),
+            Column::new("allegiance", |x: &Station| x.allegiance.clone()),
+        ];
+        columns.extend(station_body(|x: &Station| x.body.as_ref()));
+        columns.push(Column::new("commodities_count", |x: &Station| {
+            x.commodities.as_ref().map(Vec::len)
+        }));
+        columns.extend(controlling_faction(|x: &Station| {
+            x.controlling_faction.as_ref()
+        }));
+        columns.extend(vec![
+            Column::new("distance_to_arrival", |x: &Station| x.distance_to_arrival),
+            Column::new("economy", |x: &Station| x.economy.clone()),
+            Column::new("government", |x: &Station| x.government.clone()),
+            Column::new("have_market", |x: &Station| Some(x.have_market)),
+            Column::new("have_outfitting", |x: &Station| Some(x.have_outfitting)),
+            Column::new("have_shipyard", |x: &Station| Some(x.have_shipyard)),
+            Column::new("market_id", |x: &Station| x.market_id),
+            Column::new("name", |x: &Station| Some(x.name.clone())),
+            Column::new("other_services", |x: &Station| {
+                Some(Joined(&x.other_services).to_string())
+            }),
+            Column::new("outfitting_count", |x: &Station| {
+                x.outfitting.as_ref().map(Vec::len)
+            }),
+            Column::new("second_economy", |x: &Station| x.second_economy.clone()),
+            Column::new("ships_count", |x: &Station| x.ships.as_ref().map(Vec::len)),
+            Column::new("system_id", |x: &Station| x.system_id),
+            Column::new("system_id64", |x: &Station| x.system_id64),
+            Column::new("system_name", |x: &Station| x.system_name.clone()),
+            Column::new("type", |x: &Station| x.typ.clone()),
+        ]);
+        columns.extend(update_time(|x: &Station| &x.update_time));
+        columns
+    }
+}
+
+impl CsvEntry for SystemWithCoordinates {
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::new("id", |x: &SystemWithCoordinates| Some(x.id)),
+            Column::new("coords_x", |x: &SystemWithCoordinates| Some(x.coords.x)),
+            Column::new("coords_y", |x: &SystemWithCoordinates| Some(x.coords.y)),
+            Column::new("coords_z", |x: &SystemWithCoordinates| Some(x.coords.z)),
+            Column::new("id64", |x: &SystemWithCoordinates| x.id64),
+            Column::new("name", |x: &SystemWithCoordinates| Some(x.name.clone())),
+            Column::new("date", |x: &SystemWithCoordinates| {
+                Some(x.date.format(DATE_FORMAT))
+            }),
+        ]
+    }
+}
+
+impl CsvEntry for SystemWithoutCoordinates {
+    fn columns() -> Vec<Column<Self>> {
+        vec![
+            Column::new("id", |x: &SystemWithoutCoordinates| Some(x.id)),
+            Column::new("estimated_coordinates_x", |x: &SystemWithoutCoordinates| {
+                x.estimated_coordinates.as_ref().map(|c| c.x)
+            }),
+            Column::new("estimated_coordinates_y", |x: &SystemWithoutCoordinates| {
+                x.estimated_coordinates.as_ref().map(|c| c.y)
+            }),
+            Column::new("estimated_coordinates_z", |x: &SystemWithoutCoordinates| {
+                x.estimated_coordinates.as_ref().map(|c| c.z)
+            }),
+            Column::new(
+                "estimated_coordinates_precision",
+                |x: &SystemWithoutCoordinates| {
+                    x.estimated_coordinates.as_ref().map(|c| c.precision)
+                },
+            ),
+            Column::new("id64", |x: &SystemWithoutCoordinates| x.id64),
+            Column::new("name", |x: &SystemWithoutCoordinates| Some(x.name.clone())),
+            Column::new("date", |x: &SystemWithoutCoordinates| {
+                Some(x.date.format(DATE_FORMAT))
+            }),
+        ]
+    }
+}
+
+impl CsvEntry for SystemPopulated {
+    fn columns() -> Vec<Column<Self>> {
+        let mut columns = vec![
+            Column::new("id", |x: &SystemPopulated| Some(x.id)),
+            Column::new("allegiance", |x: &SystemPopulated| x.allegiance.clone()),
+            Column::new("bodies_count", |x: &SystemPopulated| Some(x.bodies.len())),
+        ];
+        columns.extend(controlling_faction(|x: &SystemPopulated| {
+            Some(&x.controlling_faction)
+        }));
+        columns.extend(vec![
+            Column::new("coords_x", |x: &SystemPopulated| Some(x.coords.x)),
+            Column::new("coords_y", |x: &SystemPopulated| Some(x.coords.y)),
+            Column::new("coords_z", |x: &SystemPopulated| Some(x.coords.z)),
+            Column::new("economy", |x: &SystemPopulated| x.economy.clone()),
+            Column::new("factions_count", |x: &SystemPopulated| {
+                x.factions.as_ref().map(Vec::len)
+            }),
+            Column::new("government", |x: &SystemPopulated| x.government.clone()),
+            Column::new("id64", |x: &SystemPopulated| x.id64),
+            Column::new("name", |x: &SystemPopulated| Some(x.name.clone())),
+            Column::new("population", |x: &SystemPopulated| x.population),
+            Column::new("security", |x: &SystemPopulated| Some(x.security.clone())),
+            Column::new("state", |x: &SystemPopulated| x.state.clone()),
+            Column::new("stations_count", |x: &SystemPopulated| {
+                Some(x.stations.len())
+            }),
+            Column::new("date", |x: &SystemPopulated| {
+                Some(x.date.format(DATE_FORMAT))
+            }),
+        ]);
+        columns
+    }
+}
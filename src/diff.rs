@@ -0,0 +1,232 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::index::IndexedDump;
+use crate::model::RootEntry;
+use crate::sort::{ExternalSort, Sorted};
+
+/// Difference of an entry between two dumps.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<T> {
+    /// The entry is only in the new dump.
+    Added(T),
+    /// The entry is only in the old dump.
+    Removed(T),
+    Modified {
+        old: T,
+        new: T,
+        /// Names of the top-level fields that differ, in alphabetical order.
+        fields: Vec<String>,
+    },
+}
+
+impl<T: RootEntry> Change<T> {
+    pub fn entry_id(&self) -> u64 {
+        match self {
+            Change::Added(entry) | Change::Removed(entry) => entry.entry_id(),
+            Change::Modified { new, .. } => new.entry_id(),
+        }
+    }
+}
+
+/// Compares two dumps held in memory, returning the changes in `id` order.
+///
+/// Entries are matched by [`RootEntry::entry_id`]. Entries sharing an `id` are
+/// matched in the order they appear in each dump.
+pub fn diff<T, O, N>(old: O, new: N) -> Result<Vec<Change<T>>>
+where
+    T: RootEntry + PartialEq,
+    O: IntoIterator<Item = Result<T>>,
+    N: IntoIterator<Item = Result<T>>,
+{
+    let mut old = old
+        .into_iter()
+        .collect::<Result<Vec<_>>>()
+        .context("reading old dump")?;
+    let mut new = new
+        .into_iter()
+        .collect::<Result<Vec<_>>>()
+        .context("reading new dump")?;
+    old.sort_by_key(|entry| entry.entry_id());
+    new.sort_by_key(|entry| entry.entry_id());
+
+    Merge::new(old.into_iter().map(Ok), new.into_iter().map(Ok)).collect()
+}
+
+/// Compares two indexed dumps without loading them into memory.
+///
+/// Yields the changes in `id` order, matching entries like [`diff`].
+/// Uncompressed dumps are walked in index order, reading each record by random access.
+/// Compressed dumps, where every random read would decode its member from the start,
+/// are read once and sorted by `id` with an [`ExternalSort`] before this returns.
+pub fn diff_indexed<T>(old: IndexedDump<T>, new: IndexedDump<T>) -> Result<IndexedDiff<T>>
+where
+    T: RootEntry + PartialEq,
+{
+    diff_indexed_with(&ExternalSort::new(), old, new)
+}
+
+/// Same as [`diff_indexed`], sorting compressed dumps with `sort`.
+pub fn diff_indexed_with<T>(
+    sort: &ExternalSort,
+    old: IndexedDump<T>,
+    new: IndexedDump<T>,
+) -> Result<IndexedDiff<T>>
+where
+    T: RootEntry + PartialEq,
+{
+    let old = Entries::new(sort, old).context("sorting old dump")?;
+    let new = Entries::new(sort, new).context("sorting new dump")?;
+    Ok(IndexedDiff(Merge::new(old, new)))
+}
+
+/// Iterator over the changes between two indexed dumps, created by [`diff_indexed`].
+pub struct IndexedDiff<T: RootEntry>(Merge<Entries<T>, Entries<T>>);
+
+impl<T: RootEntry + PartialEq> Iterator for IndexedDiff<T> {
+    type Item = Result<Change<T>>;
+
+    fn next(&mut self) -> Option<Result<Change<T>>> {
+        self.0.next()
+    }
+}
+
+/// Entries of an indexed dump in `id` order.
+enum Entries<T: RootEntry> {
+    Indexed { dump: IndexedDump<T>, pos: u64 },
+    Sorted(Sorted<T, u64, fn(&T) -> u64>),
+}
+
+impl<T: RootEntry> Entries<T> {
+    fn new(sort: &ExternalSort, dump: IndexedDump<T>) -> Result<Entries<T>> {
+        if dump.is_seekable() {
+            return Ok(Entries::Indexed { dump, pos: 0 });
+        }
+        let key: fn(&T) -> u64 = T::entry_id;
+        Ok(Entries::Sorted(sort.sort_by_key(dump.entries()?, key)?))
+    }
+}
+
+impl<T: RootEntry> Iterator for Entries<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        match self {
+            Entries::Indexed { dump, pos } => {
+                if *pos >= dump.len() {
+                    return None;
+                }
+                *pos += 1;
+                Some(
+                    dump.entry_at(*pos - 1)
+                        .and_then(|entry| dump.read_entry(&entry)),
+                )
+            }
+            Entries::Sorted(entries) => entries.next(),
+        }
+    }
+}
+
+/// Full outer merge of two entry streams sorted by `id`.
+///
+/// Unlike a [`merge_join`](crate::join::merge_join), which skips the right entries
+/// without a match, the entries only in the new dump are yielded as added.
+struct Merge<O: Iterator, N: Iterator> {
+    old: Peekable<O>,
+    new: Peekable<N>,
+}
+
+impl<T, O, N> Merge<O, N>
+where
+    T: RootEntry + PartialEq,
+    O: Iterator<Item = Result<T>>,
+    N: Iterator<Item = Result<T>>,
+{
+    fn new(old: O, new: N) -> Merge<O, N> {
+        Merge {
+            old: old.peekable(),
+            new: new.peekable(),
+        }
+    }
+
+    fn next_change(&mut self) -> Result<Option<Change<T>>> {
+        loop {
+            let old = peek_id(&mut self.old).context("reading old dump")?;
+            let new = peek_id(&mut self.new).context("reading new dump")?;
+            let order = match (old, new) {
+                (Some(o), Some(n)) => o.cmp(&n),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return Ok(None),
+            };
+            match order {
+                Ordering::Less => return Ok(self.old.next().transpose()?.map(Change::Removed)),
+                Ordering::Greater => return Ok(self.new.next().transpose()?.map(Change::Added)),
+                Ordering::Equal => {
+                    if let (Some(o), Some(n)) =
+                        (self.old.next().transpose()?, self.new.next().transpose()?)
+                    {
+                        if let Some(change) = compare(o, n)? {
+                            return Ok(Some(change));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T, O, N> Iterator for Merge<O, N>
+where
+    T: RootEntry + PartialEq,
+    O: Iterator<Item = Result<T>>,
+    N: Iterator<Item = Result<T>>,
+{
+    type Item = Result<Change<T>>;
+
+    fn next(&mut self) -> Option<Result<Change<T>>> {
+        self.next_change().transpose()
+    }
+}
+
+/// `id` of the next entry, taking the error if reading it failed.
+fn peek_id<T, I>(entries: &mut Peekable<I>) -> Result<Option<u64>>
+where
+    T: RootEntry,
+    I: Iterator<Item = Result<T>>,
+{
+    match entries.peek() {
+        None => Ok(None),
+        Some(Ok(entry)) => Ok(Some(entry.entry_id())),
+        Some(Err(_)) => match entries.next() {
+            Some(Err(e)) => Err(e),
+            _ => unreachable!(),
+        },
+    }
+}
+
+fn compare<T: RootEntry + PartialEq>(old: T, new: T) -> Result<Option<Change<T>>> {
+    if old == new {
+        return Ok(None);
+    }
+    let fields = changed_fields(&old, &new)?;
+    Ok(Some(Change::Modified { old, new, fields }))
+}
+
+fn changed_fields<T: RootEntry>(old: &T, new: &T) -> Result<Vec<String>> {
+    let (old, new) = match (serde_json::to_value(old)?, serde_json::to_value(new)?) {
+        (Value::Object(old), Value::Object(new)) => (old, new),
+        _ => return Ok(Vec::new()),
+    };
+    let mut fields: Vec<String> = old
+        .iter()
+        .filter(|(k, v)| new.get(*k) != Some(v))
+        .map(|(k, _)| k.clone())
+        .chain(new.keys().filter(|k| !old.contains_key(*k)).cloned())
+        .collect();
+    fields.sort();
+    Ok(fields)
+}
@@ -0,0 +1,229 @@
+use std::error;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use serde_path_to_error::{Path, Segment};
+
+use crate::reader::Position;
+
+/// Category of a [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum ParseErrorKind {
+    /// The record is not valid JSON.
+    Syntax,
+    /// A field not present in the model.
+    UnknownField,
+    /// An enum value not present in the model.
+    UnknownVariant,
+    /// A required field is absent.
+    MissingField,
+    /// A value has the wrong JSON type, e.g. a string instead of a number.
+    InvalidType,
+    /// A value has the right type but is not accepted, e.g. a malformed date.
+    InvalidValue,
+    /// An array has the wrong number of elements.
+    InvalidLength,
+    Other,
+}
+
+impl ParseErrorKind {
+    pub(crate) fn classify(message: &str) -> ParseErrorKind {
+        if message.starts_with("unknown field") {
+            ParseErrorKind::UnknownField
+        } else if message.starts_with("unknown variant") {
+            ParseErrorKind::UnknownVariant
+        } else if message.starts_with("missing field") {
+            ParseErrorKind::MissingField
+        } else if message.starts_with("invalid type") {
+            ParseErrorKind::InvalidType
+        } else if message.starts_with("invalid value") {
+            ParseErrorKind::InvalidValue
+        } else if message.starts_with("invalid length") {
+            ParseErrorKind::InvalidLength
+        } else {
+            ParseErrorKind::Other
+        }
+    }
+}
+
+/// Failure to parse a dump entry.
+///
+/// Returned inside the `anyhow::Error` of
+/// [`RootEntry::parse_dump_json`](crate::model::RootEntry::parse_dump_json)
+/// and the readers, and can be recovered with `anyhow::Error::downcast_ref`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// [`RootEntry::type_name`](crate::model::RootEntry::type_name) of the entry.
+    pub type_name: &'static str,
+    /// `id` of the entry, if the record is valid JSON.
+    pub id: Option<u64>,
+    /// Location of the record, set by the readers.
+    pub position: Option<Position>,
+    /// JSON path of the failing field, e.g. `rings[2].type`.
+    pub path: Option<String>,
+    /// Value found at `path`.
+    pub value: Option<Value>,
+    message: String,
+}
+
+impl ParseError {
+    /// Builds the error for `bs`, which failed to parse as `T` with `error`.
+    pub(crate) fn new<T: DeserializeOwned>(
+        type_name: &'static str,
+        bs: &[u8],
+        error: impl fmt::Display,
+    ) -> ParseError {
+        ParseError::with_locator(type_name, bs, error, locate::<T>)
+    }
+
+    /// Same as [`new`](ParseError::new), with a custom search for the failing field.
+    pub(crate) fn with_locator(
+        type_name: &'static str,
+        bs: &[u8],
+        error: impl fmt::Display,
+        locator: impl FnOnce(&Value) -> Option<(Path, String)>,
+    ) -> ParseError {
+        let value: Value = match serde_json::from_slice(bs) {
+            Ok(v) => v,
+            Err(e) => {
+                return ParseError {
+                    kind: ParseErrorKind::Syntax,
+                    type_name,
+                    id: None,
+                    position: None,
+                    path: None,
+                    value: None,
+                    message: e.to_string(),
+                }
+            }
+        };
+
+        match locator(&value) {
+            Some((path, message)) => ParseError::at_path(type_name, &value, &path, message),
+            None => {
+                let message = error.to_string();
+                ParseError {
+                    kind: ParseErrorKind::classify(&message),
+                    type_name,
+                    id: value.get("id").and_then(Value::as_u64),
+                    position: None,
+                    path: None,
+                    value: None,
+                    message,
+                }
+            }
+        }
+    }
+
+    /// Builds the error for the field at `path` in `value`, which failed with `message`.
+    pub(crate) fn at_path(
+        type_name: &'static str,
+        value: &Value,
+        path: &Path,
+        message: String,
+    ) -> ParseError {
+        ParseError {
+            kind: ParseErrorKind::classify(&message),
+            type_name,
+            id: value.get("id").and_then(Value::as_u64),
+            position: None,
+            path: Some(path.to_string()),
+            value: lookup(value, path).cloned(),
+            message,
+        }
+    }
+
+    /// Builds the error from a simd-json error alone, as its input has been overwritten.
+    #[cfg(feature = "simd-json")]
+    pub(crate) fn from_simd(type_name: &'static str, e: simd_json::Error) -> ParseError {
+        let (kind, message) = match e.error() {
+            simd_json::ErrorType::Serde(message) => {
+                (ParseErrorKind::classify(message), message.clone())
+            }
+            _ if e.is_data() => (ParseErrorKind::InvalidType, e.to_string()),
+            _ => (ParseErrorKind::Syntax, e.to_string()),
+        };
+        ParseError {
+            kind,
+            type_name,
+            id: None,
+            position: None,
+            path: None,
+            value: None,
+            message,
+        }
+    }
+
+    /// Adds the entry id and the failing field found by `locator` in `bs`,
+    /// the input of a simd-json error, which simd-json overwrote while parsing a copy of it.
+    #[cfg(feature = "simd-json")]
+    pub(crate) fn locate_in(
+        mut self,
+        bs: &[u8],
+        locator: impl FnOnce(&Value) -> Option<(Path, String)>,
+    ) -> ParseError {
+        if let Ok(value) = serde_json::from_slice::<Value>(bs) {
+            self.id = value.get("id").and_then(Value::as_u64);
+            if let Some((path, _)) = locator(&value) {
+                self.value = lookup(&value, &path).cloned();
+                self.path = Some(path.to_string());
+            }
+        }
+        self
+    }
+
+    /// Error message of the underlying deserializer.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "parsing {} entry", self.type_name)?;
+        if let Some(id) = self.id {
+            write!(f, " {}", id)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, " at `{}`", path)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Applies [`ParseError::locate_in`] to `e` if it is a [`ParseError`].
+#[cfg(feature = "simd-json")]
+pub(crate) fn locate_in(
+    e: anyhow::Error,
+    bs: &[u8],
+    locator: impl FnOnce(&Value) -> Option<(Path, String)>,
+) -> anyhow::Error {
+    match e.downcast::<ParseError>() {
+        Ok(e) => e.locate_in(bs, locator).into(),
+        Err(e) => e,
+    }
+}
+
+/// Deserializes `value` as `T`, returning the path and message of the first error.
+pub(crate) fn locate<T: DeserializeOwned>(value: &Value) -> Option<(Path, String)> {
+    match serde_path_to_error::deserialize::<_, T>(value) {
+        Ok(_) => None,
+        Err(e) => {
+            let path = e.path().clone();
+            Some((path, e.into_inner().to_string()))
+        }
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &Path) -> Option<&'a Value> {
+    path.iter().try_fold(value, |v, segment| match segment {
+        Segment::Seq { index } => v.get(index),
+        Segment::Map { key } => v.get(key),
+        _ => None,
+    })
+}
@@ -0,0 +1,1899 @@
+//! Seeded generator of synthetic dumps shaped like the EDSM ones.
+//!
+//! [`Generator`] yields the entries of one system at a time.
+//! Within a [`GeneratedSystem`], bodies have consistent `bodyId`s, `parents` chains and `id64`s,
+//! and stations, factions and PowerPlay entries refer to the system, its bodies and each other.
+//! [`DumpSetWriter`] streams them to dump files, so the output size is only bounded by the disk.
+//!
+//! The same seed always generates the same entries.
+
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use strum::IntoEnumIterator;
+
+use crate::model::any::DumpKind;
+use crate::model::bgs::{
+    ActiveState, Allegiance, ControllingFaction, Economy, Government, Happiness, PendingState,
+    RecoveringState, Security, State,
+};
+use crate::model::body::{
+    AtmosphereComposition, AtmosphereCompositionKey, AtmosphereType, Belt, Body, BodyT, Discovery,
+    Luminosity, Materials, MaterialsKey, Parent, Planet, PlanetSubType, ReserveLevel, Ring,
+    SolidComposition, SpectralClass, Star, StarSubType, TerraformingState, VolcanismType,
+};
+use crate::model::powerplay::{Power, PowerPlay, PowerState};
+use crate::model::station::{
+    Commodity, Name, OtherService, Outfitting, Ship, Station, StationBody, StationType, UpdateTime,
+};
+use crate::model::system::{Coords, SystemWithCoordinates};
+use crate::model::system_populated::{FactionInPopulated, StationInPopulated, SystemPopulated};
+use crate::writer::DumpWriter;
+
+/// `2015-01-01 00:00:00`
+const FIRST_DATE: i64 = 1_420_070_400;
+/// `2024-12-31 23:59:59`
+const LAST_DATE: i64 = 1_735_689_599;
+const DAY: i64 = 86_400;
+
+/// Position of the corner of the first sector of `id64`s, in light years.
+const GALAXY_ORIGIN: [f64; 3] = [-49_985.0, -40_985.0, -24_105.0];
+const SECTOR_SIZE: f64 = 1280.0;
+/// Bits of the sector index along x, y and z in `id64`s.
+const SECTOR_BITS: [u32; 3] = [7, 6, 7];
+/// Relative frequency of the mass codes `a` to `h`.
+const MASS_CODE_WEIGHTS: [u32; 8] = [20, 25, 25, 20, 7, 2, 1, 0];
+
+const LS_PER_AU: f64 = 499.005;
+const KM_PER_AU: f64 = 149_597_870.7;
+const SOLAR_RADIUS_AU: f64 = 0.004_650_47;
+const EARTH_MASSES_PER_SOLAR_MASS: f64 = 332_946.0;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+const DEFAULT_POPULATED_RATIO: f64 = 0.05;
+/// Number of factions kept as candidates for expanding into new populated systems.
+const RECENT_FACTIONS: usize = 256;
+
+const SECTOR_PREFIXES: &[&str] = &[
+    "Aae", "Bla", "Boe", "Byoo", "Col", "Dry", "Eo", "Flye", "Gru", "Hyp", "Jue", "Kyloa", "Lyua",
+    "Myo", "Nyeu", "Oob", "Phre", "Pru", "Schee", "Sko", "Syn", "Thue", "Wre", "Zu",
+];
+const SECTOR_SUFFIXES: &[&str] = &[
+    "ae", "aea", "ai", "aoscs", "eia", "eou", "euq", "iae", "ia", "oe", "ooe", "ou", "uefe", "yoi",
+];
+const FACTION_TEMPLATES: &[&str] = &[
+    "{} Holdings",
+    "Defence Party of {}",
+    "{} Crimson Council",
+    "Progressive Party of {}",
+    "{} Gold Major Inc",
+    "{} Purple Cartel",
+    "{} Alliance Union",
+    "New {} Order",
+    "{} Empire Assembly",
+    "{} Liberals",
+    "Independent {} Front",
+    "{} Jet Brotherhood",
+];
+const STATION_NAMES: &[&str] = &[
+    "Azeban",
+    "Brandenstein",
+    "Dalton",
+    "Galileo",
+    "Hooke",
+    "Herschel",
+    "Jameson",
+    "Kepler",
+    "Leonov",
+    "Mitchell",
+    "Nobel",
+    "Ohm",
+    "Polansky",
+    "Ramsay",
+    "Tanner",
+    "Vonarburg",
+    "Wohler",
+];
+const STATION_SUFFIXES: &[&str] = &[
+    "City",
+    "Dock",
+    "Enterprise",
+    "Gateway",
+    "Horizons",
+    "Hub",
+    "Landing",
+    "Orbital",
+    "Port",
+    "Ring",
+    "Station",
+    "Terminal",
+    "Vision",
+];
+const COMMANDERS: &[&str] = &[
+    "Alot",
+    "Cmdr Jameson",
+    "Dalan",
+    "Eol",
+    "Hanna",
+    "Kalak",
+    "Marlin",
+    "Nib",
+    "Orvidius",
+    "Zenith",
+];
+/// `id`, name and average price.
+const COMMODITIES: &[(&str, &str, u64)] = &[
+    ("agriculturalmedicines", "Agri-Medicines", 1200),
+    ("animalmeat", "Animal Meat", 1300),
+    ("beer", "Beer", 180),
+    ("bertrandite", "Bertrandite", 2700),
+    ("biowaste", "Biowaste", 60),
+    ("clothing", "Clothing", 500),
+    ("coffee", "Coffee", 1300),
+    ("consumertechnology", "Consumer Technology", 6800),
+    ("domesticappliances", "Domestic Appliances", 600),
+    ("explosives", "Explosives", 360),
+    ("fish", "Fish", 400),
+    ("foodcartridges", "Food Cartridges", 120),
+    ("fruitandvegetables", "Fruit and Vegetables", 400),
+    ("gold", "Gold", 47_000),
+    ("hydrogenfuel", "Hydrogen Fuel", 110),
+    ("liquor", "Liquor", 700),
+    ("mineraloil", "Mineral Oil", 200),
+    ("palladium", "Palladium", 50_000),
+    ("performanceenhancers", "Performance Enhancers", 6800),
+    ("silver", "Silver", 38_000),
+    ("tea", "Tea", 1600),
+    ("tritium", "Tritium", 41_000),
+    ("water", "Water", 150),
+];
+const MODULES: &[(&str, &str)] = &[
+    ("128049381", "Pulse Laser"),
+    ("128049444", "Missile Rack"),
+    ("128064038", "Power Plant"),
+    ("128064068", "Thrusters"),
+    ("128064113", "Life Support"),
+    ("128064178", "Power Distributor"),
+    ("128064218", "Sensors"),
+    ("128064263", "Frame Shift Drive"),
+    ("128064298", "Shield Generator"),
+    ("128064338", "Cargo Rack"),
+    ("128064353", "Fuel Scoop"),
+    ("128668536", "Shield Booster"),
+];
+const SHIPS: &[(u64, &str)] = &[
+    (128_049_249, "Sidewinder"),
+    (128_049_255, "Eagle"),
+    (128_049_261, "Hauler"),
+    (128_049_267, "Adder"),
+    (128_049_279, "Cobra MkIII"),
+    (128_049_303, "Asp Explorer"),
+    (128_049_309, "Vulture"),
+    (128_049_321, "Federal Dropship"),
+    (128_049_327, "Type-6 Transporter"),
+    (128_049_339, "Python"),
+    (128_049_363, "Anaconda"),
+    (128_816_574, "Krait MkII"),
+];
+
+/// Entries of one generated system in each dump.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedSystem {
+    pub system: SystemWithCoordinates,
+    pub bodies: Vec<Body>,
+    pub stations: Vec<Station>,
+    /// Set for populated systems, with the same bodies and stations.
+    pub populated: Option<SystemPopulated>,
+    /// One entry per power, two for contested systems.
+    pub power_play: Vec<PowerPlay>,
+}
+
+/// Infinite iterator of [`GeneratedSystem`]s from a seed.
+///
+/// Ids of systems, bodies, stations and factions count up from 1.
+/// Populated systems gather around Sol, like the bubble, and the others spread over the galactic disc.
+pub struct Generator {
+    rng: ChaCha8Rng,
+    populated_ratio: f64,
+    next_system_id: u64,
+    next_body_id: u64,
+    next_station_id: u64,
+    next_faction_id: u64,
+    /// Next `n2`, the last number of system names, for each boxel.
+    boxels: HashMap<u64, u64>,
+    factions: VecDeque<Faction>,
+    star_kinds: Vec<StarKind>,
+    spectral_classes: HashMap<String, SpectralClass>,
+}
+
+#[derive(Debug, Clone)]
+struct Faction {
+    id: u64,
+    name: String,
+    allegiance: Allegiance,
+    government: Government,
+    is_player: bool,
+}
+
+struct StarKind {
+    sub_type: StarSubType,
+    weight: u32,
+    /// Letter of the spectral class, followed by a digit from the temperature.
+    class: Option<&'static str>,
+    solar_masses: (f64, f64),
+    temperature: (f64, f64),
+    solar_radius: (f64, f64),
+    luminosity: Vec<Luminosity>,
+    is_scoopable: bool,
+}
+
+struct Orbit {
+    /// In AU.
+    semi_major_axis: f64,
+    /// In days.
+    period: f64,
+    eccentricity: f64,
+    inclination: f64,
+    arg_of_periapsis: f64,
+}
+
+/// Attributes shared by the bodies of a system.
+struct SystemRef<'a> {
+    system: &'a SystemWithCoordinates,
+    date: DateTime<Utc>,
+    /// In millions of years.
+    age: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Generator {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            populated_ratio: DEFAULT_POPULATED_RATIO,
+            next_system_id: 1,
+            next_body_id: 1,
+            next_station_id: 1,
+            next_faction_id: 1,
+            boxels: HashMap::new(),
+            factions: VecDeque::new(),
+            star_kinds: star_kinds(),
+            spectral_classes: SpectralClass::iter().map(|c| (c.to_string(), c)).collect(),
+        }
+    }
+
+    /// Sets the fraction of populated systems, `0.05` by default.
+    pub fn populated_ratio(mut self, ratio: f64) -> Generator {
+        self.populated_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    fn system(&mut self) -> GeneratedSystem {
+        let populated = self.rng.gen_bool(self.populated_ratio);
+        let (coords, id64, name) = self.location(populated);
+        let id = self.next_system_id;
+        self.next_system_id += 1;
+        let date = self.date(FIRST_DATE, LAST_DATE - 180 * DAY);
+        let system = SystemWithCoordinates {
+            id,
+            coords,
+            id64: Some(id64),
+            name,
+            date,
+        };
+
+        let sys = SystemRef {
+            system: &system,
+            date: self.date_after(date, 30),
+            age: self.rng.gen_range(10..13_000),
+        };
+        let bodies = self.bodies(&sys);
+
+        let (stations, populated, power_play) = if populated {
+            let date = self.date_after(date, 60);
+            let factions = self.factions(&system.name);
+            let stations = self.stations(&system, &bodies, &factions, date);
+            let populated = self.populated(&system, &bodies, &stations, factions, date);
+            let power_play = self.power_play(&populated);
+            (stations, Some(populated), power_play)
+        } else if self.rng.gen_bool(0.01) {
+            let date = self.date_after(date, 60);
+            (vec![self.fleet_carrier(&system, date)], None, Vec::new())
+        } else {
+            (Vec::new(), None, Vec::new())
+        };
+
+        GeneratedSystem {
+            system,
+            bodies,
+            stations,
+            populated,
+            power_play,
+        }
+    }
+
+    // Systems
+
+    /// Coordinates, `id64` and procedural name of a new system.
+    fn location(&mut self, populated: bool) -> (Coords, u64, String) {
+        loop {
+            let position = if populated {
+                [
+                    self.normal(0.0, 120.0),
+                    self.normal(0.0, 60.0),
+                    self.normal(0.0, 120.0),
+                ]
+            } else {
+                [
+                    self.normal(0.0, 9000.0).clamp(-40_000.0, 40_000.0),
+                    self.normal(0.0, 400.0).clamp(-2000.0, 2000.0),
+                    self.normal(25_000.0, 12_000.0).clamp(-20_000.0, 65_000.0),
+                ]
+            };
+            // Dump coordinates are multiples of 1/32 ly
+            let position = position.map(|x| (x * 32.0).round() / 32.0);
+
+            let mass_code = self.weighted(&MASS_CODE_WEIGHTS) as u64;
+            let boxel_size = 10.0 * (1 << mass_code) as f64;
+            let boxel_bits = 7 - mass_code as u32;
+            let mut sector = [0; 3];
+            let mut boxel = [0; 3];
+            for axis in 0..3 {
+                let rel = position[axis] - GALAXY_ORIGIN[axis];
+                sector[axis] = (rel / SECTOR_SIZE).floor() as u64;
+                boxel[axis] = ((rel - sector[axis] as f64 * SECTOR_SIZE) / boxel_size) as u64;
+            }
+
+            // Mass code, then boxel and sector along z, y and x, then n2
+            let mut id64 = mass_code;
+            let mut shift = 3;
+            for axis in [2, 1, 0] {
+                id64 |= boxel[axis] << shift;
+                shift += boxel_bits;
+                id64 |= sector[axis] << shift;
+                shift += SECTOR_BITS[axis];
+            }
+            let next_n2 = self.boxels.entry(id64).or_insert(0);
+            if *next_n2 >= 1 << (55 - shift) {
+                continue;
+            }
+            let n2 = *next_n2;
+            *next_n2 += 1;
+            id64 |= n2 << shift;
+
+            let boxels_per_axis = 128 >> mass_code;
+            let boxel_index = boxel[0] + (boxel[1] + boxel[2] * boxels_per_axis) * boxels_per_axis;
+            let letter = |n: u64| (b'A' + (n % 26) as u8) as char;
+            let n1 = boxel_index / (26 * 26 * 26);
+            let name = format!(
+                "{} {}{}-{} {}{}",
+                sector_name(sector),
+                letter(boxel_index),
+                letter(boxel_index / 26),
+                letter(boxel_index / (26 * 26)),
+                (b'a' + mass_code as u8) as char,
+                if n1 == 0 {
+                    n2.to_string()
+                } else {
+                    format!("{}-{}", n1, n2)
+                },
+            );
+
+            let coords = Coords {
+                x: position[0] as f32,
+                y: position[1] as f32,
+                z: position[2] as f32,
+            };
+            return (coords, id64, name);
+        }
+    }
+
+    // Bodies
+
+    /// Stars of a system, then the planets and moons of each star.
+    ///
+    /// `bodyId` 0 is the main star of single systems, or the barycentre of binary ones.
+    fn bodies(&mut self, sys: &SystemRef) -> Vec<Body> {
+        let mut bodies = Vec::new();
+        let mut kinds = vec![self.star_kind()];
+        if self.rng.gen_bool(0.25) {
+            kinds.push(self.star_kind());
+            kinds.sort_by(|a, b| {
+                let mass = |i: &usize| self.star_kinds[*i].solar_masses.1;
+                mass(b).partial_cmp(&mass(a)).unwrap()
+            });
+        }
+
+        let binary = kinds.len() == 2;
+        let mut next_body_id = if binary { 1 } else { 0 };
+        let mut stars = Vec::new();
+        let mut distance = 0.0;
+        // Orbits of the stars around their barycentre
+        let separation = self.log_normal(100.0, 1.5);
+        for (i, kind) in kinds.into_iter().enumerate() {
+            let name = if binary {
+                format!("{} {}", sys.system.name, ["A", "B"][i])
+            } else {
+                sys.system.name.clone()
+            };
+            let (parents, orbit) = if binary {
+                let share = if i == 0 { 0.4 } else { 0.6 };
+                distance = if i == 0 { 0.0 } else { separation * LS_PER_AU };
+                let orbit = self.orbit(separation * share, 2.0);
+                (vec![Parent::Null(0)], Some(orbit))
+            } else {
+                (Vec::new(), None)
+            };
+            let star = self.star(sys, kind, next_body_id, name, parents, orbit, distance);
+            next_body_id += 1;
+            stars.push((star, distance));
+        }
+
+        for (star, distance) in stars {
+            let planets = self
+                .rng
+                .gen_range(0..=(star.solar_masses.sqrt() * 12.0) as u64);
+            let parents: Vec<Parent> = std::iter::once(Parent::Star(star.body_id.unwrap()))
+                .chain(star.parents.clone().unwrap_or_default())
+                .collect();
+            let star_radius = star.solar_radius as f64 * SOLAR_RADIUS_AU;
+            let temperature = star.surface_temperature as f64;
+            let mass = star.solar_masses as f64;
+            let name = star.name.clone();
+            bodies.push(Body::Star(star));
+
+            let mut semi_major_axis = self.log_normal(0.1, 0.8) * mass.sqrt().max(0.1);
+            for k in 1..=planets {
+                semi_major_axis *= self.rng.gen_range(1.4..2.2);
+                let orbit = self.orbit(semi_major_axis, mass);
+                let temperature = temperature * (star_radius / (2.0 * semi_major_axis)).sqrt();
+                let sub_type = self.planet_sub_type(temperature, semi_major_axis);
+                let planet = self.planet(
+                    sys,
+                    next_body_id,
+                    format!("{} {}", name, k),
+                    parents.clone(),
+                    sub_type,
+                    orbit,
+                    temperature,
+                    distance + semi_major_axis * LS_PER_AU,
+                );
+                next_body_id += 1;
+                let moons = self.moons(sys, &planet, temperature, &mut next_body_id);
+                bodies.push(Body::Planet(planet));
+                bodies.extend(moons.into_iter().map(Body::Planet));
+            }
+        }
+        bodies
+    }
+
+    fn star_kind(&mut self) -> usize {
+        let weights: Vec<u32> = self.star_kinds.iter().map(|k| k.weight).collect();
+        self.weighted(&weights)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn star(
+        &mut self,
+        sys: &SystemRef,
+        kind: usize,
+        body_id: u64,
+        name: String,
+        parents: Vec<Parent>,
+        orbit: Option<Orbit>,
+        distance: f64,
+    ) -> Star {
+        let kind = &self.star_kinds[kind];
+        let (sub_type, class, luminosity, is_scoopable) = (
+            kind.sub_type,
+            kind.class,
+            kind.luminosity.clone(),
+            kind.is_scoopable,
+        );
+        let (solar_masses, temperature_range, solar_radius) =
+            (kind.solar_masses, kind.temperature, kind.solar_radius);
+
+        // Heavier stars are hotter and larger
+        let position = self.rng.gen::<f64>();
+        let solar_masses = lerp(solar_masses, position);
+        let temperature = lerp(
+            temperature_range,
+            position * 0.8 + self.rng.gen::<f64>() * 0.2,
+        );
+        let solar_radius = lerp(solar_radius, position);
+        // From 0 for the hottest stars of the class to 9 for the coolest
+        let spectral_class = class.and_then(|class| {
+            let (min, max) = temperature_range;
+            let digit = (9.99 * (max - temperature) / (max - min).max(1.0)) as u32;
+            self.spectral_classes
+                .get(&format!("{}{}", class, digit))
+                .cloned()
+        });
+        let luminosity = luminosity.choose(&mut self.rng).cloned();
+        // Relative to the Sun, floored for black holes
+        let luminosity_ratio = (solar_radius.powi(2) * (temperature / 5778.0).powi(4)).max(1e-8);
+        let absolute_magnitude = 4.83 - 2.5 * luminosity_ratio.log10();
+
+        let belts = if self.rng.gen_bool(0.2) {
+            let mut radius = self.log_normal(500_000.0, 1.0);
+            let belts = (0..self.rng.gen_range(1..=2))
+                .map(|i| {
+                    let inner_radius = radius;
+                    radius *= self.rng.gen_range(1.5..3.0);
+                    Belt {
+                        inner_radius: round(inner_radius, 0),
+                        mass: round(self.log_normal(1e13, 2.0), -9),
+                        name: format!("{} {} Belt", name, ["A", "B"][i]),
+                        outer_radius: round(radius, 0),
+                        typ: Some(self.variant()),
+                    }
+                })
+                .collect();
+            Some(belts)
+        } else {
+            None
+        };
+        let reserve_level = belts.as_ref().map(|_| self.variant::<ReserveLevel>());
+
+        Star {
+            id: self.body_id(),
+            absolute_magnitude: Some(round(absolute_magnitude, 6)),
+            age: sys.age,
+            arg_of_periapsis: orbit.as_ref().map(|o| round(o.arg_of_periapsis, 6)),
+            axial_tilt: Some(0.0),
+            belts,
+            body_id: Some(body_id),
+            discovery: self.discovery(sys),
+            distance_to_arrival: distance.round() as u64,
+            id64: Some(body_id64(sys.system, body_id)),
+            is_main_star: body_id <= 1,
+            is_scoopable,
+            luminosity,
+            name,
+            orbital_eccentricity: orbit.as_ref().map(|o| round(o.eccentricity, 6)),
+            orbital_inclination: orbit.as_ref().map(|o| round(o.inclination, 6)),
+            orbital_period: orbit.as_ref().map(|o| o.period as f32),
+            parents: some_if_any(parents),
+            reserve_level,
+            rings: None,
+            rotational_period: Some(self.log_normal(3.0, 0.8) as f32),
+            rotational_period_tidally_locked: false,
+            semi_major_axis: orbit.as_ref().map(|o| o.semi_major_axis as f32),
+            solar_masses: round(solar_masses, 6),
+            solar_radius: solar_radius as f32,
+            spectral_class,
+            sub_type,
+            surface_temperature: temperature.round() as u64,
+            system_id: Some(sys.system.id),
+            system_id64: sys.system.id64,
+            system_name: Some(sys.system.name.clone()),
+            update_time: sys.date,
+        }
+    }
+
+    fn moons(
+        &mut self,
+        sys: &SystemRef,
+        planet: &Planet,
+        temperature: f64,
+        next_body_id: &mut u64,
+    ) -> Vec<Planet> {
+        let count = if is_gas_giant(&planet.sub_type) {
+            if self.rng.gen_bool(0.6) {
+                self.rng.gen_range(1..=5)
+            } else {
+                0
+            }
+        } else if self.rng.gen_bool(0.15) {
+            self.rng.gen_range(1..=2)
+        } else {
+            0
+        };
+
+        let parents: Vec<Parent> = std::iter::once(Parent::Planet(planet.body_id.unwrap()))
+            .chain(planet.parents.clone().unwrap_or_default())
+            .collect();
+        let mass = planet.earth_masses as f64 / EARTH_MASSES_PER_SOLAR_MASS;
+        let mut semi_major_axis = planet.radius as f64 / KM_PER_AU * self.rng.gen_range(2.0..6.0);
+        (0..count)
+            .map(|i| {
+                semi_major_axis *= self.rng.gen_range(1.3..2.0);
+                let orbit = self.orbit(semi_major_axis, mass);
+                let sub_type = self.planet_sub_type(temperature, 0.0);
+                let moon = self.planet(
+                    sys,
+                    *next_body_id,
+                    format!("{} {}", planet.name, (b'a' + i as u8) as char),
+                    parents.clone(),
+                    sub_type,
+                    orbit,
+                    temperature,
+                    planet.distance_to_arrival as f64 + semi_major_axis * LS_PER_AU,
+                );
+                *next_body_id += 1;
+                moon
+            })
+            .collect()
+    }
+
+    fn planet_sub_type(&mut self, temperature: f64, semi_major_axis: f64) -> PlanetSubType {
+        use PlanetSubType::*;
+
+        let r = self.rng.gen::<f64>();
+        if semi_major_axis > 0.5 && r < 0.35 {
+            return match temperature {
+                t if t < 150.0 => match r {
+                    r if r < 0.01 => GasGiantWithAmmoniaBasedLife,
+                    r if r < 0.02 => HeliumRichGasGiant,
+                    _ => ClassIGasGiant,
+                },
+                t if t < 250.0 => match r {
+                    r if r < 0.02 => GasGiantWithWaterBasedLife,
+                    r if r < 0.03 => WaterGiant,
+                    _ => ClassIiGasGiant,
+                },
+                t if t < 800.0 => ClassIiiGasGiant,
+                t if t < 1400.0 => ClassIvGasGiant,
+                _ => ClassVGasGiant,
+            };
+        }
+        match temperature {
+            t if t > 800.0 => match r {
+                r if r < 0.15 => MetalRichBody,
+                r if r < 0.6 => HighMetalContentWorld,
+                _ => RockyBody,
+            },
+            t if t > 350.0 => match r {
+                r if r < 0.05 => MetalRichBody,
+                r if r < 0.5 => HighMetalContentWorld,
+                _ => RockyBody,
+            },
+            t if t > 200.0 => match r {
+                r if r < 0.03 && (240.0..330.0).contains(&t) => EarthLikeWorld,
+                r if r < 0.1 => WaterWorld,
+                r if r < 0.45 => HighMetalContentWorld,
+                r if r < 0.75 => RockyBody,
+                _ => RockyIceWorld,
+            },
+            t if t > 90.0 => match r {
+                r if r < 0.05 => AmmoniaWorld,
+                r if r < 0.15 => RockyBody,
+                r if r < 0.45 => RockyIceWorld,
+                _ => IcyBody,
+            },
+            _ => match r {
+                r if r < 0.15 => RockyIceWorld,
+                _ => IcyBody,
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn planet(
+        &mut self,
+        sys: &SystemRef,
+        body_id: u64,
+        name: String,
+        parents: Vec<Parent>,
+        sub_type: PlanetSubType,
+        orbit: Orbit,
+        temperature: f64,
+        distance: f64,
+    ) -> Planet {
+        let gas_giant = is_gas_giant(&sub_type);
+        let earth_masses = if gas_giant {
+            self.log_normal(150.0, 1.0).clamp(10.0, 4000.0)
+        } else {
+            match sub_type {
+                PlanetSubType::EarthLikeWorld | PlanetSubType::WaterWorld => {
+                    self.log_normal(0.8, 0.5)
+                }
+                _ => self.log_normal(0.15, 1.3),
+            }
+            .clamp(0.0005, 12.0)
+        };
+        let radius = if gas_giant {
+            self.rng.gen_range(40_000.0..80_000.0)
+        } else {
+            EARTH_RADIUS_KM * earth_masses.powf(0.28)
+        };
+        let gravity = earth_masses / (radius / EARTH_RADIUS_KM).powi(2);
+
+        let (atmosphere_type, atmosphere_composition, surface_pressure) = if gas_giant {
+            let hydrogen = self.rng.gen_range(72.0..76.0);
+            let composition = [
+                (AtmosphereCompositionKey::Hydrogen, round(hydrogen, 2)),
+                (AtmosphereCompositionKey::Helium, round(100.0 - hydrogen, 2)),
+            ];
+            (
+                AtmosphereType::NoAtmosphere,
+                Some(composition.iter().cloned().collect()),
+                0.0,
+            )
+        } else {
+            self.atmosphere(&sub_type, temperature)
+        };
+        let is_landable = atmosphere_composition.is_none();
+
+        let tidally_locked = orbit.semi_major_axis < 0.3 && self.rng.gen_bool(0.8);
+        let rotational_period = if tidally_locked {
+            orbit.period
+        } else {
+            self.log_normal(1.0, 1.0)
+        };
+        let rings = if gas_giant && self.rng.gen_bool(0.4) {
+            let mut ring_radius = radius * self.rng.gen_range(1.3..1.8);
+            let rings = (0..self.rng.gen_range(1..=2))
+                .map(|i| {
+                    let inner_radius = ring_radius;
+                    ring_radius *= self.rng.gen_range(1.1..1.6);
+                    Ring {
+                        inner_radius: round(inner_radius, 0),
+                        mass: round(self.log_normal(1e10, 1.5), -6),
+                        name: format!("{} {} Ring", name, ["A", "B"][i]),
+                        outer_radius: round(ring_radius, 0),
+                        typ: Some(self.variant()),
+                    }
+                })
+                .collect();
+            Some(rings)
+        } else {
+            None
+        };
+        let reserve_level = rings.as_ref().map(|_| self.variant::<ReserveLevel>());
+        let terraforming_state = match sub_type {
+            PlanetSubType::HighMetalContentWorld
+            | PlanetSubType::RockyBody
+            | PlanetSubType::WaterWorld
+                if (150.0..450.0).contains(&temperature) && self.rng.gen_bool(0.3) =>
+            {
+                TerraformingState::CandidateForTerraforming
+            }
+            _ => TerraformingState::NotTerraformable,
+        };
+        let volcanism_type = if gas_giant || self.rng.gen_bool(0.7) {
+            VolcanismType::NoVolcanism
+        } else {
+            self.variant()
+        };
+        let materials = if is_landable {
+            Some(self.materials())
+        } else {
+            None
+        };
+        let solid_composition = if gas_giant {
+            None
+        } else {
+            Some(self.solid_composition(&sub_type))
+        };
+
+        Planet {
+            id: self.body_id(),
+            arg_of_periapsis: Some(round(orbit.arg_of_periapsis, 6)),
+            atmosphere_composition,
+            atmosphere_type: Some(atmosphere_type),
+            axial_tilt: Some(round(self.normal(0.0, 0.4), 6)),
+            belts: None,
+            body_id: Some(body_id),
+            discovery: self.discovery(sys),
+            distance_to_arrival: distance.round() as u64,
+            earth_masses: round(earth_masses, 6),
+            gravity: Some(gravity as f32),
+            id64: Some(body_id64(sys.system, body_id)),
+            is_landable,
+            materials,
+            name,
+            orbital_eccentricity: Some(round(orbit.eccentricity, 6)),
+            orbital_inclination: Some(round(orbit.inclination, 6)),
+            orbital_period: Some(orbit.period as f32),
+            parents: some_if_any(parents),
+            radius: round(radius, 4),
+            reserve_level,
+            rings,
+            rotational_period: Some(rotational_period as f32),
+            rotational_period_tidally_locked: tidally_locked,
+            semi_major_axis: Some(orbit.semi_major_axis as f32),
+            solid_composition,
+            sub_type,
+            surface_pressure: Some(round(surface_pressure, 6)),
+            surface_temperature: temperature.round() as u64,
+            system_id: Some(sys.system.id),
+            system_id64: sys.system.id64,
+            system_name: Some(sys.system.name.clone()),
+            terraforming_state: Some(terraforming_state),
+            volcanism_type: Some(volcanism_type),
+            update_time: sys.date,
+        }
+    }
+
+    /// Atmosphere of a terrestrial planet, with its main gas and the surface pressure.
+    fn atmosphere(
+        &mut self,
+        sub_type: &PlanetSubType,
+        temperature: f64,
+    ) -> (AtmosphereType, Option<AtmosphereComposition>, f64) {
+        use AtmosphereCompositionKey as Gas;
+
+        let (typ, gas, pressure) = match sub_type {
+            PlanetSubType::EarthLikeWorld => (
+                AtmosphereType::SuitableForWaterBasedLife,
+                Gas::Nitrogen,
+                self.log_normal(1.0, 0.3),
+            ),
+            PlanetSubType::WaterWorld => (AtmosphereType::Water, Gas::Water, 1.0),
+            PlanetSubType::AmmoniaWorld => (AtmosphereType::Ammonia, Gas::Ammonia, 1.0),
+            _ if self.rng.gen_bool(0.65) || temperature > 1500.0 => {
+                return (AtmosphereType::NoAtmosphere, None, 0.0);
+            }
+            _ => {
+                let atmospheres = [
+                    (AtmosphereType::ThinCarbonDioxide, Gas::CarbonDioxide),
+                    (AtmosphereType::ThinSulphurDioxide, Gas::SulphurDioxide),
+                    (AtmosphereType::ThinArgon, Gas::Argon),
+                    (AtmosphereType::ThinAmmonia, Gas::Ammonia),
+                    (AtmosphereType::ThinNitrogen, Gas::Nitrogen),
+                    (AtmosphereType::ThinNeon, Gas::Neon),
+                    (AtmosphereType::ThinMethane, Gas::Methane),
+                    (AtmosphereType::CarbonDioxide, Gas::CarbonDioxide),
+                    (AtmosphereType::ThickCarbonDioxide, Gas::CarbonDioxide),
+                ];
+                let (typ, gas) = atmospheres.choose(&mut self.rng).cloned().unwrap();
+                (typ, gas, self.log_normal(0.05, 1.5))
+            }
+        };
+
+        let main = self.rng.gen_range(60.0..99.0);
+        let mut others: Vec<Gas> = Gas::iter().filter(|g| *g != gas).collect();
+        others.shuffle(&mut self.rng);
+        let share = (100.0 - main) / 2.0;
+        let composition = [(gas, main), (others[0], share), (others[1], share)]
+            .iter()
+            .map(|(gas, x)| (*gas, round(*x, 2)))
+            .collect();
+        (typ, Some(composition), pressure)
+    }
+
+    fn materials(&mut self) -> Materials {
+        use MaterialsKey::*;
+
+        let common = [
+            (Iron, 20.0),
+            (Nickel, 15.0),
+            (Sulphur, 15.0),
+            (Carbon, 12.0),
+            (Chromium, 9.0),
+            (Phosphorus, 8.0),
+            (Manganese, 8.0),
+            (Zinc, 5.0),
+            (Vanadium, 5.0),
+            (Germanium, 5.0),
+        ];
+        let rare: Vec<MaterialsKey> = MaterialsKey::iter()
+            .filter(|m| common.iter().all(|(c, _)| c != m))
+            .collect();
+        let mut shares = Vec::new();
+        for (m, x) in &common {
+            if self.rng.gen_bool(0.85) {
+                shares.push((*m, x * self.rng.gen_range(0.5..1.5)));
+            }
+        }
+        let count = self.rng.gen_range(2..=4);
+        for m in rare.choose_multiple(&mut self.rng, count) {
+            shares.push((*m, self.rng.gen_range(0.5..2.5)));
+        }
+
+        let total: f64 = shares.iter().map(|(_, x)| x).sum();
+        shares
+            .into_iter()
+            .map(|(m, x)| (m, round(x * 100.0 / total, 2)))
+            .collect()
+    }
+
+    fn solid_composition(&mut self, sub_type: &PlanetSubType) -> SolidComposition {
+        let (ice, metal) = match sub_type {
+            PlanetSubType::IcyBody => (self.rng.gen_range(70.0..100.0), 0.0),
+            PlanetSubType::RockyIceWorld => (self.rng.gen_range(10.0..40.0), 0.0),
+            PlanetSubType::MetalRichBody => (0.0, self.rng.gen_range(60.0..95.0)),
+            PlanetSubType::HighMetalContentWorld => (0.0, self.rng.gen_range(25.0..40.0)),
+            PlanetSubType::RockyBody => (0.0, self.rng.gen_range(10.0..25.0)),
+            _ => (
+                self.rng.gen_range(0.0..30.0),
+                self.rng.gen_range(20.0..35.0),
+            ),
+        };
+        let ice = round(ice, 2);
+        let metal = round(metal * (100.0 - ice as f64) / 100.0, 2);
+        SolidComposition {
+            ice,
+            metal,
+            rock: round(100.0 - ice as f64 - metal as f64, 2),
+        }
+    }
+
+    /// Orbit of the given size around a body of `central_mass` solar masses.
+    fn orbit(&mut self, semi_major_axis: f64, central_mass: f64) -> Orbit {
+        Orbit {
+            semi_major_axis,
+            period: 365.25 * (semi_major_axis.powi(3) / central_mass.max(1e-9)).sqrt(),
+            eccentricity: (-self.rng.gen::<f64>().ln() * 0.05).min(0.9),
+            inclination: self.normal(0.0, 5.0),
+            arg_of_periapsis: self.rng.gen_range(0.0..360.0),
+        }
+    }
+
+    fn discovery(&mut self, sys: &SystemRef) -> Option<Discovery> {
+        if self.rng.gen_bool(0.2) {
+            Some(Discovery {
+                commander: COMMANDERS.choose(&mut self.rng).unwrap().to_string(),
+                date: self.date(sys.system.date.timestamp(), sys.date.timestamp()),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn body_id(&mut self) -> u64 {
+        let id = self.next_body_id;
+        self.next_body_id += 1;
+        id
+    }
+
+    // Populated systems
+
+    /// Factions present in a system, with new ones created there and older ones expanding to it.
+    fn factions(&mut self, system_name: &str) -> Vec<Faction> {
+        let count = self.rng.gen_range(3..=7);
+        let created = if self.factions.len() < count {
+            count
+        } else {
+            self.rng.gen_range(1..=2)
+        };
+
+        let mut factions = Vec::with_capacity(count);
+        let templates: Vec<&str> = FACTION_TEMPLATES
+            .choose_multiple(&mut self.rng, created)
+            .cloned()
+            .collect();
+        for template in templates {
+            let faction = Faction {
+                id: self.next_faction_id,
+                name: template.replace("{}", system_name),
+                allegiance: self.weighted_choice(&[
+                    (Allegiance::Federation, 30),
+                    (Allegiance::Empire, 25),
+                    (Allegiance::Alliance, 15),
+                    (Allegiance::Independent, 30),
+                ]),
+                government: self.weighted_choice(&[
+                    (Government::Anarchy, 10),
+                    (Government::Communism, 5),
+                    (Government::Confederacy, 10),
+                    (Government::Cooperative, 10),
+                    (Government::Corporate, 25),
+                    (Government::Democracy, 15),
+                    (Government::Dictatorship, 10),
+                    (Government::Feudal, 5),
+                    (Government::Patronage, 5),
+                    (Government::Theocracy, 5),
+                ]),
+                is_player: self.rng.gen_bool(0.15),
+            };
+            self.next_faction_id += 1;
+            if self.factions.len() == RECENT_FACTIONS {
+                self.factions.pop_front();
+            }
+            self.factions.push_back(faction.clone());
+            factions.push(faction);
+        }
+        while factions.len() < count {
+            let faction = &self.factions[self.rng.gen_range(0..self.factions.len())];
+            if factions.iter().all(|f| f.id != faction.id) {
+                factions.push(faction.clone());
+            }
+        }
+        factions
+    }
+
+    fn stations(
+        &mut self,
+        system: &SystemWithCoordinates,
+        bodies: &[Body],
+        factions: &[Faction],
+        date: DateTime<Utc>,
+    ) -> Vec<Station> {
+        let landable: Vec<&Body> = bodies
+            .iter()
+            .filter(|b| matches!(b, Body::Planet(p) if p.is_landable))
+            .collect();
+        let count = self.rng.gen_range(1..=6);
+        let mut stations = Vec::with_capacity(count + 1);
+        for i in 0..count {
+            let planetary = i > 0 && !landable.is_empty() && self.rng.gen_bool(0.5);
+            let (typ, body) = if planetary {
+                let typ = self.weighted_choice(&[
+                    (StationType::PlanetaryOutpost, 40),
+                    (StationType::PlanetaryPort, 10),
+                    (StationType::OdysseySettlement, 50),
+                ]);
+                (typ, *landable.choose(&mut self.rng).unwrap())
+            } else {
+                let typ = self.weighted_choice(&[
+                    (StationType::CoriolisStarport, 30),
+                    (StationType::OrbisStarport, 15),
+                    (StationType::OcellusStarport, 10),
+                    (StationType::Outpost, 40),
+                    (StationType::AsteroidBase, 5),
+                ]);
+                (typ, bodies.choose(&mut self.rng).unwrap())
+            };
+            let faction = factions.choose(&mut self.rng).unwrap();
+            stations.push(self.station(system, typ, body, faction, date));
+        }
+        if self.rng.gen_bool(0.2) {
+            stations.push(self.fleet_carrier(system, date));
+        }
+        stations
+    }
+
+    fn station(
+        &mut self,
+        system: &SystemWithCoordinates,
+        typ: StationType,
+        body: &Body,
+        faction: &Faction,
+        date: DateTime<Utc>,
+    ) -> Station {
+        let planetary = matches!(
+            typ,
+            StationType::PlanetaryOutpost
+                | StationType::PlanetaryPort
+                | StationType::OdysseySettlement
+        );
+        let large = matches!(
+            typ,
+            StationType::CoriolisStarport
+                | StationType::OrbisStarport
+                | StationType::OcellusStarport
+                | StationType::PlanetaryPort
+                | StationType::AsteroidBase
+        );
+        let (latitude, longitude) = if planetary {
+            (
+                Some(round(self.rng.gen_range(-90.0..90.0), 6)),
+                Some(round(self.rng.gen_range(-180.0..180.0), 6)),
+            )
+        } else {
+            (None, None)
+        };
+        let distance = body.distance_to_arrival().unwrap_or(0) as f64
+            + if planetary {
+                0.0
+            } else {
+                self.rng.gen_range(0.0..5.0)
+            };
+
+        let have_market = self.rng.gen_bool(if planetary { 0.5 } else { 0.9 });
+        let have_outfitting = large || self.rng.gen_bool(0.3);
+        let have_shipyard = large && self.rng.gen_bool(0.8);
+        let mut other_services: Vec<OtherService> = OtherService::iter()
+            .filter(|_| self.rng.gen_bool(0.5))
+            .collect();
+        other_services.shuffle(&mut self.rng);
+
+        let economies = [
+            Economy::Agriculture,
+            Economy::Colony,
+            Economy::Extraction,
+            Economy::HighTech,
+            Economy::Industrial,
+            Economy::Military,
+            Economy::Refinery,
+            Economy::Service,
+            Economy::Terraforming,
+            Economy::Tourism,
+        ];
+        let economy = economies.choose(&mut self.rng).cloned();
+        let second_economy = if self.rng.gen_bool(0.5) {
+            economies.choose(&mut self.rng).cloned()
+        } else {
+            None
+        };
+        let market_date = self.date_after(date, 10);
+
+        let id = self.next_station_id;
+        self.next_station_id += 1;
+        Station {
+            id,
+            allegiance: Some(faction.allegiance.clone()),
+            body: Some(StationBody {
+                id: body.id(),
+                latitude,
+                longitude,
+                name: body.name().to_owned(),
+            }),
+            commodities: if have_market {
+                Some(self.commodities())
+            } else {
+                None
+            },
+            controlling_faction: Some(ControllingFaction {
+                id: Some(faction.id),
+                allegiance: None,
+                government: None,
+                is_player: None,
+                name: Some(faction.name.clone()),
+            }),
+            distance_to_arrival: Some(round(distance, 3)),
+            economy,
+            government: Some(faction.government.clone()),
+            have_market,
+            have_outfitting,
+            have_shipyard,
+            market_id: Some(market_id(id)),
+            name: format!(
+                "{} {}",
+                STATION_NAMES.choose(&mut self.rng).unwrap(),
+                STATION_SUFFIXES.choose(&mut self.rng).unwrap()
+            ),
+            other_services,
+            outfitting: if have_outfitting {
+                Some(self.outfitting())
+            } else {
+                None
+            },
+            second_economy,
+            ships: if have_shipyard {
+                Some(self.ships())
+            } else {
+                None
+            },
+            system_id: Some(system.id),
+            system_id64: system.id64,
+            system_name: Some(system.name.clone()),
+            typ: Some(typ),
+            update_time: UpdateTime {
+                information: date,
+                market: if have_market { Some(market_date) } else { None },
+                outfitting: if have_outfitting {
+                    Some(market_date)
+                } else {
+                    None
+                },
+                shipyard: if have_shipyard {
+                    Some(market_date)
+                } else {
+                    None
+                },
+            },
+        }
+    }
+
+    /// Fleet carrier, which has no body nor faction.
+    fn fleet_carrier(&mut self, system: &SystemWithCoordinates, date: DateTime<Utc>) -> Station {
+        const CALLSIGN: &[u8] = b"0123456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+        let mut name: String = (0..7)
+            .map(|_| *CALLSIGN.choose(&mut self.rng).unwrap() as char)
+            .collect();
+        name.replace_range(3..4, "-");
+
+        let id = self.next_station_id;
+        self.next_station_id += 1;
+        Station {
+            id,
+            allegiance: None,
+            body: None,
+            commodities: None,
+            controlling_faction: Some(ControllingFaction {
+                id: None,
+                allegiance: None,
+                government: None,
+                is_player: None,
+                name: Some("FleetCarrier".to_owned()),
+            }),
+            distance_to_arrival: None,
+            economy: Some(Economy::FleetCarrier),
+            government: Some(Government::FleetCarrier),
+            have_market: true,
+            have_outfitting: false,
+            have_shipyard: false,
+            market_id: Some(market_id(id)),
+            name,
+            other_services: vec![
+                OtherService::Refuel,
+                OtherService::Repair,
+                OtherService::Restock,
+            ],
+            outfitting: None,
+            second_economy: None,
+            ships: None,
+            system_id: Some(system.id),
+            system_id64: system.id64,
+            system_name: Some(system.name.clone()),
+            typ: Some(StationType::FleetCarrier),
+            update_time: UpdateTime {
+                information: date,
+                market: None,
+                outfitting: None,
+                shipyard: None,
+            },
+        }
+    }
+
+    fn commodities(&mut self) -> Vec<Commodity> {
+        let count = self.rng.gen_range(5..=COMMODITIES.len());
+        let mut commodities: Vec<_> = COMMODITIES.choose_multiple(&mut self.rng, count).collect();
+        commodities.sort();
+        commodities
+            .into_iter()
+            .map(|(id, name, price)| {
+                let price = *price as f64 * self.rng.gen_range(0.8..1.2);
+                let (buy_price, sell_price, stock, demand) = if self.rng.gen_bool(0.5) {
+                    let stock = self.rng.gen_range(100..20_000);
+                    (price as u64, (price * 0.95) as u64, stock, 0)
+                } else {
+                    let demand = self.rng.gen_range(100..5000);
+                    (0, (price * 1.1) as u64, 0, demand)
+                };
+                Commodity {
+                    id: Some(id.to_string()),
+                    name: name.to_string(),
+                    buy_price,
+                    demand,
+                    sell_price,
+                    stock,
+                    stock_bracket: if stock > 0 {
+                        self.rng.gen_range(1..=3)
+                    } else {
+                        0
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn outfitting(&mut self) -> Vec<Outfitting> {
+        let count = self.rng.gen_range(1..=MODULES.len());
+        MODULES
+            .choose_multiple(&mut self.rng, count)
+            .map(|(id, name)| Outfitting {
+                id: Some(id.to_string()),
+                name: name.to_string(),
+            })
+            .collect()
+    }
+
+    fn ships(&mut self) -> Vec<Ship> {
+        let count = self.rng.gen_range(1..=SHIPS.len());
+        SHIPS
+            .choose_multiple(&mut self.rng, count)
+            .map(|(id, name)| Ship {
+                id: *id,
+                name: Name::String(name.to_string()),
+            })
+            .collect()
+    }
+
+    fn populated(
+        &mut self,
+        system: &SystemWithCoordinates,
+        bodies: &[Body],
+        stations: &[Station],
+        factions: Vec<Faction>,
+        date: DateTime<Utc>,
+    ) -> SystemPopulated {
+        let weights: Vec<f64> = factions.iter().map(|_| self.rng.gen::<f64>()).collect();
+        let total: f64 = weights.iter().sum();
+        let mut factions: Vec<FactionInPopulated> = factions
+            .into_iter()
+            .zip(weights)
+            .map(|(f, weight)| self.faction_in_populated(f, weight / total, date))
+            .collect();
+        factions.sort_by(|a, b| b.influence.partial_cmp(&a.influence).unwrap());
+        let controlling = &factions[0];
+
+        SystemPopulated {
+            id: system.id,
+            allegiance: controlling.allegiance.clone(),
+            bodies: bodies.to_vec(),
+            controlling_faction: ControllingFaction {
+                id: Some(controlling.id),
+                allegiance: controlling.allegiance.clone(),
+                government: controlling.government.clone(),
+                is_player: Some(controlling.is_player),
+                name: Some(controlling.name.clone()),
+            },
+            coords: system.coords,
+            economy: stations.iter().find_map(|s| s.economy.clone()),
+            government: controlling.government.clone(),
+            id64: system.id64,
+            name: system.name.clone(),
+            population: Some(self.log_normal(1e6, 3.0).round() as u64),
+            security: self.variant::<Security>(),
+            state: controlling.state.clone(),
+            stations: stations.iter().map(station_in_populated).collect(),
+            date,
+            factions: Some(factions),
+        }
+    }
+
+    fn faction_in_populated(
+        &mut self,
+        faction: Faction,
+        influence: f64,
+        date: DateTime<Utc>,
+    ) -> FactionInPopulated {
+        let state = if self.rng.gen_bool(0.6) {
+            State::None
+        } else {
+            self.variant()
+        };
+        let active_states = if state == State::None {
+            Vec::new()
+        } else {
+            vec![ActiveState {
+                state: state.clone(),
+            }]
+        };
+        let pending_states = if self.rng.gen_bool(0.2) {
+            vec![PendingState {
+                state: self.variant(),
+                trend: 0,
+            }]
+        } else {
+            Vec::new()
+        };
+        let recovering_states = if self.rng.gen_bool(0.2) {
+            vec![RecoveringState {
+                state: self.variant(),
+                trend: 0,
+            }]
+        } else {
+            Vec::new()
+        };
+
+        FactionInPopulated {
+            id: faction.id,
+            active_states,
+            allegiance: Some(faction.allegiance),
+            government: Some(faction.government),
+            happiness: Some(self.variant::<Happiness>()),
+            influence: round(influence, 3),
+            is_player: faction.is_player,
+            name: faction.name,
+            pending_states,
+            recovering_states,
+            state: Some(state),
+            last_update: date,
+        }
+    }
+
+    /// Entries of the powers exploiting or controlling a populated system, if any.
+    fn power_play(&mut self, populated: &SystemPopulated) -> Vec<PowerPlay> {
+        if !self.rng.gen_bool(0.3) {
+            return Vec::new();
+        }
+        let mut powers: Vec<Power> = Power::iter().collect();
+        powers.shuffle(&mut self.rng);
+        let states = if self.rng.gen_bool(0.05) {
+            vec![PowerState::Contested, PowerState::Contested]
+        } else {
+            vec![self.weighted_choice(&[
+                (PowerState::Exploited, 60),
+                (PowerState::Controlled, 25),
+                (PowerState::Prepared, 5),
+                (PowerState::InPrepareRadius, 5),
+                (PowerState::Turmoil, 4),
+                (PowerState::HomeSystem, 1),
+            ])]
+        };
+
+        powers
+            .into_iter()
+            .zip(states)
+            .map(|(power, power_state)| PowerPlay {
+                id: populated.id,
+                allegiance: populated.allegiance.clone(),
+                coords: populated.coords,
+                government: populated.government.clone(),
+                id64: populated.id64.unwrap(),
+                name: populated.name.clone(),
+                power,
+                power_state,
+                state: populated.state.clone(),
+                date: populated.date,
+            })
+            .collect()
+    }
+
+    // Random values
+
+    fn normal(&mut self, mean: f64, sd: f64) -> f64 {
+        // Box-Muller transform
+        let u = 1.0 - self.rng.gen::<f64>();
+        let v = self.rng.gen::<f64>();
+        mean + sd * (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+    }
+
+    fn log_normal(&mut self, median: f64, sigma: f64) -> f64 {
+        median * self.normal(0.0, sigma).exp()
+    }
+
+    /// Date in whole seconds between two timestamps.
+    fn date(&mut self, from: i64, to: i64) -> DateTime<Utc> {
+        let secs = self.rng.gen_range(from..=to.max(from));
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    /// Date up to `days` after `date`.
+    fn date_after(&mut self, date: DateTime<Utc>, days: i64) -> DateTime<Utc> {
+        let from = date.timestamp();
+        self.date(from, (from + days * DAY).min(LAST_DATE))
+    }
+
+    /// Index picked with the given relative frequencies.
+    fn weighted(&mut self, weights: &[u32]) -> usize {
+        let total: u32 = weights.iter().sum();
+        let mut x = self.rng.gen_range(0..total);
+        weights
+            .iter()
+            .position(|w| {
+                if x < *w {
+                    true
+                } else {
+                    x -= w;
+                    false
+                }
+            })
+            .unwrap()
+    }
+
+    fn weighted_choice<T: Clone>(&mut self, choices: &[(T, u32)]) -> T {
+        let weights: Vec<u32> = choices.iter().map(|(_, w)| *w).collect();
+        choices[self.weighted(&weights)].0.clone()
+    }
+
+    /// Known variant of a string enum.
+    fn variant<T: IntoEnumIterator>(&mut self) -> T {
+        let count = T::iter().count();
+        T::iter().nth(self.rng.gen_range(0..count)).unwrap()
+    }
+}
+
+impl Iterator for Generator {
+    type Item = GeneratedSystem;
+
+    fn next(&mut self) -> Option<GeneratedSystem> {
+        Some(self.system())
+    }
+}
+
+/// Set of values for each dump written by [`DumpSetWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DumpSet<T> {
+    pub systems_with_coordinates: T,
+    pub bodies: T,
+    pub stations: T,
+    pub systems_populated: T,
+    pub power_play: T,
+}
+
+/// Writer of the dump files of [`GeneratedSystem`]s, through [`DumpWriter`].
+pub struct DumpSetWriter<W> {
+    systems_with_coordinates: DumpWriter<W, SystemWithCoordinates>,
+    bodies: DumpWriter<W, Body>,
+    stations: DumpWriter<W, Station>,
+    systems_populated: DumpWriter<W, SystemPopulated>,
+    power_play: DumpWriter<W, PowerPlay>,
+}
+
+impl<W: Write> DumpSetWriter<W> {
+    pub fn new(outputs: DumpSet<W>) -> DumpSetWriter<W> {
+        DumpSetWriter {
+            systems_with_coordinates: DumpWriter::new(outputs.systems_with_coordinates),
+            bodies: DumpWriter::new(outputs.bodies),
+            stations: DumpWriter::new(outputs.stations),
+            systems_populated: DumpWriter::new(outputs.systems_populated),
+            power_play: DumpWriter::new(outputs.power_play),
+        }
+    }
+
+    pub fn write(&mut self, generated: &GeneratedSystem) -> Result<()> {
+        self.systems_with_coordinates.write(&generated.system)?;
+        for body in &generated.bodies {
+            self.bodies.write(body)?;
+        }
+        for station in &generated.stations {
+            self.stations.write(station)?;
+        }
+        if let Some(populated) = &generated.populated {
+            self.systems_populated.write(populated)?;
+        }
+        for power_play in &generated.power_play {
+            self.power_play.write(power_play)?;
+        }
+        Ok(())
+    }
+
+    /// Number of entries written to each dump.
+    pub fn count(&self) -> DumpSet<u64> {
+        DumpSet {
+            systems_with_coordinates: self.systems_with_coordinates.count(),
+            bodies: self.bodies.count(),
+            stations: self.stations.count(),
+            systems_populated: self.systems_populated.count(),
+            power_play: self.power_play.count(),
+        }
+    }
+
+    pub fn finish(self) -> Result<DumpSet<W>> {
+        Ok(DumpSet {
+            systems_with_coordinates: self.systems_with_coordinates.finish()?,
+            bodies: self.bodies.finish()?,
+            stations: self.stations.finish()?,
+            systems_populated: self.systems_populated.finish()?,
+            power_play: self.power_play.finish()?,
+        })
+    }
+}
+
+impl DumpSetWriter<BufWriter<File>> {
+    /// Creates the dump files in `dir`, named as on EDSM (see [`DumpKind::file_name`]).
+    pub fn create<P: AsRef<Path>>(dir: P) -> Result<DumpSetWriter<BufWriter<File>>> {
+        let dir = dir.as_ref();
+        let create = |kind: DumpKind| -> Result<BufWriter<File>> {
+            let path = dir.join(kind.file_name());
+            let f = File::create(&path).with_context(|| format!("creating {}", path.display()))?;
+            Ok(BufWriter::new(f))
+        };
+        Ok(DumpSetWriter::new(DumpSet {
+            systems_with_coordinates: create(DumpKind::SystemWithCoordinates)?,
+            bodies: create(DumpKind::Body)?,
+            stations: create(DumpKind::Station)?,
+            systems_populated: create(DumpKind::SystemPopulated)?,
+            power_play: create(DumpKind::PowerPlay)?,
+        }))
+    }
+}
+
+// Helpers
+
+fn star_kinds() -> Vec<StarKind> {
+    use Luminosity::*;
+    use StarSubType::*;
+
+    let main_sequence = || vec![Va, Vab, Vb, Vz, V];
+    let kind =
+        |sub_type, weight, class, solar_masses, temperature, solar_radius, luminosity| StarKind {
+            is_scoopable: matches!(class, Some("O" | "B" | "A" | "F" | "G" | "K" | "M")),
+            sub_type,
+            weight,
+            class,
+            solar_masses,
+            temperature,
+            solar_radius,
+            luminosity,
+        };
+    vec![
+        kind(
+            OBlueWhiteStar,
+            5,
+            Some("O"),
+            (16.0, 90.0),
+            (30_000.0, 52_000.0),
+            (6.6, 15.0),
+            main_sequence(),
+        ),
+        kind(
+            BBlueWhiteStar,
+            100,
+            Some("B"),
+            (2.1, 16.0),
+            (10_000.0, 30_000.0),
+            (1.8, 6.6),
+            main_sequence(),
+        ),
+        kind(
+            ABlueWhiteStar,
+            300,
+            Some("A"),
+            (1.4, 2.1),
+            (7500.0, 10_000.0),
+            (1.4, 1.8),
+            main_sequence(),
+        ),
+        kind(
+            FWhiteStar,
+            600,
+            Some("F"),
+            (1.04, 1.4),
+            (6000.0, 7500.0),
+            (1.15, 1.4),
+            main_sequence(),
+        ),
+        kind(
+            GWhiteYellowStar,
+            800,
+            Some("G"),
+            (0.8, 1.04),
+            (5200.0, 6000.0),
+            (0.96, 1.15),
+            main_sequence(),
+        ),
+        kind(
+            KYellowOrangeStar,
+            1500,
+            Some("K"),
+            (0.45, 0.8),
+            (3700.0, 5200.0),
+            (0.7, 0.96),
+            main_sequence(),
+        ),
+        kind(
+            MRedDwarfStar,
+            4000,
+            Some("M"),
+            (0.08, 0.45),
+            (2400.0, 3700.0),
+            (0.1, 0.7),
+            main_sequence(),
+        ),
+        kind(
+            LBrownDwarfStar,
+            800,
+            Some("L"),
+            (0.06, 0.08),
+            (1300.0, 2400.0),
+            (0.08, 0.12),
+            vec![V],
+        ),
+        kind(
+            TBrownDwarfStar,
+            600,
+            Some("T"),
+            (0.02, 0.06),
+            (550.0, 1300.0),
+            (0.08, 0.12),
+            vec![V],
+        ),
+        kind(
+            YBrownDwarfStar,
+            200,
+            Some("Y"),
+            (0.01, 0.02),
+            (250.0, 550.0),
+            (0.07, 0.1),
+            vec![V],
+        ),
+        kind(
+            TTauriStar,
+            200,
+            Some("TTS"),
+            (0.3, 2.0),
+            (3000.0, 6000.0),
+            (1.0, 5.0),
+            vec![Vz, V],
+        ),
+        kind(
+            HerbigAeBeStar,
+            40,
+            Some("AeBe"),
+            (2.0, 8.0),
+            (7000.0, 12_000.0),
+            (2.0, 5.0),
+            vec![Vz, V],
+        ),
+        kind(
+            KYellowOrangeGiantStar,
+            100,
+            Some("K"),
+            (0.8, 3.0),
+            (3700.0, 5000.0),
+            (10.0, 50.0),
+            vec![IIIa, IIIab, III],
+        ),
+        kind(
+            MRedGiantStar,
+            100,
+            Some("M"),
+            (0.8, 3.0),
+            (2600.0, 3700.0),
+            (50.0, 200.0),
+            vec![IIIa, IIIab, III],
+        ),
+        kind(
+            BBlueWhiteSuperGiantStar,
+            5,
+            Some("B"),
+            (10.0, 30.0),
+            (10_000.0, 30_000.0),
+            (30.0, 100.0),
+            vec![Ia, Iab, Ib],
+        ),
+        kind(
+            ABlueWhiteSuperGiantStar,
+            5,
+            Some("A"),
+            (8.0, 20.0),
+            (7500.0, 10_000.0),
+            (50.0, 200.0),
+            vec![Ia, Iab, Ib],
+        ),
+        kind(
+            FWhiteSuperGiantStar,
+            5,
+            Some("F"),
+            (8.0, 20.0),
+            (6000.0, 7500.0),
+            (100.0, 300.0),
+            vec![Ia, Iab, Ib],
+        ),
+        kind(
+            GWhiteYellowSuperGiantStar,
+            5,
+            Some("G"),
+            (8.0, 20.0),
+            (5200.0, 6000.0),
+            (100.0, 400.0),
+            vec![Ia, Iab, Ib],
+        ),
+        kind(
+            MRedSuperGiantStar,
+            5,
+            Some("M"),
+            (10.0, 30.0),
+            (2600.0, 3700.0),
+            (300.0, 1000.0),
+            vec![Ia, Iab, Ib],
+        ),
+        kind(
+            CStar,
+            10,
+            None,
+            (1.0, 3.0),
+            (2500.0, 3500.0),
+            (50.0, 300.0),
+            vec![III],
+        ),
+        kind(
+            WolfRayetStar,
+            5,
+            None,
+            (10.0, 25.0),
+            (30_000.0, 200_000.0),
+            (0.5, 2.0),
+            vec![V],
+        ),
+        kind(
+            WhiteDwarfDAStar,
+            150,
+            None,
+            (0.5, 1.0),
+            (5000.0, 40_000.0),
+            (0.008, 0.02),
+            vec![VII],
+        ),
+        kind(
+            WhiteDwarfDBStar,
+            30,
+            None,
+            (0.5, 1.0),
+            (12_000.0, 30_000.0),
+            (0.008, 0.02),
+            vec![VII],
+        ),
+        kind(
+            WhiteDwarfDCStar,
+            20,
+            None,
+            (0.5, 1.0),
+            (4000.0, 11_000.0),
+            (0.008, 0.02),
+            vec![VII],
+        ),
+        kind(
+            NeutronStar,
+            60,
+            None,
+            (1.2, 2.5),
+            (100_000.0, 10_000_000.0),
+            (0.000_01, 0.000_02),
+            vec![VII],
+        ),
+        kind(
+            BlackHole,
+            20,
+            None,
+            (3.0, 50.0),
+            (0.0, 0.0),
+            (0.000_01, 0.000_1),
+            Vec::new(),
+        ),
+    ]
+}
+
+/// Procedural name of the sector at the given index along x, y and z.
+fn sector_name(sector: [u64; 3]) -> String {
+    let hash = sector[0]
+        .wrapping_mul(0x9e37_79b9)
+        .wrapping_add(sector[1].wrapping_mul(0x85eb_ca6b))
+        .wrapping_add(sector[2].wrapping_mul(0xc2b2_ae35));
+    let word = |h: u64| {
+        let prefix = SECTOR_PREFIXES[(h % SECTOR_PREFIXES.len() as u64) as usize];
+        let suffix = SECTOR_SUFFIXES[(h / 97 % SECTOR_SUFFIXES.len() as u64) as usize];
+        format!("{}{}", prefix, suffix)
+    };
+    format!("{} {}", word(hash), word(hash >> 16))
+}
+
+/// `id64` of a body, which is the `id64` of its system with the `bodyId` in the upper 9 bits.
+fn body_id64(system: &SystemWithCoordinates, body_id: u64) -> u64 {
+    system.id64.unwrap() | body_id << 55
+}
+
+fn market_id(station_id: u64) -> u64 {
+    3_228_000_000 + station_id
+}
+
+fn is_gas_giant(sub_type: &PlanetSubType) -> bool {
+    use PlanetSubType::*;
+
+    matches!(
+        sub_type,
+        ClassIGasGiant
+            | ClassIiGasGiant
+            | ClassIiiGasGiant
+            | ClassIvGasGiant
+            | ClassVGasGiant
+            | GasGiantWithAmmoniaBasedLife
+            | GasGiantWithWaterBasedLife
+            | HeliumGasGiant
+            | HeliumRichGasGiant
+            | WaterGiant
+    )
+}
+
+fn station_in_populated(station: &Station) -> StationInPopulated {
+    StationInPopulated {
+        id: station.id,
+        allegiance: station.allegiance.clone(),
+        body: station.body.clone(),
+        controlling_faction: station.controlling_faction.clone(),
+        distance_to_arrival: station.distance_to_arrival,
+        economy: station.economy.clone(),
+        government: station.government.clone(),
+        have_market: station.have_market,
+        have_outfitting: station.have_outfitting,
+        have_shipyard: station.have_shipyard,
+        market_id: station.market_id,
+        name: station.name.clone(),
+        other_services: station.other_services.clone(),
+        second_economy: station.second_economy.clone(),
+        st_type: station.typ.clone(),
+        update_time: station.update_time.clone(),
+    }
+}
+
+fn some_if_any(parents: Vec<Parent>) -> Option<Vec<Parent>> {
+    if parents.is_empty() {
+        None
+    } else {
+        Some(parents)
+    }
+}
+
+fn lerp((min, max): (f64, f64), t: f64) -> f64 {
+    min + (max - min) * t
+}
+
+/// Rounds to `digits` decimal places, or to a multiple of `10^-digits` if negative.
+fn round(x: f64, digits: i32) -> f32 {
+    let scale = 10f64.powi(digits);
+    ((x * scale).round() / scale) as f32
+}
@@ -0,0 +1,596 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context, Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+use crate::compression::Members;
+use crate::compression::{check_supported, decompress, Compression, SeekPoint};
+use crate::error::ParseError;
+use crate::model::RootEntry;
+use crate::reader::{at_position, DumpReader, RecordLines};
+use crate::recovery::{ErrorReport, SkipErrors, DEFAULT_MAX_SAMPLES};
+use crate::sort::{ExternalSort, SortEntry};
+
+const MAGIC: &[u8; 8] = b"EDSMIDX2";
+const ENTRY_SIZE: u64 = 28;
+const ID64_SIZE: u64 = 16;
+const NO_ID64: u64 = u64::MAX;
+/// Size of the blocks at the start and the end of a dump hashed into its index.
+const HASHED_BLOCK_SIZE: u64 = 64 * 1024;
+
+/// Location of an entry in a dump file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub id: u64,
+    pub id64: Option<u64>,
+    /// Byte offset of the record in the decompressed dump.
+    pub offset: u64,
+    /// Length of the record, without surrounding whitespace and `,`.
+    pub len: u32,
+}
+
+impl SortEntry for IndexEntry {
+    fn parse_run_json_mut(bs: &mut [u8]) -> Result<IndexEntry> {
+        Ok(serde_json::from_slice(bs)?)
+    }
+}
+
+/// Position of an entry in `id` order, sorted by `id64` to write the `id64` table.
+#[derive(Serialize, Deserialize)]
+struct Id64Entry {
+    id64: u64,
+    ordinal: u64,
+}
+
+impl SortEntry for Id64Entry {
+    fn parse_run_json_mut(bs: &mut [u8]) -> Result<Id64Entry> {
+        Ok(serde_json::from_slice(bs)?)
+    }
+}
+
+/// The fields of a record read to index it.
+///
+/// These are what [`RootEntry::entry_id`] and [`RootEntry::entry_id64`] return for the model types.
+#[derive(Deserialize)]
+struct EntryIds {
+    id: u64,
+    #[serde(default)]
+    id64: Option<u64>,
+}
+
+/// Default path of the index of `dump`: the dump path with `.idx` appended.
+pub fn index_path<P: AsRef<Path>>(dump: P) -> PathBuf {
+    let mut path = dump.as_ref().as_os_str().to_owned();
+    path.push(".idx");
+    PathBuf::from(path)
+}
+
+/// Scans the dump file `dump` and writes an index of its entries to `index`, with the default [`IndexBuilder`].
+///
+/// Returns the number of indexed entries.
+pub fn build_index<T, P, Q>(dump: P, index: Q) -> Result<u64>
+where
+    T: RootEntry,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    Ok(IndexBuilder::new().build::<T, _, _>(dump, index)?.parsed)
+}
+
+/// Builder of dump indexes.
+///
+/// Only the `id` and `id64` fields of the records are parsed,
+/// and the entries are sorted with an [`ExternalSort`], so full dumps are indexed in bounded memory.
+///
+/// For compressed dumps, the index also records a [`SeekPoint`] at the start of
+/// each gzip member, bzip2 stream or zstd frame, so random access is only
+/// fast for dumps compressed in many small blocks.
+#[derive(Debug, Clone, Default)]
+pub struct IndexBuilder {
+    skip_errors: bool,
+    sort: ExternalSort,
+}
+
+impl IndexBuilder {
+    pub fn new() -> IndexBuilder {
+        IndexBuilder::default()
+    }
+
+    /// Leaves out the records failing to parse instead of failing, as [`DumpReader::skip_errors`] does.
+    ///
+    /// [`DumpReader::skip_errors`]: crate::reader::DumpReader::skip_errors
+    pub fn skip_errors(mut self, skip_errors: bool) -> IndexBuilder {
+        self.skip_errors = skip_errors;
+        self
+    }
+
+    /// Sets the sort of the entries, e.g. to change its temporary directory.
+    pub fn sort(mut self, sort: ExternalSort) -> IndexBuilder {
+        self.sort = sort;
+        self
+    }
+
+    /// Scans the dump file `dump` and writes an index of its entries to `index`.
+    ///
+    /// Returns the numbers of indexed and skipped records.
+    pub fn build<T, P, Q>(&self, dump: P, index: Q) -> Result<ErrorReport>
+    where
+        T: RootEntry,
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let dump = dump.as_ref();
+        let mut f = File::open(dump).with_context(|| format!("opening {}", dump.display()))?;
+        let dump_len = f.metadata()?.len();
+        let dump_hash =
+            hash_blocks(&mut f, dump_len).with_context(|| format!("reading {}", dump.display()))?;
+        f.seek(SeekFrom::Start(0))?;
+        let mut r = BufReader::new(f);
+        let compression = Compression::detect(r.fill_buf().context("reading magic bytes")?);
+        check_supported(compression)?;
+
+        let index = index.as_ref();
+        let f = File::create(index).with_context(|| format!("creating {}", index.display()))?;
+        let mut w = BufWriter::new(f);
+        let header = Header {
+            type_name: T::type_name(),
+            compression,
+            dump_len,
+            dump_hash,
+        };
+        let report = match compression {
+            Compression::None => {
+                self.write(&header, &mut RecordLines::new(r), &mut w, |_| Vec::new())
+            }
+            #[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+            _ => {
+                let members = Members::new(compression, r, SeekPoint::default())?;
+                let mut lines = RecordLines::new(BufReader::new(members));
+                self.write(&header, &mut lines, &mut w, |lines| {
+                    lines.get_ref().get_ref().seek_points().to_vec()
+                })
+            }
+            #[cfg(not(any(feature = "gzip", feature = "bzip2", feature = "zstd")))]
+            _ => unreachable!("compressed input is not supported"),
+        };
+        report
+            .and_then(|report| {
+                w.flush()?;
+                Ok(report)
+            })
+            .with_context(|| format!("writing {}", index.display()))
+    }
+
+    /// Sorts the entries of `lines` and writes the index, with the seek points taken from `lines` once read.
+    fn write<R: BufRead, W: Write>(
+        &self,
+        header: &Header,
+        lines: &mut RecordLines<R>,
+        w: &mut W,
+        seek_points: impl FnOnce(&RecordLines<R>) -> Vec<SeekPoint>,
+    ) -> Result<ErrorReport> {
+        let mut report = ErrorReport::new(header.type_name);
+        let mut id64s_len = 0;
+        let entries = self.sort.sort_by_key(
+            Scan {
+                lines: &mut *lines,
+                buf: Vec::new(),
+                skip_errors: self.skip_errors,
+                report: &mut report,
+            }
+            .inspect(|entry| {
+                if matches!(entry, Ok(IndexEntry { id64: Some(_), .. })) {
+                    id64s_len += 1;
+                }
+            }),
+            |e| e.id,
+        )?;
+        let seek_points = seek_points(lines);
+        header.write_to(w, &seek_points, report.parsed, id64s_len)?;
+
+        let mut ordinal = 0;
+        let id64s = entries.filter_map(|entry| {
+            let entry = match entry.and_then(|e| write_entry(w, &e).map(|_| e)) {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+            ordinal += 1;
+            entry.id64.map(|id64| {
+                Ok(Id64Entry {
+                    id64,
+                    ordinal: ordinal - 1,
+                })
+            })
+        });
+        for e in self.sort.sort_by_key(id64s, |e| e.id64)? {
+            let e = e?;
+            w.write_all(&e.id64.to_le_bytes())?;
+            w.write_all(&e.ordinal.to_le_bytes())?;
+        }
+        Ok(report)
+    }
+}
+
+/// Iterator over the index entries of the records of a dump.
+struct Scan<'a, R> {
+    lines: &'a mut RecordLines<R>,
+    buf: Vec<u8>,
+    skip_errors: bool,
+    report: &'a mut ErrorReport,
+}
+
+impl<'a, R: BufRead> Iterator for Scan<'a, R> {
+    type Item = Result<IndexEntry>;
+
+    fn next(&mut self) -> Option<Result<IndexEntry>> {
+        loop {
+            self.buf.clear();
+            let (position, range) = match self.lines.read_into(&mut self.buf) {
+                Ok(Some(record)) => record,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            let len = match u32::try_from(range.len()) {
+                Ok(len) => len,
+                Err(e) => return Some(Err(Error::new(e).context("record too long"))),
+            };
+            let record = &self.buf[range.clone()];
+            match serde_json::from_slice::<EntryIds>(record) {
+                Ok(ids) => {
+                    self.report.parsed += 1;
+                    return Some(Ok(IndexEntry {
+                        id: ids.id,
+                        id64: ids.id64,
+                        offset: position.offset + range.start as u64,
+                        len,
+                    }));
+                }
+                Err(e) => {
+                    let e = ParseError::new::<EntryIds>(self.report.type_name, record, e).into();
+                    if !self.skip_errors {
+                        return Some(Err(at_position(e, position)));
+                    }
+                    self.report
+                        .record_error(DEFAULT_MAX_SAMPLES, position, record, e);
+                }
+            }
+        }
+    }
+}
+
+/// Fields of the index header, identifying the indexed dump.
+struct Header {
+    type_name: &'static str,
+    compression: Compression,
+    dump_len: u64,
+    dump_hash: u64,
+}
+
+impl Header {
+    fn write_to<W: Write>(
+        &self,
+        w: &mut W,
+        seek_points: &[SeekPoint],
+        entries_len: u64,
+        id64s_len: u64,
+    ) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[
+            compression_code(self.compression),
+            self.type_name.len() as u8,
+        ])?;
+        w.write_all(self.type_name.as_bytes())?;
+        for n in &[
+            self.dump_len,
+            self.dump_hash,
+            seek_points.len() as u64,
+            entries_len,
+            id64s_len,
+        ] {
+            w.write_all(&n.to_le_bytes())?;
+        }
+        for p in seek_points {
+            w.write_all(&p.compressed.to_le_bytes())?;
+            w.write_all(&p.uncompressed.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+fn write_entry<W: Write>(w: &mut W, e: &IndexEntry) -> Result<()> {
+    w.write_all(&e.id.to_le_bytes())?;
+    w.write_all(&e.id64.unwrap_or(NO_ID64).to_le_bytes())?;
+    w.write_all(&e.offset.to_le_bytes())?;
+    w.write_all(&e.len.to_le_bytes())?;
+    Ok(())
+}
+
+/// FNV-1a hash of the first and the last [`HASHED_BLOCK_SIZE`] bytes of the file `f` of `len` bytes,
+/// telling a dump from another one of the same length.
+fn hash_blocks(f: &mut File, len: u64) -> io::Result<u64> {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut buf = Vec::new();
+    for start in [0, len.saturating_sub(HASHED_BLOCK_SIZE)] {
+        f.seek(SeekFrom::Start(start))?;
+        buf.clear();
+        Read::by_ref(f)
+            .take(HASHED_BLOCK_SIZE)
+            .read_to_end(&mut buf)?;
+        for b in &buf {
+            hash = (hash ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    Ok(hash)
+}
+
+fn compression_code(compression: Compression) -> u8 {
+    match compression {
+        Compression::None => 0,
+        Compression::Gzip => 1,
+        Compression::Bzip2 => 2,
+        Compression::Zstd => 3,
+    }
+}
+
+fn compression_from_code(code: u8) -> Option<Compression> {
+    match code {
+        0 => Some(Compression::None),
+        1 => Some(Compression::Gzip),
+        2 => Some(Compression::Bzip2),
+        3 => Some(Compression::Zstd),
+        _ => None,
+    }
+}
+
+/// Dump file with random access to its entries through an index built by [`build_index`].
+///
+/// Lookups binary-search the index file without loading it into memory.
+pub struct IndexedDump<T> {
+    dump: File,
+    index: File,
+    compression: Compression,
+    seek_points: Vec<SeekPoint>,
+    entries_start: u64,
+    entries_len: u64,
+    id64s_start: u64,
+    id64s_len: u64,
+    buf: Vec<u8>,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<T: RootEntry> IndexedDump<T> {
+    pub fn open<P: AsRef<Path>, Q: AsRef<Path>>(dump: P, index: Q) -> Result<IndexedDump<T>> {
+        let (dump, index) = (dump.as_ref(), index.as_ref());
+        let dump_file = File::open(dump).with_context(|| format!("opening {}", dump.display()))?;
+        let index_file =
+            File::open(index).with_context(|| format!("opening {}", index.display()))?;
+
+        let mut r = BufReader::new(&index_file);
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)
+            .with_context(|| format!("reading {}", index.display()))?;
+        ensure!(&magic == MAGIC, "{} is not a dump index", index.display());
+
+        let mut bs = [0u8; 2];
+        r.read_exact(&mut bs)?;
+        let compression = compression_from_code(bs[0])
+            .with_context(|| format!("unknown compression in {}", index.display()))?;
+        check_supported(compression)?;
+        let mut type_name = vec![0u8; bs[1] as usize];
+        r.read_exact(&mut type_name)?;
+        if type_name != T::type_name().as_bytes() {
+            bail!(
+                "{} indexes {} entries, not {}",
+                index.display(),
+                String::from_utf8_lossy(&type_name),
+                T::type_name()
+            );
+        }
+
+        let dump_len = read_u64(&mut r)?;
+        let dump_hash = read_u64(&mut r)?;
+        let mut dump_file = dump_file;
+        let actual_len = dump_file.metadata()?.len();
+        ensure!(
+            dump_len == actual_len && dump_hash == hash_blocks(&mut dump_file, actual_len)?,
+            "{} is outdated for {}",
+            index.display(),
+            dump.display()
+        );
+        let seek_points_len = read_u64(&mut r)?;
+        let entries_len = read_u64(&mut r)?;
+        let id64s_len = read_u64(&mut r)?;
+        let seek_points = (0..seek_points_len)
+            .map(|_| {
+                Ok(SeekPoint {
+                    compressed: read_u64(&mut r)?,
+                    uncompressed: read_u64(&mut r)?,
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()
+            .with_context(|| format!("reading {}", index.display()))?;
+
+        let entries_start =
+            (MAGIC.len() + 2 + type_name.len() + 5 * 8) as u64 + seek_points_len * 16;
+        let id64s_start = entries_start + entries_len * ENTRY_SIZE;
+        Ok(IndexedDump {
+            dump: dump_file,
+            index: index_file,
+            compression,
+            seek_points,
+            entries_start,
+            entries_len,
+            id64s_start,
+            id64s_len,
+            buf: Vec::new(),
+            _entry: PhantomData,
+        })
+    }
+
+    /// Number of indexed entries.
+    pub fn len(&self) -> u64 {
+        self.entries_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries_len == 0
+    }
+
+    /// Seek points of a compressed dump.
+    pub fn seek_points(&self) -> &[SeekPoint] {
+        &self.seek_points
+    }
+
+    /// Whether records are read without decoding, i.e. the dump is uncompressed.
+    ///
+    /// Random reads in a compressed dump decode from the previous seek point,
+    /// which is quadratic in the size of the members.
+    pub(crate) fn is_seekable(&self) -> bool {
+        self.compression == Compression::None
+    }
+
+    /// Reads the dump from the start, skipping the records left out of the index.
+    pub(crate) fn entries(&self) -> Result<SkipErrors<Box<dyn BufRead + Send>, T>> {
+        let mut dump = self.dump.try_clone()?;
+        dump.seek(SeekFrom::Start(0))?;
+        let r = decompress(BufReader::new(dump))?;
+        Ok(DumpReader::new(r).skip_errors().max_samples(0))
+    }
+
+    /// The `i`-th entry in `id` order, for `i` below [`len`](IndexedDump::len).
+    pub fn entry_at(&mut self, i: u64) -> Result<IndexEntry> {
+        let mut bs = [0u8; ENTRY_SIZE as usize];
+        self.index
+            .seek(SeekFrom::Start(self.entries_start + i * ENTRY_SIZE))?;
+        self.index.read_exact(&mut bs).context("reading index")?;
+        let id64 = u64_at(&bs, 8);
+        Ok(IndexEntry {
+            id: u64_at(&bs, 0),
+            id64: if id64 == NO_ID64 { None } else { Some(id64) },
+            offset: u64_at(&bs, 16),
+            len: u32::from_le_bytes([bs[24], bs[25], bs[26], bs[27]]),
+        })
+    }
+
+    /// Looks up the entry with [`RootEntry::entry_id`] `id`.
+    ///
+    /// If several entries share `id`, the first one in the dump is returned.
+    pub fn find(&mut self, id: u64) -> Result<Option<IndexEntry>> {
+        let i = self.lower_bound(self.entries_len, |this, i| Ok(this.entry_at(i)?.id < id))?;
+        if i < self.entries_len {
+            let entry = self.entry_at(i)?;
+            if entry.id == id {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Looks up the entry with [`RootEntry::entry_id64`] `id64`, like [`find`](IndexedDump::find).
+    pub fn find_id64(&mut self, id64: u64) -> Result<Option<IndexEntry>> {
+        let i = self.lower_bound(self.id64s_len, |this, i| Ok(this.id64_at(i)?.0 < id64))?;
+        if i < self.id64s_len {
+            let (found, ordinal) = self.id64_at(i)?;
+            if found == id64 {
+                return self.entry_at(ordinal).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn get(&mut self, id: u64) -> Result<Option<T>> {
+        match self.find(id)? {
+            Some(entry) => self.read_entry(&entry).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_by_id64(&mut self, id64: u64) -> Result<Option<T>> {
+        match self.find_id64(id64)? {
+            Some(entry) => self.read_entry(&entry).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads and parses the record at `entry`.
+    pub fn read_entry(&mut self, entry: &IndexEntry) -> Result<T> {
+        self.buf.resize(entry.len as usize, 0);
+        self.read_record(entry.offset)
+            .with_context(|| format!("reading record at offset {}", entry.offset))?;
+        T::parse_dump_json_mut(&mut self.buf)
+    }
+
+    fn read_record(&mut self, offset: u64) -> Result<()> {
+        if self.compression == Compression::None {
+            self.dump.seek(SeekFrom::Start(offset))?;
+            self.dump.read_exact(&mut self.buf)?;
+            return Ok(());
+        }
+        self.read_compressed_record(offset)
+    }
+
+    #[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+    fn read_compressed_record(&mut self, offset: u64) -> Result<()> {
+        let i = self
+            .seek_points
+            .partition_point(|p| p.uncompressed <= offset);
+        let point = match i.checked_sub(1) {
+            Some(i) => self.seek_points[i],
+            None => bail!("no seek point before offset {}", offset),
+        };
+
+        self.dump.seek(SeekFrom::Start(point.compressed))?;
+        let mut members = Members::new(self.compression, BufReader::new(&self.dump), point)?;
+        let skip = offset - point.uncompressed;
+        let skipped = io::copy(&mut (&mut members).take(skip), &mut io::sink())?;
+        ensure!(skipped == skip, "dump ended before offset {}", offset);
+        members.read_exact(&mut self.buf)?;
+        Ok(())
+    }
+
+    #[cfg(not(any(feature = "gzip", feature = "bzip2", feature = "zstd")))]
+    fn read_compressed_record(&mut self, _offset: u64) -> Result<()> {
+        unreachable!("compressed input is not supported")
+    }
+
+    fn lower_bound<F>(&mut self, len: u64, mut is_before: F) -> Result<u64>
+    where
+        F: FnMut(&mut Self, u64) -> Result<bool>,
+    {
+        let (mut lo, mut hi) = (0, len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if is_before(self, mid)? {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        Ok(lo)
+    }
+
+    fn id64_at(&mut self, i: u64) -> Result<(u64, u64)> {
+        let mut bs = [0u8; ID64_SIZE as usize];
+        self.index
+            .seek(SeekFrom::Start(self.id64s_start + i * ID64_SIZE))?;
+        self.index.read_exact(&mut bs).context("reading index")?;
+        Ok((u64_at(&bs, 0), u64_at(&bs, 8)))
+    }
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut bs = [0u8; 8];
+    r.read_exact(&mut bs)?;
+    Ok(u64::from_le_bytes(bs))
+}
+
+fn u64_at(bs: &[u8], at: usize) -> u64 {
+    let mut n = [0u8; 8];
+    n.copy_from_slice(&bs[at..at + 8]);
+    u64::from_le_bytes(n)
+}
@@ -0,0 +1,300 @@
+use std::iter::Peekable;
+
+use anyhow::{anyhow, Result};
+
+use crate::model::body::{Body, BodyT};
+use crate::model::projection::{BodyBrief, SystemBrief};
+use crate::model::station::Station;
+use crate::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use crate::model::system_populated::SystemPopulated;
+
+/// `id64` of the system of an entry, used as the key of [`join_systems`].
+///
+/// For systems, this is their own `id64`; for bodies and stations, the `systemId64`.
+pub trait SystemKey {
+    fn system_key(&self) -> Option<u64>;
+}
+
+impl SystemKey for SystemWithCoordinates {
+    fn system_key(&self) -> Option<u64> {
+        self.id64
+    }
+}
+
+impl SystemKey for SystemWithoutCoordinates {
+    fn system_key(&self) -> Option<u64> {
+        self.id64
+    }
+}
+
+impl SystemKey for SystemPopulated {
+    fn system_key(&self) -> Option<u64> {
+        self.id64
+    }
+}
+
+impl SystemKey for SystemBrief {
+    fn system_key(&self) -> Option<u64> {
+        self.id64
+    }
+}
+
+impl SystemKey for Body {
+    fn system_key(&self) -> Option<u64> {
+        self.system_id64()
+    }
+}
+
+impl SystemKey for BodyBrief {
+    fn system_key(&self) -> Option<u64> {
+        self.system_id64()
+    }
+}
+
+impl SystemKey for Station {
+    fn system_key(&self) -> Option<u64> {
+        self.system_id64
+    }
+}
+
+/// Streaming join of two entry streams sorted by key, such as the [`Sorted`](crate::sort::Sorted) entries of an
+/// [`ExternalSort`](crate::sort::ExternalSort).
+///
+/// Each left entry is yielded with the right entries having the same key, in their order.
+/// Left entries without a key or without matching right entries are yielded with none.
+/// Right entries without a key or without a matching left entry are skipped, see [`unmatched`](MergeJoin::unmatched);
+/// those after the last left entry are read once the left stream ends.
+/// If several left entries share a key, the first one gets all the matching right entries.
+///
+/// Both streams must be sorted by ascending key, entries without a key first, as [`Option`] orders them.
+/// An out of order entry is reported as an error.
+pub struct MergeJoin<IL, IR: Iterator, FL, FR, K> {
+    left: IL,
+    right: Peekable<IR>,
+    left_key: FL,
+    right_key: FR,
+    last_left: Option<K>,
+    last_right: Option<K>,
+    unmatched: u64,
+}
+
+/// Joins `left` and `right` on the keys given by `left_key` and `right_key`, see [`MergeJoin`].
+pub fn merge_join<L, R, K, IL, IR, FL, FR>(
+    left: IL,
+    left_key: FL,
+    right: IR,
+    right_key: FR,
+) -> MergeJoin<IL::IntoIter, IR::IntoIter, FL, FR, K>
+where
+    K: Ord,
+    IL: IntoIterator<Item = Result<L>>,
+    IR: IntoIterator<Item = Result<R>>,
+    FL: FnMut(&L) -> Option<K>,
+    FR: FnMut(&R) -> Option<K>,
+{
+    MergeJoin {
+        left: left.into_iter(),
+        right: right.into_iter().peekable(),
+        left_key,
+        right_key,
+        last_left: None,
+        last_right: None,
+        unmatched: 0,
+    }
+}
+
+impl<IL, IR: Iterator, FL, FR, K> MergeJoin<IL, IR, FL, FR, K> {
+    /// Number of right entries skipped so far, as no left entry has their key.
+    pub fn unmatched(&self) -> u64 {
+        self.unmatched
+    }
+}
+
+impl<L, R, K, IL, IR, FL, FR> MergeJoin<IL, IR, FL, FR, K>
+where
+    K: Ord,
+    IL: Iterator<Item = Result<L>>,
+    IR: Iterator<Item = Result<R>>,
+    FL: FnMut(&L) -> Option<K>,
+    FR: FnMut(&R) -> Option<K>,
+{
+    /// Takes the right entries with a key up to `key`, or all of them if `None`, returning those equal to it.
+    fn take_right(&mut self, key: Option<&K>) -> Result<Vec<R>> {
+        let mut matched = Vec::new();
+        loop {
+            let right_key = match self.right.peek() {
+                None => return Ok(matched),
+                Some(Ok(entry)) => (self.right_key)(entry),
+                Some(Err(_)) => {
+                    return match self.right.next() {
+                        Some(Err(e)) => Err(e),
+                        _ => unreachable!(),
+                    }
+                }
+            };
+            let right_key = match right_key {
+                None if self.last_right.is_some() => {
+                    return Err(anyhow!("right entries are not sorted by key"))
+                }
+                None => {
+                    self.right.next();
+                    self.unmatched += 1;
+                    continue;
+                }
+                Some(k) if matches!(key, Some(key) if &k > key) => return Ok(matched),
+                Some(k) => k,
+            };
+            if matches!(&self.last_right, Some(last) if last > &right_key) {
+                return Err(anyhow!("right entries are not sorted by key"));
+            }
+
+            let entry = self.right.next().unwrap()?;
+            if key == Some(&right_key) {
+                matched.push(entry);
+            } else {
+                self.unmatched += 1;
+            }
+            self.last_right = Some(right_key);
+        }
+    }
+}
+
+impl<L, R, K, IL, IR, FL, FR> Iterator for MergeJoin<IL, IR, FL, FR, K>
+where
+    K: Ord,
+    IL: Iterator<Item = Result<L>>,
+    IR: Iterator<Item = Result<R>>,
+    FL: FnMut(&L) -> Option<K>,
+    FR: FnMut(&R) -> Option<K>,
+{
+    type Item = Result<(L, Vec<R>)>;
+
+    fn next(&mut self) -> Option<Result<(L, Vec<R>)>> {
+        let entry = match self.left.next() {
+            Some(Ok(entry)) => entry,
+            Some(Err(e)) => return Some(Err(e)),
+            None => {
+                // Count the remaining right entries, once
+                if self.right.peek().is_some() {
+                    if let Err(e) = self.take_right(None) {
+                        return Some(Err(e));
+                    }
+                }
+                return None;
+            }
+        };
+        let key = match (self.left_key)(&entry) {
+            None if self.last_left.is_some() => {
+                return Some(Err(anyhow!("left entries are not sorted by key")))
+            }
+            None => return Some(Ok((entry, Vec::new()))),
+            Some(key) => key,
+        };
+        if matches!(&self.last_left, Some(last) if last > &key) {
+            return Some(Err(anyhow!("left entries are not sorted by key")));
+        }
+        // Right entries of a duplicate key went to the first left entry
+        let matched = if self.last_left.as_ref() == Some(&key) {
+            Ok(Vec::new())
+        } else {
+            self.take_right(Some(&key))
+        };
+        self.last_left = Some(key);
+        Some(matched.map(|matched| (entry, matched)))
+    }
+}
+
+/// A system with its bodies and stations, yielded by [`join_systems`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemEntries<S, B, T> {
+    pub system: S,
+    pub bodies: Vec<B>,
+    pub stations: Vec<T>,
+}
+
+type SystemBodies<S, B, IS, IB> =
+    MergeJoin<IS, IB, fn(&S) -> Option<u64>, fn(&B) -> Option<u64>, u64>;
+
+type SystemStations<S, B, T, IS, IB, IT> = MergeJoin<
+    SystemBodies<S, B, IS, IB>,
+    IT,
+    fn(&(S, Vec<B>)) -> Option<u64>,
+    fn(&T) -> Option<u64>,
+    u64,
+>;
+
+/// Iterator of [`join_systems`].
+pub struct SystemJoin<S, B, T, IS, IB: Iterator, IT: Iterator> {
+    inner: SystemStations<S, B, T, IS, IB, IT>,
+}
+
+/// Joins systems with their bodies and stations, all sorted by [`SystemKey`].
+///
+/// This reads each stream once, so it handles full dumps sorted with an [`ExternalSort`](crate::sort::ExternalSort)
+/// by [`SystemKey::system_key`].
+/// Bodies and stations of systems not in `systems` are skipped.
+pub fn join_systems<S, B, T, IS, IB, IT>(
+    systems: IS,
+    bodies: IB,
+    stations: IT,
+) -> SystemJoin<S, B, T, IS::IntoIter, IB::IntoIter, IT::IntoIter>
+where
+    S: SystemKey,
+    B: SystemKey,
+    T: SystemKey,
+    IS: IntoIterator<Item = Result<S>>,
+    IB: IntoIterator<Item = Result<B>>,
+    IT: IntoIterator<Item = Result<T>>,
+{
+    let with_bodies = merge_join(
+        systems,
+        S::system_key as fn(&S) -> Option<u64>,
+        bodies,
+        B::system_key as fn(&B) -> Option<u64>,
+    );
+    SystemJoin {
+        inner: merge_join(
+            with_bodies,
+            system_with_bodies_key::<S, B> as fn(&(S, Vec<B>)) -> Option<u64>,
+            stations,
+            T::system_key as fn(&T) -> Option<u64>,
+        ),
+    }
+}
+
+fn system_with_bodies_key<S: SystemKey, B>((system, _): &(S, Vec<B>)) -> Option<u64> {
+    system.system_key()
+}
+
+impl<S, B, T, IS, IB: Iterator, IT: Iterator> SystemJoin<S, B, T, IS, IB, IT> {
+    /// Number of bodies skipped so far, as their system is not in the systems.
+    pub fn unmatched_bodies(&self) -> u64 {
+        self.inner.left.unmatched()
+    }
+
+    /// Number of stations skipped so far, as their system is not in the systems.
+    pub fn unmatched_stations(&self) -> u64 {
+        self.inner.unmatched()
+    }
+}
+
+impl<S, B, T, IS, IB, IT> Iterator for SystemJoin<S, B, T, IS, IB, IT>
+where
+    IS: Iterator<Item = Result<S>>,
+    IB: Iterator<Item = Result<B>>,
+    IT: Iterator<Item = Result<T>>,
+{
+    type Item = Result<SystemEntries<S, B, T>>;
+
+    fn next(&mut self) -> Option<Result<SystemEntries<S, B, T>>> {
+        Some(
+            self.inner
+                .next()?
+                .map(|((system, bodies), stations)| SystemEntries {
+                    system,
+                    bodies,
+                    stations,
+                }),
+        )
+    }
+}
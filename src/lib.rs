@@ -1 +1,24 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod binary;
+#[cfg(feature = "type_hash")]
+pub mod cache;
+pub mod compression;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod diff;
+pub mod error;
+#[cfg(feature = "generator")]
+pub mod generator;
+pub mod index;
+pub mod join;
+pub mod merge;
 pub mod model;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod reader;
+pub mod recovery;
+pub mod sort;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod writer;
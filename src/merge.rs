@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::model::RootEntry;
+use crate::reader::DumpReader;
+use crate::writer::DumpWriter;
+
+/// Counts of a [`merge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+pub struct MergeStats {
+    /// Base entries without a newer delta entry.
+    pub kept: u64,
+    /// Delta entries with an `id` not in the base.
+    pub inserted: u64,
+    /// Delta entries replacing an older base entry.
+    pub replaced: u64,
+    /// Delta entries not newer than the base entry or another delta entry.
+    pub skipped: u64,
+}
+
+/// Applies `deltas` to the `base` snapshot, writing the merged snapshot to `out` with a [`DumpWriter`].
+///
+/// Entries are matched by [`RootEntry::entry_id`], and the one with the later
+/// [`RootEntry::time`] wins. Merged entries keep the order of `base`, and
+/// inserted entries follow in `id` order.
+/// Deltas are held in memory, while `base` is streamed.
+pub fn merge<T, B, D, W>(base: B, deltas: D, out: W) -> Result<MergeStats>
+where
+    T: RootEntry,
+    B: IntoIterator<Item = Result<T>>,
+    D: IntoIterator<Item = Result<T>>,
+    W: Write,
+{
+    let mut stats = MergeStats::default();
+
+    let mut updates: HashMap<u64, T> = HashMap::new();
+    for entry in deltas {
+        let entry = entry.context("reading delta")?;
+        match updates.get(&entry.entry_id()) {
+            Some(current) if current.time() >= entry.time() => stats.skipped += 1,
+            Some(_) => {
+                stats.skipped += 1;
+                updates.insert(entry.entry_id(), entry);
+            }
+            None => {
+                updates.insert(entry.entry_id(), entry);
+            }
+        }
+    }
+
+    let mut w = DumpWriter::new(out);
+    for entry in base {
+        let entry = entry.context("reading base")?;
+        match updates.remove(&entry.entry_id()) {
+            Some(update) if update.time() > entry.time() => {
+                stats.replaced += 1;
+                w.write(&update)?;
+            }
+            Some(_) => {
+                stats.skipped += 1;
+                stats.kept += 1;
+                w.write(&entry)?;
+            }
+            None => {
+                stats.kept += 1;
+                w.write(&entry)?;
+            }
+        }
+    }
+
+    let mut inserts: Vec<T> = updates.into_values().collect();
+    inserts.sort_by_key(|entry| entry.entry_id());
+    for entry in &inserts {
+        stats.inserted += 1;
+        w.write(entry)?;
+    }
+    w.finish()?;
+
+    Ok(stats)
+}
+
+/// Same as [`merge`], reading dump files and writing an uncompressed dump file.
+pub fn merge_files<T, P, Q, O>(base: P, deltas: &[Q], out: O) -> Result<MergeStats>
+where
+    T: RootEntry,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    O: AsRef<Path>,
+{
+    let base = DumpReader::<_, T>::open(base)?;
+    let deltas = deltas
+        .iter()
+        .map(DumpReader::<_, T>::open)
+        .collect::<Result<Vec<_>>>()?;
+
+    let out = out.as_ref();
+    let f = File::create(out).with_context(|| format!("creating {}", out.display()))?;
+    merge(base, deltas.into_iter().flatten(), BufWriter::new(f))
+        .with_context(|| format!("writing {}", out.display()))
+}
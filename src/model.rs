@@ -1,31 +1,78 @@
 #![allow(clippy::upper_case_acronyms)]
 
+pub mod any;
 pub mod bgs;
 pub mod body;
+pub mod lenient;
 pub mod powerplay;
+pub mod projection;
 pub mod station;
 pub mod system;
 pub mod system_populated;
 
+#[cfg(feature = "arbitrary")]
+pub(crate) mod arb;
 #[macro_use]
-mod util;
-mod dec;
+pub(crate) mod util;
+pub(crate) mod dec;
 
 use std::borrow::Cow;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::from_slice;
 
+use crate::error::ParseError;
+#[cfg(feature = "simd-json")]
+use crate::error::{locate, locate_in};
+use lenient::Extra;
+
 pub trait RootEntry: 'static + Send + Sync + DeserializeOwned + Serialize {
     fn entry_id(&self) -> u64;
+    /// `id64` of the entry, for the types having one.
+    fn entry_id64(&self) -> Option<u64> {
+        None
+    }
     fn type_name() -> &'static str;
     fn time(&self) -> DateTime<Utc>;
 
+    /// Parses a dump record, failing with a [`ParseError`].
+    #[cfg(not(feature = "simd-json"))]
+    fn parse_dump_json(bs: &[u8]) -> Result<Self> {
+        from_slice(bs).map_err(|e| ParseError::new::<Self>(Self::type_name(), bs, e).into())
+    }
+
+    /// Parses a dump record, failing with a [`ParseError`].
+    #[cfg(feature = "simd-json")]
     fn parse_dump_json(bs: &[u8]) -> Result<Self> {
-        from_slice(bs).context("parsing entry")
+        Self::parse_dump_json_mut(&mut bs.to_vec()).map_err(|e| locate_in(e, bs, locate::<Self>))
+    }
+
+    /// Same as [`parse_dump_json`](RootEntry::parse_dump_json), but may use `bs` as scratch space.
+    ///
+    /// With the `simd-json` feature, `bs` is overwritten during parsing,
+    /// so the [`ParseError`] has no `id`, `path` or `value`.
+    /// Types overriding `parse_dump_json` should override this too.
+    #[cfg(not(feature = "simd-json"))]
+    fn parse_dump_json_mut(bs: &mut [u8]) -> Result<Self> {
+        Self::parse_dump_json(bs)
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse_dump_json_mut(bs: &mut [u8]) -> Result<Self> {
+        simd_json::serde::from_slice(bs)
+            .map_err(|e| ParseError::from_simd(Self::type_name(), e).into())
+    }
+
+    /// Same as [`parse_dump_json`](RootEntry::parse_dump_json), but moves the fields not known to the model
+    /// to the returned [`Extra`] instead of failing, see [`lenient`].
+    ///
+    /// Types overriding `parse_dump_json` should override this too.
+    fn parse_dump_json_lenient(bs: &[u8]) -> Result<(Self, Extra)> {
+        let value = lenient::to_value(Self::type_name(), bs)?;
+        lenient::strip_unknown(Self::type_name(), value)
     }
 
     #[deprecated = "use parse_dump_json()"]
@@ -33,3 +80,15 @@ pub trait RootEntry: 'static + Send + Sync + DeserializeOwned + Serialize {
         Cow::Borrowed(s)
     }
 }
+
+/// Borrowed counterpart of a [`RootEntry`], deserialized without copying strings.
+pub trait RootEntryRef<'a>: Sized + Deserialize<'a> + Serialize {
+    type Owned: RootEntry;
+
+    fn parse_dump_json(bs: &'a [u8]) -> Result<Self> {
+        from_slice(bs)
+            .map_err(|e| ParseError::new::<Self::Owned>(Self::Owned::type_name(), bs, e).into())
+    }
+
+    fn to_owned_entry(&self) -> Self::Owned;
+}
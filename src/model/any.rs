@@ -0,0 +1,212 @@
+use std::fmt;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use strum::{EnumIter, IntoEnumIterator};
+
+use super::body::Body;
+use super::powerplay::PowerPlay;
+use super::station::Station;
+use super::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use super::system_populated::SystemPopulated;
+use super::RootEntry;
+
+/// Kind of an EDSM dump file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
+pub enum DumpKind {
+    Body,
+    PowerPlay,
+    Station,
+    SystemPopulated,
+    SystemWithCoordinates,
+    SystemWithoutCoordinates,
+}
+
+impl DumpKind {
+    /// Detects the kind from an EDSM dump file name, e.g. `bodies7days.json.gz`.
+    pub fn from_file_name(name: &str) -> Option<DumpKind> {
+        let stem = [".gz", ".bz2", ".zst"]
+            .iter()
+            .find_map(|ext| name.strip_suffix(ext))
+            .unwrap_or(name);
+        let stem = stem.strip_suffix(".json")?;
+        let stem = stem.strip_suffix("7days").unwrap_or(stem);
+        DumpKind::iter().find(|kind| kind.file_stem() == stem)
+    }
+
+    /// Detects the kind from the file name of `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<DumpKind> {
+        path.as_ref()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(DumpKind::from_file_name)
+    }
+
+    /// Name of the full dump file on EDSM.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            DumpKind::Body => "bodies.json",
+            DumpKind::PowerPlay => "powerPlay.json",
+            DumpKind::Station => "stations.json",
+            DumpKind::SystemPopulated => "systemsPopulated.json",
+            DumpKind::SystemWithCoordinates => "systemsWithCoordinates.json",
+            DumpKind::SystemWithoutCoordinates => "systemsWithoutCoordinates.json",
+        }
+    }
+
+    /// Name of the dump file of the entries updated in the last 7 days on EDSM, for the kinds that have one.
+    pub fn seven_days_file_name(self) -> Option<&'static str> {
+        match self {
+            DumpKind::Body => Some("bodies7days.json"),
+            DumpKind::SystemWithCoordinates => Some("systemsWithCoordinates7days.json"),
+            _ => None,
+        }
+    }
+
+    fn file_stem(self) -> &'static str {
+        match self {
+            DumpKind::Body => "bodies",
+            DumpKind::PowerPlay => "powerPlay",
+            DumpKind::Station => "stations",
+            DumpKind::SystemPopulated => "systemsPopulated",
+            DumpKind::SystemWithCoordinates => "systemsWithCoordinates",
+            DumpKind::SystemWithoutCoordinates => "systemsWithoutCoordinates",
+        }
+    }
+
+    /// [`RootEntry::type_name`] of the entries.
+    pub fn type_name(self) -> &'static str {
+        match self {
+            DumpKind::Body => Body::type_name(),
+            DumpKind::PowerPlay => PowerPlay::type_name(),
+            DumpKind::Station => Station::type_name(),
+            DumpKind::SystemPopulated => SystemPopulated::type_name(),
+            DumpKind::SystemWithCoordinates => SystemWithCoordinates::type_name(),
+            DumpKind::SystemWithoutCoordinates => SystemWithoutCoordinates::type_name(),
+        }
+    }
+
+    pub fn parse_dump_json(self, bs: &[u8]) -> Result<AnyEntry> {
+        Ok(match self {
+            DumpKind::Body => Body::parse_dump_json(bs)?.into(),
+            DumpKind::PowerPlay => PowerPlay::parse_dump_json(bs)?.into(),
+            DumpKind::Station => Station::parse_dump_json(bs)?.into(),
+            DumpKind::SystemPopulated => SystemPopulated::parse_dump_json(bs)?.into(),
+            DumpKind::SystemWithCoordinates => SystemWithCoordinates::parse_dump_json(bs)?.into(),
+            DumpKind::SystemWithoutCoordinates => {
+                SystemWithoutCoordinates::parse_dump_json(bs)?.into()
+            }
+        })
+    }
+
+    /// JSON Schema of the records of this kind of dump.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(self) -> schemars::schema::RootSchema {
+        use schemars::schema_for;
+
+        match self {
+            DumpKind::Body => schema_for!(Body),
+            DumpKind::PowerPlay => schema_for!(PowerPlay),
+            DumpKind::Station => schema_for!(Station),
+            DumpKind::SystemPopulated => schema_for!(SystemPopulated),
+            DumpKind::SystemWithCoordinates => schema_for!(SystemWithCoordinates),
+            DumpKind::SystemWithoutCoordinates => schema_for!(SystemWithoutCoordinates),
+        }
+    }
+
+    /// See [`RootEntry::parse_dump_json_mut`].
+    pub fn parse_dump_json_mut(self, bs: &mut [u8]) -> Result<AnyEntry> {
+        Ok(match self {
+            DumpKind::Body => Body::parse_dump_json_mut(bs)?.into(),
+            DumpKind::PowerPlay => PowerPlay::parse_dump_json_mut(bs)?.into(),
+            DumpKind::Station => Station::parse_dump_json_mut(bs)?.into(),
+            DumpKind::SystemPopulated => SystemPopulated::parse_dump_json_mut(bs)?.into(),
+            DumpKind::SystemWithCoordinates => {
+                SystemWithCoordinates::parse_dump_json_mut(bs)?.into()
+            }
+            DumpKind::SystemWithoutCoordinates => {
+                SystemWithoutCoordinates::parse_dump_json_mut(bs)?.into()
+            }
+        })
+    }
+}
+
+impl fmt::Display for DumpKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.type_name())
+    }
+}
+
+/// Entry of any dump kind.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum AnyEntry {
+    Body(Body),
+    PowerPlay(PowerPlay),
+    Station(Station),
+    SystemPopulated(SystemPopulated),
+    SystemWithCoordinates(SystemWithCoordinates),
+    SystemWithoutCoordinates(SystemWithoutCoordinates),
+}
+
+macro_rules! any_entry_dispatch {
+    ($self:ident, $x:ident => $e:expr) => {
+        match $self {
+            AnyEntry::Body($x) => $e,
+            AnyEntry::PowerPlay($x) => $e,
+            AnyEntry::Station($x) => $e,
+            AnyEntry::SystemPopulated($x) => $e,
+            AnyEntry::SystemWithCoordinates($x) => $e,
+            AnyEntry::SystemWithoutCoordinates($x) => $e,
+        }
+    };
+}
+
+impl AnyEntry {
+    pub fn kind(&self) -> DumpKind {
+        match self {
+            AnyEntry::Body(_) => DumpKind::Body,
+            AnyEntry::PowerPlay(_) => DumpKind::PowerPlay,
+            AnyEntry::Station(_) => DumpKind::Station,
+            AnyEntry::SystemPopulated(_) => DumpKind::SystemPopulated,
+            AnyEntry::SystemWithCoordinates(_) => DumpKind::SystemWithCoordinates,
+            AnyEntry::SystemWithoutCoordinates(_) => DumpKind::SystemWithoutCoordinates,
+        }
+    }
+
+    pub fn entry_id(&self) -> u64 {
+        any_entry_dispatch!(self, x => x.entry_id())
+    }
+
+    pub fn entry_id64(&self) -> Option<u64> {
+        any_entry_dispatch!(self, x => x.entry_id64())
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.kind().type_name()
+    }
+
+    pub fn time(&self) -> DateTime<Utc> {
+        any_entry_dispatch!(self, x => x.time())
+    }
+}
+
+macro_rules! any_entry_from {
+    ($t:ident) => {
+        impl From<$t> for AnyEntry {
+            fn from(x: $t) -> AnyEntry {
+                AnyEntry::$t(x)
+            }
+        }
+    };
+}
+
+any_entry_from!(Body);
+any_entry_from!(PowerPlay);
+any_entry_from!(Station);
+any_entry_from!(SystemPopulated);
+any_entry_from!(SystemWithCoordinates);
+any_entry_from!(SystemWithoutCoordinates);
@@ -0,0 +1,47 @@
+//! Generators for `arbitrary` derives of fields whose values must survive a JSON round-trip.
+
+use std::collections::BTreeMap;
+
+use arbitrary::{Result, Unstructured};
+use chrono::{DateTime, TimeZone, Utc};
+use strum::IntoEnumIterator;
+
+/// `9999-12-31 23:59:59`, the last date with a four digit year.
+const MAX_TIMESTAMP: i64 = 253_402_300_799;
+
+/// Finite `f32`, as JSON has no NaN or infinity.
+pub fn f32(u: &mut Unstructured) -> Result<f32> {
+    let x: f32 = u.arbitrary()?;
+    Ok(if x.is_finite() { x } else { 0.0 })
+}
+
+pub fn f32_opt(u: &mut Unstructured) -> Result<Option<f32>> {
+    Ok(if u.arbitrary()? { Some(f32(u)?) } else { None })
+}
+
+/// Date in whole seconds, as written by `date_format`.
+pub fn date(u: &mut Unstructured) -> Result<DateTime<Utc>> {
+    let secs = u.int_in_range(0..=MAX_TIMESTAMP)?;
+    Ok(Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+pub fn date_opt(u: &mut Unstructured) -> Result<Option<DateTime<Utc>>> {
+    Ok(if u.arbitrary()? { Some(date(u)?) } else { None })
+}
+
+/// Value of an `Unknown` variant, which never equals the name of a known variant.
+#[cfg(feature = "unknown_variants")]
+pub fn unknown_variant(u: &mut Unstructured) -> Result<String> {
+    Ok(format!("Unknown {}", u.arbitrary::<String>()?))
+}
+
+/// Map with a finite number for some of the keys.
+pub fn key_map<K: IntoEnumIterator + Ord>(u: &mut Unstructured) -> Result<BTreeMap<K, f32>> {
+    let mut map = BTreeMap::new();
+    for key in K::iter() {
+        if u.arbitrary()? {
+            map.insert(key, f32(u)?);
+        }
+    }
+    Ok(map)
+}
@@ -1,20 +1,28 @@
+use std::borrow::Cow;
+
 use serde::{Deserialize, Serialize};
 use strum::EnumIter;
-use variant_count::VariantCount;
 
+#[cfg(all(feature = "arbitrary", feature = "unknown_variants"))]
+use super::arb;
+use super::dec::cow_str_opt;
 use super::util::DisplayViaSerde;
-use crate::display_via_serde;
+use crate::{display_via_serde, json_schema_via_serde, unknown_variant_serde};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct ActiveState {
     pub state: State,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum Allegiance {
     Alliance,
@@ -24,12 +32,24 @@ pub enum Allegiance {
     #[serde(rename = "Pilots Federation")]
     PilotsFederation,
     Thargoid,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl Allegiance {
+    pub const VARIANT_COUNT: usize = 6;
 }
 
+unknown_variant_serde!(Allegiance);
 display_via_serde!(Allegiance);
+json_schema_via_serde!(Allegiance);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct ControllingFaction {
@@ -41,8 +61,35 @@ pub struct ControllingFaction {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct ControllingFactionRef<'a> {
+    pub id: Option<u64>,
+    // Attributes
+    pub allegiance: Option<Allegiance>,
+    pub government: Option<Government>,
+    pub is_player: Option<bool>,
+    #[serde(borrow, deserialize_with = "cow_str_opt::deserialize", default)]
+    pub name: Option<Cow<'a, str>>,
+}
+
+impl<'a> ControllingFactionRef<'a> {
+    pub fn to_owned(&self) -> ControllingFaction {
+        ControllingFaction {
+            id: self.id,
+            allegiance: self.allegiance.clone(),
+            government: self.government.clone(),
+            is_player: self.is_player,
+            name: self.name.as_deref().map(str::to_owned),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum Economy {
     None,
@@ -64,12 +111,24 @@ pub enum Economy {
     Terraforming,
     Tourism,
     Engineer,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl Economy {
+    pub const VARIANT_COUNT: usize = 17;
 }
 
+unknown_variant_serde!(Economy);
 display_via_serde!(Economy);
+json_schema_via_serde!(Economy);
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum Government {
     None,
@@ -90,24 +149,24 @@ pub enum Government {
     WorkshopEngineer,
     #[serde(rename = "Fleet Carrier")]
     FleetCarrier,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl Government {
+    pub const VARIANT_COUNT: usize = 15;
 }
 
+unknown_variant_serde!(Government);
 display_via_serde!(Government);
+json_schema_via_serde!(Government);
 
-#[derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    EnumIter,
-    VariantCount,
-)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum Happiness {
     Despondent,
@@ -116,12 +175,24 @@ pub enum Happiness {
     None,
     Happy,
     Elated,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
 }
 
+impl Happiness {
+    pub const VARIANT_COUNT: usize = 6;
+}
+
+unknown_variant_serde!(Happiness);
 display_via_serde!(Happiness);
+json_schema_via_serde!(Happiness);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct PendingState {
@@ -131,6 +202,8 @@ pub struct PendingState {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct RecoveringState {
@@ -138,32 +211,34 @@ pub struct RecoveringState {
     pub trend: u8,
 }
 
-#[derive(
-    Debug,
-    Clone,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    EnumIter,
-    VariantCount,
-)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum Security {
     Anarchy,
     Low,
     Medium,
     High,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl Security {
+    pub const VARIANT_COUNT: usize = 4;
 }
 
+unknown_variant_serde!(Security);
 display_via_serde!(Security);
+json_schema_via_serde!(Security);
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum State {
     Blight,
@@ -195,6 +270,16 @@ pub enum State {
     #[serde(rename = "Terrorist Attack")]
     TerroristAttack,
     War,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl State {
+    pub const VARIANT_COUNT: usize = 21;
 }
 
+unknown_variant_serde!(State);
 display_via_serde!(State);
+json_schema_via_serde!(State);
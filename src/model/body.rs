@@ -1,19 +1,27 @@
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::fmt;
+use std::iter::FromIterator;
 
 use anyhow::{Error, Result};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use serde_json::from_slice;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{from_slice, Value};
+use serde_path_to_error::Path;
 use strum::EnumIter;
 use variant_count::VariantCount;
 
-use super::dec::date_format;
-use super::RootEntry;
+#[cfg(feature = "arbitrary")]
+use super::arb;
+use super::dec::{cow_str_opt, date_format};
+use super::lenient::{self, Extra};
+use super::{RootEntry, RootEntryRef};
+#[cfg(feature = "simd-json")]
+use crate::error::locate_in;
+use crate::error::{locate, ParseError};
 
 use super::util::DisplayViaSerde;
-use crate::display_via_serde;
+use crate::{display_via_serde, json_schema_via_serde, unknown_variant_serde};
 
 // Main Type
 
@@ -69,18 +77,122 @@ impl<T: BodyT> BodyT for &T {
     deref_impl!(surface_temperature, Option<u64>);
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Body tagged by its `type` field.
+///
+/// Encodings that are not self-describing, such as bincode, use the layout of [`BodyS`] instead.
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
-#[serde(deny_unknown_fields)]
-#[serde(tag = "type")]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[allow(clippy::large_enum_variant)]
 pub enum Body {
+    Planet(Planet),
+    Star(Star),
+    Unknown(Unknown),
+}
+
+#[derive(Serialize)]
+#[serde(rename = "Body", tag = "type")]
+enum TaggedBodyRef<'a> {
+    Planet(&'a Planet),
+    Star(&'a Star),
+    #[serde(rename = "null")]
+    Unknown(&'a Unknown),
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Body", tag = "type")]
+#[serde(deny_unknown_fields)]
+#[allow(clippy::large_enum_variant)]
+enum TaggedBody {
     Planet(Planet),
     Star(Star),
     #[serde(rename = "null")]
     Unknown(Unknown),
 }
 
+#[derive(Serialize)]
+#[serde(rename = "BodyS")]
+enum BodySRef<'a> {
+    Planet(&'a Planet),
+    Star(&'a Star),
+    #[serde(rename = "null")]
+    Unknown(&'a Unknown),
+}
+
+impl Serialize for Body {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            match self {
+                Body::Planet(x) => TaggedBodyRef::Planet(x),
+                Body::Star(x) => TaggedBodyRef::Star(x),
+                Body::Unknown(x) => TaggedBodyRef::Unknown(x),
+            }
+            .serialize(serializer)
+        } else {
+            match self {
+                Body::Planet(x) => BodySRef::Planet(x),
+                Body::Star(x) => BodySRef::Star(x),
+                Body::Unknown(x) => BodySRef::Unknown(x),
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+/// Schema of the `type`-tagged layout, in which [`Unknown`] bodies have a `type` of null.
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Body {
+    fn schema_name() -> String {
+        "Body".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{Schema, SchemaObject, SubschemaValidation};
+
+        fn tagged(schema: Schema, tags: Vec<Value>) -> Schema {
+            let mut schema = schema.into_object();
+            let object = schema.object();
+            object.properties.insert(
+                "type".to_owned(),
+                SchemaObject {
+                    enum_values: Some(tags),
+                    ..Default::default()
+                }
+                .into(),
+            );
+            object.required.insert("type".to_owned());
+            schema.into()
+        }
+
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                one_of: Some(vec![
+                    tagged(Planet::json_schema(gen), vec!["Planet".into()]),
+                    tagged(Star::json_schema(gen), vec!["Star".into()]),
+                    tagged(Unknown::json_schema(gen), vec![Value::Null, "null".into()]),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl<'de> Deserialize<'de> for Body {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Body, D::Error> {
+        if deserializer.is_human_readable() {
+            Ok(match TaggedBody::deserialize(deserializer)? {
+                TaggedBody::Planet(x) => Body::Planet(x),
+                TaggedBody::Star(x) => Body::Star(x),
+                TaggedBody::Unknown(x) => Body::Unknown(x),
+            })
+        } else {
+            BodyS::deserialize(deserializer).map(Body::from)
+        }
+    }
+}
+
 macro_rules! body_common_field {
     ($f:ident, $ty:ty ) => {
         fn $f(&self) -> $ty {
@@ -120,6 +232,10 @@ impl RootEntry for Body {
         self.id()
     }
 
+    fn entry_id64(&self) -> Option<u64> {
+        self.id64()
+    }
+
     fn type_name() -> &'static str {
         "body"
     }
@@ -132,16 +248,63 @@ impl RootEntry for Body {
         }
     }
 
+    #[cfg(not(feature = "simd-json"))]
     fn parse_dump_json(bs: &[u8]) -> Result<Self> {
         match from_slice(bs) {
             Ok(v) => Ok(v),
             Err(e) => match from_slice(bs) {
                 Ok(v) => Ok(Body::Unknown(v)),
-                Err(_) => Err(Error::new(e)),
+                Err(_) => Err(parse_error(bs, e)),
+            },
+        }
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse_dump_json(bs: &[u8]) -> Result<Self> {
+        Self::parse_dump_json_mut(&mut bs.to_vec()).map_err(|e| locate_in(e, bs, locate_body))
+    }
+
+    fn parse_dump_json_lenient(bs: &[u8]) -> Result<(Self, Extra)> {
+        let mut value = lenient::to_value(Body::type_name(), bs)?;
+        // Parse the variant named by `type`, which reports unknown fields with their paths
+        let typ = match value.as_object_mut() {
+            Some(m) if matches!(m.get("type"), Some(t) if t == "Planet" || t == "Star") => {
+                m.remove("type")
+            }
+            _ => None,
+        };
+        let parsed = match typ.as_ref().and_then(Value::as_str) {
+            Some("Planet") => lenient::strip_unknown(Body::type_name(), value)
+                .map(|(x, extra)| (Body::Planet(x), extra)),
+            Some("Star") => lenient::strip_unknown(Body::type_name(), value)
+                .map(|(x, extra)| (Body::Star(x), extra)),
+            _ => lenient::strip_unknown(Body::type_name(), value),
+        };
+        match parsed {
+            Ok(v) => Ok(v),
+            Err(e) => match from_slice(bs) {
+                Ok(v) => Ok((Body::Unknown(v), Extra::new())),
+                Err(_) => Err(e),
             },
         }
     }
 
+    #[cfg(feature = "simd-json")]
+    fn parse_dump_json_mut(bs: &mut [u8]) -> Result<Self> {
+        let mut de = simd_json::Deserializer::from_slice(bs)
+            .map_err(|e| ParseError::from_simd(Body::type_name(), e))?;
+        match Body::deserialize(&mut de) {
+            Ok(v) => Ok(v),
+            Err(e) => {
+                de.restart();
+                match Unknown::deserialize(&mut de) {
+                    Ok(v) => Ok(Body::Unknown(v)),
+                    Err(_) => Err(ParseError::from_simd(Body::type_name(), e).into()),
+                }
+            }
+        }
+    }
+
     fn pre_filter(s: &str) -> Cow<'_, str> {
         let null_pos = s.find(r#""type":null"#);
         let first_compound = match (s.find(":{"), s.find('[')) {
@@ -165,9 +328,34 @@ impl RootEntry for Body {
     }
 }
 
+/// Builds a [`ParseError`] with [`locate_body`].
+fn parse_error(bs: &[u8], e: impl fmt::Display) -> Error {
+    ParseError::with_locator(Body::type_name(), bs, e, locate_body).into()
+}
+
+/// Locates the failing field in the variant named by `type`.
+fn locate_body(value: &Value) -> Option<(Path, String)> {
+    match value.get("type").and_then(Value::as_str) {
+        Some(typ @ "Planet") | Some(typ @ "Star") => {
+            let mut value = value.clone();
+            if let Some(m) = value.as_object_mut() {
+                m.remove("type");
+            }
+            if typ == "Planet" {
+                locate::<Planet>(&value)
+            } else {
+                locate::<Star>(&value)
+            }
+        }
+        _ => locate::<Body>(value),
+    }
+}
+
 /// Surrogate type for some encodings.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 #[allow(clippy::large_enum_variant)]
 pub enum BodyS {
@@ -236,6 +424,10 @@ impl RootEntry for BodyS {
         self.id()
     }
 
+    fn entry_id64(&self) -> Option<u64> {
+        self.id64()
+    }
+
     fn type_name() -> &'static str {
         "body"
     }
@@ -283,37 +475,50 @@ macro_rules! body_t_impl_deref {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Planet {
     pub id: u64,
     // Attributes
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub arg_of_periapsis: Option<f32>,
     pub atmosphere_composition: Option<AtmosphereComposition>,
     pub atmosphere_type: Option<AtmosphereType>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub axial_tilt: Option<f32>,
     pub belts: Option<Vec<Belt>>,
     pub body_id: Option<u64>,
     pub discovery: Option<Discovery>,
     pub distance_to_arrival: u64,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub earth_masses: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub gravity: Option<f32>,
     pub id64: Option<u64>,
     pub is_landable: bool,
     pub materials: Option<Materials>,
     pub name: String,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub orbital_eccentricity: Option<f32>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub orbital_inclination: Option<f32>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub orbital_period: Option<f32>,
     pub parents: Option<Vec<Parent>>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub radius: f32,
     pub reserve_level: Option<ReserveLevel>,
     pub rings: Option<Vec<Ring>>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub rotational_period: Option<f32>,
     pub rotational_period_tidally_locked: bool,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub semi_major_axis: Option<f32>,
     pub solid_composition: Option<SolidComposition>,
     pub sub_type: PlanetSubType,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub surface_pressure: Option<f32>,
     pub surface_temperature: u64,
     pub system_id: Option<u64>,
@@ -323,7 +528,12 @@ pub struct Planet {
     pub volcanism_type: Option<VolcanismType>,
     // Metadata
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub update_time: DateTime<Utc>,
 }
 
@@ -353,14 +563,19 @@ impl BodyT for Planet {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Star {
     pub id: u64,
     // Attributes
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub absolute_magnitude: Option<f32>,
     pub age: u64,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub arg_of_periapsis: Option<f32>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub axial_tilt: Option<f32>,
     pub belts: Option<Vec<Belt>>,
     pub body_id: Option<u64>,
@@ -371,16 +586,23 @@ pub struct Star {
     pub is_scoopable: bool,
     pub luminosity: Option<Luminosity>,
     pub name: String,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub orbital_eccentricity: Option<f32>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub orbital_inclination: Option<f32>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub orbital_period: Option<f32>,
     pub parents: Option<Vec<Parent>>,
     pub reserve_level: Option<ReserveLevel>,
     pub rings: Option<Vec<Ring>>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub rotational_period: Option<f32>,
     pub rotational_period_tidally_locked: bool,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub semi_major_axis: Option<f32>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub solar_masses: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub solar_radius: f32,
     pub spectral_class: Option<SpectralClass>,
     pub sub_type: StarSubType,
@@ -390,7 +612,12 @@ pub struct Star {
     pub system_name: Option<String>,
     // Metadata
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub update_time: DateTime<Utc>,
 }
 
@@ -420,6 +647,8 @@ impl BodyT for Star {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct Unknown {
     pub id: u64,
@@ -431,7 +660,12 @@ pub struct Unknown {
     pub system_name: Option<String>,
     // Metadata
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub update_time: DateTime<Utc>,
 }
 
@@ -459,23 +693,485 @@ impl BodyT for Unknown {
     body_t_impl_none!(surface_temperature, Option<u64>);
 }
 
+// Borrowed Type
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
+pub enum BodyRef<'a> {
+    #[serde(borrow)]
+    Planet(PlanetRef<'a>),
+    #[serde(borrow)]
+    Star(StarRef<'a>),
+    #[serde(rename = "null")]
+    #[serde(borrow)]
+    Unknown(UnknownRef<'a>),
+}
+
+impl<'a> BodyRef<'a> {
+    pub fn to_owned(&self) -> Body {
+        match self {
+            BodyRef::Planet(x) => Body::Planet(x.to_owned()),
+            BodyRef::Star(x) => Body::Star(x.to_owned()),
+            BodyRef::Unknown(x) => Body::Unknown(x.to_owned()),
+        }
+    }
+}
+
+macro_rules! body_ref_common_field {
+    ($f:ident, $ty:ty ) => {
+        fn $f(&self) -> $ty {
+            match self {
+                BodyRef::Planet(x) => x.$f(),
+                BodyRef::Star(x) => x.$f(),
+                BodyRef::Unknown(x) => x.$f(),
+            }
+        }
+    };
+}
+
+impl<'a> BodyT for BodyRef<'a> {
+    body_ref_common_field!(id, u64);
+    body_ref_common_field!(id64, Option<u64>);
+    body_ref_common_field!(body_id, Option<u64>);
+    body_ref_common_field!(system_id, Option<u64>);
+    body_ref_common_field!(system_id64, Option<u64>);
+    body_ref_common_field!(update_time, DateTime<Utc>);
+    body_ref_common_field!(name, &str);
+    body_ref_common_field!(system_name, Option<&str>);
+
+    body_ref_common_field!(axial_tilt, Option<f32>);
+    body_ref_common_field!(distance_to_arrival, Option<u64>);
+    body_ref_common_field!(orbital_eccentricity, Option<f32>);
+    body_ref_common_field!(orbital_inclination, Option<f32>);
+    body_ref_common_field!(orbital_period, Option<f32>);
+    body_ref_common_field!(parents, Option<&[Parent]>);
+    body_ref_common_field!(rotational_period, Option<f32>);
+    body_ref_common_field!(rotational_period_tidally_locked, Option<bool>);
+    body_ref_common_field!(semi_major_axis, Option<f32>);
+    body_ref_common_field!(surface_temperature, Option<u64>);
+}
+
+impl<'a> RootEntryRef<'a> for BodyRef<'a> {
+    type Owned = Body;
+
+    fn parse_dump_json(bs: &'a [u8]) -> Result<Self> {
+        match from_slice(bs) {
+            Ok(v) => Ok(v),
+            Err(e) => match from_slice(bs) {
+                Ok(v) => Ok(BodyRef::Unknown(v)),
+                Err(_) => Err(parse_error(bs, e)),
+            },
+        }
+    }
+
+    fn to_owned_entry(&self) -> Body {
+        self.to_owned()
+    }
+}
+
+macro_rules! body_t_impl_cow {
+    ($n:ident, $t:ty) => {
+        fn $n(&self) -> $t {
+            self.$n.as_deref()
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct PlanetRef<'a> {
+    pub id: u64,
+    // Attributes
+    pub arg_of_periapsis: Option<f32>,
+    pub atmosphere_composition: Option<AtmosphereComposition>,
+    pub atmosphere_type: Option<AtmosphereType>,
+    pub axial_tilt: Option<f32>,
+    #[serde(borrow)]
+    pub belts: Option<Vec<BeltRef<'a>>>,
+    pub body_id: Option<u64>,
+    #[serde(borrow)]
+    pub discovery: Option<DiscoveryRef<'a>>,
+    pub distance_to_arrival: u64,
+    pub earth_masses: f32,
+    pub gravity: Option<f32>,
+    pub id64: Option<u64>,
+    pub is_landable: bool,
+    pub materials: Option<Materials>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub orbital_eccentricity: Option<f32>,
+    pub orbital_inclination: Option<f32>,
+    pub orbital_period: Option<f32>,
+    pub parents: Option<Vec<Parent>>,
+    pub radius: f32,
+    pub reserve_level: Option<ReserveLevel>,
+    #[serde(borrow)]
+    pub rings: Option<Vec<RingRef<'a>>>,
+    pub rotational_period: Option<f32>,
+    pub rotational_period_tidally_locked: bool,
+    pub semi_major_axis: Option<f32>,
+    pub solid_composition: Option<SolidComposition>,
+    pub sub_type: PlanetSubType,
+    pub surface_pressure: Option<f32>,
+    pub surface_temperature: u64,
+    pub system_id: Option<u64>,
+    pub system_id64: Option<u64>,
+    #[serde(borrow, deserialize_with = "cow_str_opt::deserialize", default)]
+    pub system_name: Option<Cow<'a, str>>,
+    pub terraforming_state: Option<TerraformingState>,
+    pub volcanism_type: Option<VolcanismType>,
+    // Metadata
+    #[serde(with = "date_format")]
+    pub update_time: DateTime<Utc>,
+}
+
+impl<'a> PlanetRef<'a> {
+    #[cfg_attr(not(feature = "unknown_variants"), allow(clippy::clone_on_copy))]
+    pub fn to_owned(&self) -> Planet {
+        Planet {
+            id: self.id,
+            arg_of_periapsis: self.arg_of_periapsis,
+            atmosphere_composition: self.atmosphere_composition.clone(),
+            atmosphere_type: self.atmosphere_type.clone(),
+            axial_tilt: self.axial_tilt,
+            belts: self
+                .belts
+                .as_ref()
+                .map(|xs| xs.iter().map(BeltRef::to_owned).collect()),
+            body_id: self.body_id,
+            discovery: self.discovery.as_ref().map(DiscoveryRef::to_owned),
+            distance_to_arrival: self.distance_to_arrival,
+            earth_masses: self.earth_masses,
+            gravity: self.gravity,
+            id64: self.id64,
+            is_landable: self.is_landable,
+            materials: self.materials.clone(),
+            name: self.name.clone().into_owned(),
+            orbital_eccentricity: self.orbital_eccentricity,
+            orbital_inclination: self.orbital_inclination,
+            orbital_period: self.orbital_period,
+            parents: self.parents.clone(),
+            radius: self.radius,
+            reserve_level: self.reserve_level.clone(),
+            rings: self
+                .rings
+                .as_ref()
+                .map(|xs| xs.iter().map(RingRef::to_owned).collect()),
+            rotational_period: self.rotational_period,
+            rotational_period_tidally_locked: self.rotational_period_tidally_locked,
+            semi_major_axis: self.semi_major_axis,
+            solid_composition: self.solid_composition.clone(),
+            sub_type: self.sub_type,
+            surface_pressure: self.surface_pressure,
+            surface_temperature: self.surface_temperature,
+            system_id: self.system_id,
+            system_id64: self.system_id64,
+            system_name: self.system_name.as_deref().map(str::to_owned),
+            terraforming_state: self.terraforming_state.clone(),
+            volcanism_type: self.volcanism_type.clone(),
+            update_time: self.update_time,
+        }
+    }
+}
+
+impl<'a> BodyT for PlanetRef<'a> {
+    body_t_impl!(id, u64);
+    body_t_impl!(id64, Option<u64>);
+    body_t_impl!(body_id, Option<u64>);
+    body_t_impl!(system_id, Option<u64>);
+    body_t_impl!(system_id64, Option<u64>);
+    body_t_impl!(update_time, DateTime<Utc>);
+    fn name(&self) -> &str {
+        &self.name
+    }
+    body_t_impl_cow!(system_name, Option<&str>);
+
+    body_t_impl!(axial_tilt, Option<f32>);
+    body_t_impl_some!(distance_to_arrival, Option<u64>);
+    body_t_impl!(orbital_eccentricity, Option<f32>);
+    body_t_impl!(orbital_inclination, Option<f32>);
+    body_t_impl!(orbital_period, Option<f32>);
+    body_t_impl_deref!(parents, Option<&[Parent]>);
+    body_t_impl!(rotational_period, Option<f32>);
+    body_t_impl_some!(rotational_period_tidally_locked, Option<bool>);
+    body_t_impl!(semi_major_axis, Option<f32>);
+    body_t_impl_some!(surface_temperature, Option<u64>);
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct StarRef<'a> {
+    pub id: u64,
+    // Attributes
+    pub absolute_magnitude: Option<f32>,
+    pub age: u64,
+    pub arg_of_periapsis: Option<f32>,
+    pub axial_tilt: Option<f32>,
+    #[serde(borrow)]
+    pub belts: Option<Vec<BeltRef<'a>>>,
+    pub body_id: Option<u64>,
+    #[serde(borrow)]
+    pub discovery: Option<DiscoveryRef<'a>>,
+    pub distance_to_arrival: u64,
+    pub id64: Option<u64>,
+    pub is_main_star: bool,
+    pub is_scoopable: bool,
+    pub luminosity: Option<Luminosity>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub orbital_eccentricity: Option<f32>,
+    pub orbital_inclination: Option<f32>,
+    pub orbital_period: Option<f32>,
+    pub parents: Option<Vec<Parent>>,
+    pub reserve_level: Option<ReserveLevel>,
+    #[serde(borrow)]
+    pub rings: Option<Vec<RingRef<'a>>>,
+    pub rotational_period: Option<f32>,
+    pub rotational_period_tidally_locked: bool,
+    pub semi_major_axis: Option<f32>,
+    pub solar_masses: f32,
+    pub solar_radius: f32,
+    pub spectral_class: Option<SpectralClass>,
+    pub sub_type: StarSubType,
+    pub surface_temperature: u64,
+    pub system_id: Option<u64>,
+    pub system_id64: Option<u64>,
+    #[serde(borrow, deserialize_with = "cow_str_opt::deserialize", default)]
+    pub system_name: Option<Cow<'a, str>>,
+    // Metadata
+    #[serde(with = "date_format")]
+    pub update_time: DateTime<Utc>,
+}
+
+impl<'a> StarRef<'a> {
+    #[cfg_attr(not(feature = "unknown_variants"), allow(clippy::clone_on_copy))]
+    pub fn to_owned(&self) -> Star {
+        Star {
+            id: self.id,
+            absolute_magnitude: self.absolute_magnitude,
+            age: self.age,
+            arg_of_periapsis: self.arg_of_periapsis,
+            axial_tilt: self.axial_tilt,
+            belts: self
+                .belts
+                .as_ref()
+                .map(|xs| xs.iter().map(BeltRef::to_owned).collect()),
+            body_id: self.body_id,
+            discovery: self.discovery.as_ref().map(DiscoveryRef::to_owned),
+            distance_to_arrival: self.distance_to_arrival,
+            id64: self.id64,
+            is_main_star: self.is_main_star,
+            is_scoopable: self.is_scoopable,
+            luminosity: self.luminosity,
+            name: self.name.clone().into_owned(),
+            orbital_eccentricity: self.orbital_eccentricity,
+            orbital_inclination: self.orbital_inclination,
+            orbital_period: self.orbital_period,
+            parents: self.parents.clone(),
+            reserve_level: self.reserve_level.clone(),
+            rings: self
+                .rings
+                .as_ref()
+                .map(|xs| xs.iter().map(RingRef::to_owned).collect()),
+            rotational_period: self.rotational_period,
+            rotational_period_tidally_locked: self.rotational_period_tidally_locked,
+            semi_major_axis: self.semi_major_axis,
+            solar_masses: self.solar_masses,
+            solar_radius: self.solar_radius,
+            spectral_class: self.spectral_class,
+            sub_type: self.sub_type,
+            surface_temperature: self.surface_temperature,
+            system_id: self.system_id,
+            system_id64: self.system_id64,
+            system_name: self.system_name.as_deref().map(str::to_owned),
+            update_time: self.update_time,
+        }
+    }
+}
+
+impl<'a> BodyT for StarRef<'a> {
+    body_t_impl!(id, u64);
+    body_t_impl!(id64, Option<u64>);
+    body_t_impl!(body_id, Option<u64>);
+    body_t_impl!(system_id, Option<u64>);
+    body_t_impl!(system_id64, Option<u64>);
+    body_t_impl!(update_time, DateTime<Utc>);
+    fn name(&self) -> &str {
+        &self.name
+    }
+    body_t_impl_cow!(system_name, Option<&str>);
+
+    body_t_impl!(axial_tilt, Option<f32>);
+    body_t_impl_some!(distance_to_arrival, Option<u64>);
+    body_t_impl!(orbital_eccentricity, Option<f32>);
+    body_t_impl!(orbital_inclination, Option<f32>);
+    body_t_impl!(orbital_period, Option<f32>);
+    body_t_impl_deref!(parents, Option<&[Parent]>);
+    body_t_impl!(rotational_period, Option<f32>);
+    body_t_impl_some!(rotational_period_tidally_locked, Option<bool>);
+    body_t_impl!(semi_major_axis, Option<f32>);
+    body_t_impl_some!(surface_temperature, Option<u64>);
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownRef<'a> {
+    pub id: u64,
+    // Attributes
+    pub id64: Option<u64>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub system_id: Option<u64>,
+    pub system_id64: Option<u64>,
+    #[serde(borrow, deserialize_with = "cow_str_opt::deserialize", default)]
+    pub system_name: Option<Cow<'a, str>>,
+    // Metadata
+    #[serde(with = "date_format")]
+    pub update_time: DateTime<Utc>,
+}
+
+impl<'a> UnknownRef<'a> {
+    pub fn to_owned(&self) -> Unknown {
+        Unknown {
+            id: self.id,
+            id64: self.id64,
+            name: self.name.clone().into_owned(),
+            system_id: self.system_id,
+            system_id64: self.system_id64,
+            system_name: self.system_name.as_deref().map(str::to_owned),
+            update_time: self.update_time,
+        }
+    }
+}
+
+impl<'a> BodyT for UnknownRef<'a> {
+    body_t_impl!(id, u64);
+    body_t_impl!(id64, Option<u64>);
+    body_t_impl_none!(body_id, Option<u64>);
+    body_t_impl!(system_id, Option<u64>);
+    body_t_impl!(system_id64, Option<u64>);
+    body_t_impl!(update_time, DateTime<Utc>);
+    fn name(&self) -> &str {
+        &self.name
+    }
+    body_t_impl_cow!(system_name, Option<&str>);
+
+    body_t_impl_none!(axial_tilt, Option<f32>);
+    body_t_impl_none!(distance_to_arrival, Option<u64>);
+    body_t_impl_none!(orbital_eccentricity, Option<f32>);
+    body_t_impl_none!(orbital_inclination, Option<f32>);
+    body_t_impl_none!(orbital_period, Option<f32>);
+    body_t_impl_none!(parents, Option<&[Parent]>);
+    body_t_impl_none!(rotational_period, Option<f32>);
+    body_t_impl_none!(rotational_period_tidally_locked, Option<bool>);
+    body_t_impl_none!(semi_major_axis, Option<f32>);
+    body_t_impl_none!(surface_temperature, Option<u64>);
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct BeltRef<'a> {
+    pub inner_radius: f32,
+    pub mass: f32,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub outer_radius: f32,
+    #[serde(rename = "type")]
+    pub typ: Option<AsteroidType>,
+}
+
+impl<'a> BeltRef<'a> {
+    #[cfg_attr(not(feature = "unknown_variants"), allow(clippy::clone_on_copy))]
+    pub fn to_owned(&self) -> Belt {
+        Belt {
+            inner_radius: self.inner_radius,
+            mass: self.mass,
+            name: self.name.clone().into_owned(),
+            outer_radius: self.outer_radius,
+            typ: self.typ.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct DiscoveryRef<'a> {
+    #[serde(borrow)]
+    pub commander: Cow<'a, str>,
+    #[serde(with = "date_format")]
+    pub date: DateTime<Utc>,
+}
+
+impl<'a> DiscoveryRef<'a> {
+    pub fn to_owned(&self) -> Discovery {
+        Discovery {
+            commander: self.commander.clone().into_owned(),
+            date: self.date,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct RingRef<'a> {
+    pub inner_radius: f32,
+    pub mass: f32,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub outer_radius: f32,
+    #[serde(rename = "type")]
+    pub typ: Option<AsteroidType>,
+}
+
+impl<'a> RingRef<'a> {
+    #[cfg_attr(not(feature = "unknown_variants"), allow(clippy::clone_on_copy))]
+    pub fn to_owned(&self) -> Ring {
+        Ring {
+            inner_radius: self.inner_radius,
+            mass: self.mass,
+            name: self.name.clone().into_owned(),
+            outer_radius: self.outer_radius,
+            typ: self.typ.clone(),
+        }
+    }
+}
+
 // Field Type
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    EnumIter,
-    VariantCount,
-)]
+/// Schema of an object with an optional number for each key.
+#[cfg(feature = "schemars")]
+fn key_map_schema<K: Serialize + strum::IntoEnumIterator>(
+    gen: &mut schemars::gen::SchemaGenerator,
+) -> schemars::schema::Schema {
+    use schemars::schema::{InstanceType, SchemaObject};
+
+    let mut schema = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    };
+    let object = schema.object();
+    for key in K::iter() {
+        let name = serde_json::to_value(key).expect("key should serialize");
+        if let Value::String(name) = name {
+            object.properties.insert(name, gen.subschema_for::<f32>());
+        }
+    }
+    object.additional_properties = Some(Box::new(false.into()));
+    schema.into()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(not(feature = "unknown_variants"), derive(Copy))]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum AsteroidType {
     Icy,
@@ -483,21 +1179,57 @@ pub enum AsteroidType {
     #[serde(rename = "Metal Rich")]
     MetalRich,
     Metallic,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
 }
 
+impl AsteroidType {
+    pub const VARIANT_COUNT: usize = 4;
+}
+
+unknown_variant_serde!(AsteroidType);
 display_via_serde!(AsteroidType);
+json_schema_via_serde!(AsteroidType);
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
 #[serde(rename_all = "PascalCase")]
 pub struct AtmosphereComposition(BTreeMap<AtmosphereCompositionKey, f32>);
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for AtmosphereComposition {
+    fn schema_name() -> String {
+        "AtmosphereComposition".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        key_map_schema::<AtmosphereCompositionKey>(gen)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AtmosphereComposition {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arb::key_map(u).map(AtmosphereComposition)
+    }
+}
+
 impl AtmosphereComposition {
     pub fn get(&self, key: AtmosphereCompositionKey) -> Option<f32> {
         self.0.get(&key).copied()
     }
 }
 
+impl FromIterator<(AtmosphereCompositionKey, f32)> for AtmosphereComposition {
+    fn from_iter<I: IntoIterator<Item = (AtmosphereCompositionKey, f32)>>(
+        iter: I,
+    ) -> AtmosphereComposition {
+        AtmosphereComposition(iter.into_iter().collect())
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -513,6 +1245,7 @@ impl AtmosphereComposition {
     VariantCount,
 )]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum AtmosphereCompositionKey {
     Ammonia,
     Argon,
@@ -536,22 +1269,13 @@ impl AtmosphereCompositionKey {
 }
 
 display_via_serde!(AtmosphereCompositionKey);
+json_schema_via_serde!(AtmosphereCompositionKey);
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    EnumIter,
-    VariantCount,
-)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(not(feature = "unknown_variants"), derive(Copy))]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum AtmosphereType {
     /* normal */
     Ammonia,
@@ -717,22 +1441,34 @@ pub enum AtmosphereType {
     ThinWater,
     #[serde(rename = "Thin Water-rich")]
     ThinWaterRich,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
 }
 
 impl AtmosphereType {
     pub const VARIANTS: usize = 83;
+    pub const VARIANT_COUNT: usize = Self::VARIANTS;
 }
 
+unknown_variant_serde!(AtmosphereType);
 display_via_serde!(AtmosphereType);
+json_schema_via_serde!(AtmosphereType);
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Belt {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub inner_radius: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub mass: f32,
     pub name: String,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub outer_radius: f32,
     #[serde(rename = "type")]
     pub typ: Option<AsteroidType>,
@@ -740,12 +1476,19 @@ pub struct Belt {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Discovery {
     pub commander: String,
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub date: DateTime<Utc>,
 }
 
@@ -764,6 +1507,7 @@ pub struct Discovery {
     VariantCount,
 )]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum Luminosity {
     VII,
@@ -798,6 +1542,7 @@ impl Luminosity {
 }
 
 display_via_serde!(Luminosity);
+json_schema_via_serde!(Luminosity);
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
@@ -805,12 +1550,36 @@ display_via_serde!(Luminosity);
 #[serde(deny_unknown_fields)]
 pub struct Materials(BTreeMap<MaterialsKey, f32>);
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Materials {
+    fn schema_name() -> String {
+        "Materials".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        key_map_schema::<MaterialsKey>(gen)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Materials {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        arb::key_map(u).map(Materials)
+    }
+}
+
 impl Materials {
     pub fn get(&self, key: MaterialsKey) -> Option<f32> {
         self.0.get(&key).copied()
     }
 }
 
+impl FromIterator<(MaterialsKey, f32)> for Materials {
+    fn from_iter<I: IntoIterator<Item = (MaterialsKey, f32)>>(iter: I) -> Materials {
+        Materials(iter.into_iter().collect())
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -826,6 +1595,7 @@ impl Materials {
     VariantCount,
 )]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum MaterialsKey {
     Antimony,
     Arsenic,
@@ -859,9 +1629,12 @@ impl MaterialsKey {
 }
 
 display_via_serde!(MaterialsKey);
+json_schema_via_serde!(MaterialsKey);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub enum Parent {
     Null(u64),
@@ -869,6 +1642,17 @@ pub enum Parent {
     Star(u64),
 }
 
+/// Formats as `Star:3`, the kind and `bodyId` of the parent.
+impl fmt::Display for Parent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Parent::Null(id) => write!(f, "Null:{}", id),
+            Parent::Planet(id) => write!(f, "Planet:{}", id),
+            Parent::Star(id) => write!(f, "Star:{}", id),
+        }
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -884,6 +1668,7 @@ pub enum Parent {
     VariantCount,
 )]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum PlanetSubType {
     // gas ginat
@@ -954,22 +1739,13 @@ impl PlanetSubType {
 }
 
 display_via_serde!(PlanetSubType);
+json_schema_via_serde!(PlanetSubType);
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    EnumIter,
-    VariantCount,
-)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(not(feature = "unknown_variants"), derive(Copy))]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum ReserveLevel {
     Depleted,
@@ -977,22 +1753,34 @@ pub enum ReserveLevel {
     Common,
     Major,
     Pristine,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
 }
 
 impl ReserveLevel {
     pub const VARIANTS: usize = 5;
+    pub const VARIANT_COUNT: usize = Self::VARIANTS;
 }
 
+unknown_variant_serde!(ReserveLevel);
 display_via_serde!(ReserveLevel);
+json_schema_via_serde!(ReserveLevel);
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Ring {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub inner_radius: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub mass: f32,
     pub name: String,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub outer_radius: f32,
     #[serde(rename = "type")]
     pub typ: Option<AsteroidType>,
@@ -1000,14 +1788,19 @@ pub struct Ring {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "PascalCase")]
 #[serde(deny_unknown_fields)]
 pub struct SolidComposition {
     #[serde(default)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub ice: f32,
     #[serde(default)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub metal: f32,
     #[serde(default)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub rock: f32,
 }
 
@@ -1026,6 +1819,8 @@ pub struct SolidComposition {
     VariantCount,
 )]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum StarClass {
     OTypeStars,
     BTypeStars,
@@ -1107,6 +1902,7 @@ impl fmt::Display for StarClass {
     VariantCount,
 )]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum SpectralClass {
     O,
@@ -1244,6 +2040,7 @@ impl SpectralClass {
 }
 
 display_via_serde!(SpectralClass);
+json_schema_via_serde!(SpectralClass);
 
 #[derive(
     Debug,
@@ -1260,6 +2057,7 @@ display_via_serde!(SpectralClass);
     VariantCount,
 )]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum StarSubType {
     // Main sequence
@@ -1412,6 +2210,7 @@ impl StarSubType {
 }
 
 display_via_serde!(StarSubType);
+json_schema_via_serde!(StarSubType);
 
 impl StarSubType {
     pub fn filter_star_class(self) -> StarClass {
@@ -1463,21 +2262,11 @@ impl StarSubType {
     }
 }
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    EnumIter,
-    VariantCount,
-)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(not(feature = "unknown_variants"), derive(Copy))]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum TerraformingState {
     #[serde(rename = "Candidate for terraforming")]
@@ -1486,29 +2275,26 @@ pub enum TerraformingState {
     NotTerraformable,
     Terraformed,
     Terraforming,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
 }
 
 impl TerraformingState {
     pub const VARIANTS: usize = 4;
+    pub const VARIANT_COUNT: usize = Self::VARIANTS;
 }
 
+unknown_variant_serde!(TerraformingState);
 display_via_serde!(TerraformingState);
+json_schema_via_serde!(TerraformingState);
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    EnumIter,
-    VariantCount,
-)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(not(feature = "unknown_variants"), derive(Copy))]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum VolcanismType {
     #[serde(rename = "Ammonia Magma")]
@@ -1561,13 +2347,20 @@ pub enum VolcanismType {
     WaterGeysers,
     #[serde(rename = "Water Magma")]
     WaterMagma,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
 }
 
 impl VolcanismType {
     pub const VARIANTS: usize = 25;
+    pub const VARIANT_COUNT: usize = Self::VARIANTS;
 }
 
+unknown_variant_serde!(VolcanismType);
 display_via_serde!(VolcanismType);
+json_schema_via_serde!(VolcanismType);
 
 #[cfg(test)]
 mod tests {
@@ -1592,6 +2385,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "unknown_variants"))]
     fn atmosphere_type_check_variants_count() {
         let mut max = 0;
         let n = AtmosphereType::VARIANTS;
@@ -1660,6 +2454,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "unknown_variants"))]
     fn reserve_level_check_variants_count() {
         let mut max = 0;
         let n = ReserveLevel::VARIANTS;
@@ -1728,6 +2523,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "unknown_variants"))]
     fn terraforming_state_check_variants_count() {
         let mut max = 0;
         let n = TerraformingState::VARIANTS;
@@ -1745,6 +2541,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "unknown_variants"))]
     fn volcanism_type_check_variants_count() {
         let mut max = 0;
         let n = VolcanismType::VARIANTS;
@@ -1,2 +1,3 @@
+pub mod cow_str_opt;
 pub mod date_format;
 pub mod date_format_opt;
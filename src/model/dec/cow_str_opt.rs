@@ -0,0 +1,14 @@
+use std::borrow::Cow;
+
+use serde::{self, Deserialize, Deserializer};
+
+#[derive(Deserialize)]
+struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+pub fn deserialize<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<Borrowed<'a>> = Deserialize::deserialize(deserializer)?;
+    Ok(s.map(|s| s.0))
+}
@@ -1,7 +1,14 @@
-use chrono::{DateTime, TimeZone, Utc};
-use serde::{self, Deserialize, Deserializer, Serializer};
+use std::fmt;
 
-const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::de::{self, Unexpected, Visitor};
+use serde::{self, Deserializer, Serializer};
+
+pub(crate) const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Pattern of [`FORMAT`] in JSON Schema.
+#[cfg(feature = "schemars")]
+pub(crate) const PATTERN: &str = r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}$";
 
 pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -15,7 +22,36 @@ pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    Utc.datetime_from_str(&s, FORMAT)
-        .map_err(serde::de::Error::custom)
+    deserializer.deserialize_str(DateVisitor)
+}
+
+struct DateVisitor;
+
+impl<'de> Visitor<'de> for DateVisitor {
+    type Value = DateTime<Utc>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a date formatted as \"YYYY-MM-DD hh:mm:ss\"")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<DateTime<Utc>, E> {
+        NaiveDateTime::parse_from_str(s, FORMAT)
+            .map(|d| Utc.from_utc_datetime(&d))
+            .map_err(|_| de::Error::invalid_value(Unexpected::Str(s), &self))
+    }
+}
+
+#[cfg(feature = "schemars")]
+pub fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    use schemars::schema::{InstanceType, SchemaObject, StringValidation};
+
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(PATTERN.to_owned()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
 }
@@ -1,29 +1,34 @@
-use chrono::{DateTime, TimeZone, Utc};
-use serde::{self, Deserialize, Deserializer, Serializer};
+use chrono::{DateTime, Utc};
+use serde::{self, Deserialize, Deserializer, Serialize, Serializer};
 
-const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+use super::date_format;
 
 pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
     match date {
-        Some(date) => {
-            let s = format!("{}", date.format(FORMAT));
-            serializer.serialize_str(&s)
-        }
+        Some(date) => serializer.serialize_some(&Date(*date)),
         None => serializer.serialize_none(),
     }
 }
 
+#[derive(Serialize, Deserialize)]
+struct Date(#[serde(with = "date_format")] DateTime<Utc>);
+
 pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s: Option<String> = Deserialize::deserialize(deserializer)?;
-    s.map(|s| {
-        Utc.datetime_from_str(&s, FORMAT)
-            .map_err(serde::de::Error::custom)
-    })
-    .transpose()
+    let d: Option<Date> = Deserialize::deserialize(deserializer)?;
+    Ok(d.map(|d| d.0))
+}
+
+#[cfg(feature = "schemars")]
+pub fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    use schemars::schema::{InstanceType, Schema};
+
+    let mut schema = date_format::json_schema(gen).into_object();
+    schema.instance_type = Some(vec![InstanceType::String, InstanceType::Null].into());
+    Schema::Object(schema)
 }
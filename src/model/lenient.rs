@@ -0,0 +1,160 @@
+//! Lenient parsing, moving fields not known to the model aside instead of failing.
+//!
+//! The model types reject unknown fields, so a new field in the EDSM dumps fails every record.
+//! [`RootEntry::parse_dump_json_lenient`] and [`Lenient`] parse such records into the same types,
+//! keeping the unknown fields in an [`Extra`] to warn about or to write back.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use serde_path_to_error::{Path, Segment};
+
+use super::RootEntry;
+use crate::error::{ParseError, ParseErrorKind};
+
+/// Fields not known to the model, by JSON path, e.g. `rings[2].newField`.
+pub type Extra = Map<String, Value>;
+
+/// Entry parsed leniently, with the fields not known to its model.
+///
+/// As a [`RootEntry`], it reads dumps with the same readers as `T`, e.g. `DumpReader<_, Lenient<Body>>`.
+/// The unknown fields are serialized back at their paths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lenient<T> {
+    pub entry: T,
+    pub extra: Extra,
+}
+
+impl<T> Lenient<T> {
+    /// Paths of the unknown fields, e.g. `rings[2].newField`.
+    pub fn unknown_fields(&self) -> impl Iterator<Item = &str> {
+        self.extra.keys().map(String::as_str)
+    }
+}
+
+impl<T: RootEntry> RootEntry for Lenient<T> {
+    fn entry_id(&self) -> u64 {
+        self.entry.entry_id()
+    }
+
+    fn entry_id64(&self) -> Option<u64> {
+        self.entry.entry_id64()
+    }
+
+    fn type_name() -> &'static str {
+        T::type_name()
+    }
+
+    fn time(&self) -> DateTime<Utc> {
+        self.entry.time()
+    }
+
+    fn parse_dump_json(bs: &[u8]) -> Result<Self> {
+        let (entry, extra) = T::parse_dump_json_lenient(bs)?;
+        Ok(Lenient { entry, extra })
+    }
+
+    fn parse_dump_json_mut(bs: &mut [u8]) -> Result<Self> {
+        Self::parse_dump_json(bs)
+    }
+}
+
+/// Encodings that are not self-describing, such as bincode, hold `extra` as a JSON string.
+impl<T: Serialize> Serialize for Lenient<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let mut value = serde_json::to_value(&self.entry).map_err(S::Error::custom)?;
+            for (path, field) in &self.extra {
+                insert(&mut value, path, field.clone());
+            }
+            value.serialize(serializer)
+        } else {
+            let extra = serde_json::to_string(&self.extra).map_err(S::Error::custom)?;
+            (&self.entry, extra).serialize(serializer)
+        }
+    }
+}
+
+impl<'de, T: RootEntry> Deserialize<'de> for Lenient<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Lenient<T>, D::Error> {
+        if deserializer.is_human_readable() {
+            let value = Value::deserialize(deserializer)?;
+            let bs = serde_json::to_vec(&value).map_err(D::Error::custom)?;
+            Lenient::parse_dump_json(&bs).map_err(D::Error::custom)
+        } else {
+            let (entry, extra) = <(T, String)>::deserialize(deserializer)?;
+            let extra = serde_json::from_str(&extra).map_err(D::Error::custom)?;
+            Ok(Lenient { entry, extra })
+        }
+    }
+}
+
+/// Parses `bs` as JSON, failing with a [`ParseError`].
+pub(crate) fn to_value(type_name: &'static str, bs: &[u8]) -> Result<Value> {
+    serde_json::from_slice(bs)
+        .map_err(|e| ParseError::with_locator(type_name, bs, e, |_| None).into())
+}
+
+/// Deserializes `value` as `T`, moving the fields that `T` reports as unknown to the returned [`Extra`].
+pub(crate) fn strip_unknown<T: DeserializeOwned>(
+    type_name: &'static str,
+    mut value: Value,
+) -> Result<(T, Extra)> {
+    let mut extra = Extra::new();
+    loop {
+        let e = match serde_path_to_error::deserialize(&value) {
+            Ok(entry) => return Ok((entry, extra)),
+            Err(e) => e,
+        };
+        let message = e.inner().to_string();
+        if ParseErrorKind::classify(&message) == ParseErrorKind::UnknownField {
+            if let Some(field) = remove(&mut value, e.path()) {
+                extra.insert(e.path().to_string(), field);
+                continue;
+            }
+        }
+        return Err(ParseError::at_path(type_name, &value, e.path(), message).into());
+    }
+}
+
+/// Removes the field at `path`.
+fn remove(value: &mut Value, path: &Path) -> Option<Value> {
+    let mut segments: Vec<&Segment> = path.iter().collect();
+    let key = match segments.pop()? {
+        Segment::Map { key } => key,
+        _ => return None,
+    };
+    let parent = segments
+        .into_iter()
+        .try_fold(value, |v, segment| match segment {
+            Segment::Seq { index } => v.get_mut(index),
+            Segment::Map { key } => v.get_mut(key),
+            _ => None,
+        })?;
+    parent.as_object_mut()?.remove(key)
+}
+
+/// Inserts `field` at `path`, written as by [`Path`]'s `Display`.
+fn insert(value: &mut Value, path: &str, field: Value) {
+    let (parent, key) = match path.rfind('.') {
+        Some(i) => (&path[..i], &path[i + 1..]),
+        None => ("", path),
+    };
+    let mut target = Some(value);
+    for part in parent.split('.').filter(|p| !p.is_empty()) {
+        let mut indices = part.split('[');
+        if let Some(key) = indices.next().filter(|k| !k.is_empty()) {
+            target = target.and_then(|v| v.get_mut(key));
+        }
+        for index in indices {
+            let index = index.trim_end_matches(']').parse::<usize>().ok();
+            target = target.and_then(|v| v.get_mut(index?));
+        }
+    }
+    if let Some(m) = target.and_then(Value::as_object_mut) {
+        m.insert(key.to_owned(), field);
+    }
+}
@@ -1,18 +1,21 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum::EnumIter;
-use variant_count::VariantCount;
 
+#[cfg(feature = "arbitrary")]
+use super::arb;
 use super::bgs;
 use super::dec::date_format;
 use super::system;
 use super::RootEntry;
 
 use super::util::DisplayViaSerde;
-use crate::display_via_serde;
+use crate::{display_via_serde, json_schema_via_serde, unknown_variant_serde};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct PowerPlay {
@@ -28,7 +31,12 @@ pub struct PowerPlay {
     pub state: Option<bgs::State>,
     // Metadata
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub date: DateTime<Utc>,
 }
 
@@ -37,6 +45,10 @@ impl RootEntry for PowerPlay {
         self.id
     }
 
+    fn entry_id64(&self) -> Option<u64> {
+        Some(self.id64)
+    }
+
     fn type_name() -> &'static str {
         "power_play"
     }
@@ -48,8 +60,10 @@ impl RootEntry for PowerPlay {
 
 // Field Type
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum Power {
     #[serde(rename = "A. Lavigny-Duval")]
@@ -74,12 +88,24 @@ pub enum Power {
     ZacharyHudson,
     #[serde(rename = "Zemina Torval")]
     ZeminaTorval,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl Power {
+    pub const VARIANT_COUNT: usize = 11;
 }
 
+unknown_variant_serde!(Power);
 display_via_serde!(Power);
+json_schema_via_serde!(Power);
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum PowerState {
     Contested,
@@ -89,6 +115,16 @@ pub enum PowerState {
     InPrepareRadius,
     Prepared,
     Turmoil,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl PowerState {
+    pub const VARIANT_COUNT: usize = 7;
 }
 
+unknown_variant_serde!(PowerState);
 display_via_serde!(PowerState);
+json_schema_via_serde!(PowerState);
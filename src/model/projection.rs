@@ -0,0 +1,410 @@
+//! Lightweight entries holding only a few fields of a dump record.
+//!
+//! Projections implement [`RootEntry`], so they can be read with the same readers as the full types,
+//! e.g. `DumpReader<_, SystemBrief>`.
+//! Keys not in the projection are skipped without being parsed into values or validated,
+//! so unknown fields are accepted without [`lenient`](super::lenient) parsing.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::de::{self, IntoDeserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(not(feature = "simd-json"))]
+use serde_json::from_slice;
+
+use super::body::{Body, Planet, PlanetSubType, Star, StarSubType, Unknown};
+use super::dec::date_format;
+use super::powerplay::PowerPlay;
+use super::system::{Coords, SystemWithCoordinates};
+use super::system_populated::SystemPopulated;
+use super::RootEntry;
+use crate::error::ParseError;
+
+// System
+
+/// `id`, `id64`, `name` and `coords` of a system.
+///
+/// Records of the dumps of systems with coordinates, populated systems and PowerPlay all have these fields,
+/// so any of them can be read as `SystemBrief`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[serde(rename_all = "camelCase")]
+pub struct SystemBrief {
+    pub id: u64,
+    // Attributes
+    pub coords: Coords,
+    pub id64: Option<u64>,
+    pub name: String,
+    // Metadata
+    #[serde(with = "date_format")]
+    #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    pub date: DateTime<Utc>,
+}
+
+impl RootEntry for SystemBrief {
+    fn entry_id(&self) -> u64 {
+        self.id
+    }
+
+    fn entry_id64(&self) -> Option<u64> {
+        self.id64
+    }
+
+    fn type_name() -> &'static str {
+        "system"
+    }
+
+    fn time(&self) -> DateTime<Utc> {
+        self.date
+    }
+}
+
+impl From<&SystemWithCoordinates> for SystemBrief {
+    fn from(x: &SystemWithCoordinates) -> SystemBrief {
+        SystemBrief {
+            id: x.id,
+            coords: x.coords,
+            id64: x.id64,
+            name: x.name.clone(),
+            date: x.date,
+        }
+    }
+}
+
+impl From<&SystemPopulated> for SystemBrief {
+    fn from(x: &SystemPopulated) -> SystemBrief {
+        SystemBrief {
+            id: x.id,
+            coords: x.coords,
+            id64: x.id64,
+            name: x.name.clone(),
+            date: x.date,
+        }
+    }
+}
+
+impl From<&PowerPlay> for SystemBrief {
+    fn from(x: &PowerPlay) -> SystemBrief {
+        SystemBrief {
+            id: x.id,
+            coords: x.coords,
+            id64: Some(x.id64),
+            name: x.name.clone(),
+            date: x.date,
+        }
+    }
+}
+
+// Body
+
+/// `subType`, `systemId64` and `distanceToArrival` of a body, tagged by its `type` field like [`Body`].
+///
+/// As with [`Body`], records whose `type` is null, or which fail to parse as their `type`,
+/// are [`BodyBrief::Unknown`] if they have the fields of [`UnknownBrief`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+pub enum BodyBrief {
+    Planet(PlanetBrief),
+    Star(StarBrief),
+    Unknown(UnknownBrief),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetBrief {
+    pub id: u64,
+    // Attributes
+    pub distance_to_arrival: u64,
+    pub id64: Option<u64>,
+    pub sub_type: PlanetSubType,
+    pub system_id64: Option<u64>,
+    // Metadata
+    #[serde(with = "date_format")]
+    #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    pub update_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[serde(rename_all = "camelCase")]
+pub struct StarBrief {
+    pub id: u64,
+    // Attributes
+    pub distance_to_arrival: u64,
+    pub id64: Option<u64>,
+    pub sub_type: StarSubType,
+    pub system_id64: Option<u64>,
+    // Metadata
+    #[serde(with = "date_format")]
+    #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    pub update_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownBrief {
+    pub id: u64,
+    // Attributes
+    pub id64: Option<u64>,
+    pub system_id64: Option<u64>,
+    // Metadata
+    #[serde(with = "date_format")]
+    #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    pub update_time: DateTime<Utc>,
+}
+
+impl BodyBrief {
+    pub fn id(&self) -> u64 {
+        match self {
+            BodyBrief::Planet(x) => x.id,
+            BodyBrief::Star(x) => x.id,
+            BodyBrief::Unknown(x) => x.id,
+        }
+    }
+
+    pub fn id64(&self) -> Option<u64> {
+        match self {
+            BodyBrief::Planet(x) => x.id64,
+            BodyBrief::Star(x) => x.id64,
+            BodyBrief::Unknown(x) => x.id64,
+        }
+    }
+
+    pub fn system_id64(&self) -> Option<u64> {
+        match self {
+            BodyBrief::Planet(x) => x.system_id64,
+            BodyBrief::Star(x) => x.system_id64,
+            BodyBrief::Unknown(x) => x.system_id64,
+        }
+    }
+
+    pub fn distance_to_arrival(&self) -> Option<u64> {
+        match self {
+            BodyBrief::Planet(x) => Some(x.distance_to_arrival),
+            BodyBrief::Star(x) => Some(x.distance_to_arrival),
+            BodyBrief::Unknown(_) => None,
+        }
+    }
+
+    pub fn update_time(&self) -> DateTime<Utc> {
+        match self {
+            BodyBrief::Planet(x) => x.update_time,
+            BodyBrief::Star(x) => x.update_time,
+            BodyBrief::Unknown(x) => x.update_time,
+        }
+    }
+}
+
+impl RootEntry for BodyBrief {
+    fn entry_id(&self) -> u64 {
+        self.id()
+    }
+
+    fn entry_id64(&self) -> Option<u64> {
+        self.id64()
+    }
+
+    fn type_name() -> &'static str {
+        "body"
+    }
+
+    fn time(&self) -> DateTime<Utc> {
+        self.update_time()
+    }
+
+    #[cfg(not(feature = "simd-json"))]
+    fn parse_dump_json(bs: &[u8]) -> Result<Self> {
+        match from_slice(bs) {
+            Ok(v) => Ok(v),
+            Err(e) => match from_slice(bs) {
+                Ok(v) => Ok(BodyBrief::Unknown(v)),
+                Err(_) => Err(ParseError::new::<Self>(Self::type_name(), bs, e).into()),
+            },
+        }
+    }
+
+    #[cfg(feature = "simd-json")]
+    fn parse_dump_json_mut(bs: &mut [u8]) -> Result<Self> {
+        let mut de = simd_json::Deserializer::from_slice(bs)
+            .map_err(|e| ParseError::from_simd(Self::type_name(), e))?;
+        match BodyBrief::deserialize(&mut de) {
+            Ok(v) => Ok(v),
+            Err(e) => {
+                de.restart();
+                match UnknownBrief::deserialize(&mut de) {
+                    Ok(v) => Ok(BodyBrief::Unknown(v)),
+                    Err(_) => Err(ParseError::from_simd(Self::type_name(), e).into()),
+                }
+            }
+        }
+    }
+}
+
+impl From<&Planet> for PlanetBrief {
+    fn from(x: &Planet) -> PlanetBrief {
+        PlanetBrief {
+            id: x.id,
+            distance_to_arrival: x.distance_to_arrival,
+            id64: x.id64,
+            sub_type: x.sub_type,
+            system_id64: x.system_id64,
+            update_time: x.update_time,
+        }
+    }
+}
+
+impl From<&Star> for StarBrief {
+    fn from(x: &Star) -> StarBrief {
+        StarBrief {
+            id: x.id,
+            distance_to_arrival: x.distance_to_arrival,
+            id64: x.id64,
+            sub_type: x.sub_type,
+            system_id64: x.system_id64,
+            update_time: x.update_time,
+        }
+    }
+}
+
+impl From<&Unknown> for UnknownBrief {
+    fn from(x: &Unknown) -> UnknownBrief {
+        UnknownBrief {
+            id: x.id,
+            id64: x.id64,
+            system_id64: x.system_id64,
+            update_time: x.update_time,
+        }
+    }
+}
+
+impl From<&Body> for BodyBrief {
+    fn from(x: &Body) -> BodyBrief {
+        match x {
+            Body::Planet(x) => BodyBrief::Planet(x.into()),
+            Body::Star(x) => BodyBrief::Star(x.into()),
+            Body::Unknown(x) => BodyBrief::Unknown(x.into()),
+        }
+    }
+}
+
+/// Union of the fields of each kind of body, deserialized in a single pass.
+///
+/// Unlike `#[serde(tag = "type")]`, which buffers the whole record to find the tag,
+/// this keeps only these fields and skips the others with [`IgnoredAny`](serde::de::IgnoredAny).
+#[derive(Deserialize)]
+#[serde(rename = "BodyBrief", rename_all = "camelCase")]
+struct FlatBodyBrief {
+    #[serde(rename = "type")]
+    typ: Option<String>,
+    id: u64,
+    distance_to_arrival: Option<u64>,
+    id64: Option<u64>,
+    sub_type: Option<String>,
+    system_id64: Option<u64>,
+    #[serde(with = "date_format")]
+    update_time: DateTime<Utc>,
+}
+
+impl FlatBodyBrief {
+    fn sub_type<'de, T: Deserialize<'de>, E: de::Error>(&self) -> Result<T, E> {
+        let sub_type = self
+            .sub_type
+            .as_deref()
+            .ok_or_else(|| de::Error::missing_field("subType"))?;
+        T::deserialize(sub_type.to_owned().into_deserializer())
+    }
+
+    fn distance_to_arrival<E: de::Error>(&self) -> Result<u64, E> {
+        self.distance_to_arrival
+            .ok_or_else(|| de::Error::missing_field("distanceToArrival"))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "BodyBrief", tag = "type")]
+enum TaggedBodyBriefRef<'a> {
+    Planet(&'a PlanetBrief),
+    Star(&'a StarBrief),
+    #[serde(rename = "null")]
+    Unknown(&'a UnknownBrief),
+}
+
+/// Layout for encodings that are not self-describing, as with [`BodyS`](super::body::BodyS).
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "BodyBriefS")]
+enum BodyBriefS {
+    Planet(PlanetBrief),
+    Star(StarBrief),
+    #[serde(rename = "null")]
+    Unknown(UnknownBrief),
+}
+
+#[derive(Serialize)]
+#[serde(rename = "BodyBriefS")]
+enum BodyBriefSRef<'a> {
+    Planet(&'a PlanetBrief),
+    Star(&'a StarBrief),
+    #[serde(rename = "null")]
+    Unknown(&'a UnknownBrief),
+}
+
+impl Serialize for BodyBrief {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            match self {
+                BodyBrief::Planet(x) => TaggedBodyBriefRef::Planet(x),
+                BodyBrief::Star(x) => TaggedBodyBriefRef::Star(x),
+                BodyBrief::Unknown(x) => TaggedBodyBriefRef::Unknown(x),
+            }
+            .serialize(serializer)
+        } else {
+            match self {
+                BodyBrief::Planet(x) => BodyBriefSRef::Planet(x),
+                BodyBrief::Star(x) => BodyBriefSRef::Star(x),
+                BodyBrief::Unknown(x) => BodyBriefSRef::Unknown(x),
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BodyBrief {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BodyBrief, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Ok(match BodyBriefS::deserialize(deserializer)? {
+                BodyBriefS::Planet(x) => BodyBrief::Planet(x),
+                BodyBriefS::Star(x) => BodyBrief::Star(x),
+                BodyBriefS::Unknown(x) => BodyBrief::Unknown(x),
+            });
+        }
+
+        let flat = FlatBodyBrief::deserialize(deserializer)?;
+        match flat.typ.as_deref() {
+            Some("Planet") => Ok(BodyBrief::Planet(PlanetBrief {
+                id: flat.id,
+                distance_to_arrival: flat.distance_to_arrival()?,
+                id64: flat.id64,
+                sub_type: flat.sub_type()?,
+                system_id64: flat.system_id64,
+                update_time: flat.update_time,
+            })),
+            Some("Star") => Ok(BodyBrief::Star(StarBrief {
+                id: flat.id,
+                distance_to_arrival: flat.distance_to_arrival()?,
+                id64: flat.id64,
+                sub_type: flat.sub_type()?,
+                system_id64: flat.system_id64,
+                update_time: flat.update_time,
+            })),
+            None | Some("null") => Ok(BodyBrief::Unknown(UnknownBrief {
+                id: flat.id,
+                id64: flat.id64,
+                system_id64: flat.system_id64,
+                update_time: flat.update_time,
+            })),
+            Some(typ) => Err(de::Error::unknown_variant(typ, &["Planet", "Star", "null"])),
+        }
+    }
+}
@@ -1,14 +1,18 @@
+use std::borrow::Cow;
+use std::fmt;
+
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use strum::EnumIter;
-use variant_count::VariantCount;
 
+#[cfg(feature = "arbitrary")]
+use super::arb;
 use super::bgs;
-use super::dec::{date_format, date_format_opt};
-use super::RootEntry;
+use super::dec::{cow_str_opt, date_format, date_format_opt};
+use super::{RootEntry, RootEntryRef};
 
 use super::util::DisplayViaSerde;
-use crate::display_via_serde;
+use crate::{display_via_serde, json_schema_via_serde, unknown_variant_serde};
 
 use serde;
 
@@ -16,6 +20,8 @@ use serde;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Station {
@@ -25,6 +31,7 @@ pub struct Station {
     pub body: Option<StationBody>,
     pub commodities: Option<Vec<Commodity>>,
     pub controlling_faction: Option<bgs::ControllingFaction>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub distance_to_arrival: Option<f32>,
     pub economy: Option<bgs::Economy>,
     pub government: Option<bgs::Government>,
@@ -60,25 +67,233 @@ impl RootEntry for Station {
     }
 }
 
+// Borrowed Type
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct StationRef<'a> {
+    pub id: u64,
+    // Attributes
+    pub allegiance: Option<bgs::Allegiance>,
+    #[serde(borrow)]
+    pub body: Option<StationBodyRef<'a>>,
+    #[serde(borrow)]
+    pub commodities: Option<Vec<CommodityRef<'a>>>,
+    #[serde(borrow)]
+    pub controlling_faction: Option<bgs::ControllingFactionRef<'a>>,
+    pub distance_to_arrival: Option<f32>,
+    pub economy: Option<bgs::Economy>,
+    pub government: Option<bgs::Government>,
+    pub have_market: bool,
+    pub have_outfitting: bool,
+    pub have_shipyard: bool,
+    pub market_id: Option<u64>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub other_services: Vec<OtherService>,
+    #[serde(borrow)]
+    pub outfitting: Option<Vec<OutfittingRef<'a>>>,
+    pub second_economy: Option<bgs::Economy>,
+    #[serde(borrow)]
+    pub ships: Option<Vec<ShipRef<'a>>>,
+    pub system_id: Option<u64>,
+    pub system_id64: Option<u64>,
+    #[serde(borrow, deserialize_with = "cow_str_opt::deserialize", default)]
+    pub system_name: Option<Cow<'a, str>>,
+    #[serde(rename = "type")]
+    pub typ: Option<StationType>,
+    // Metadata
+    pub update_time: UpdateTime,
+}
+
+impl<'a> StationRef<'a> {
+    pub fn to_owned(&self) -> Station {
+        Station {
+            id: self.id,
+            allegiance: self.allegiance.clone(),
+            body: self.body.as_ref().map(StationBodyRef::to_owned),
+            commodities: self
+                .commodities
+                .as_ref()
+                .map(|xs| xs.iter().map(CommodityRef::to_owned).collect()),
+            controlling_faction: self
+                .controlling_faction
+                .as_ref()
+                .map(bgs::ControllingFactionRef::to_owned),
+            distance_to_arrival: self.distance_to_arrival,
+            economy: self.economy.clone(),
+            government: self.government.clone(),
+            have_market: self.have_market,
+            have_outfitting: self.have_outfitting,
+            have_shipyard: self.have_shipyard,
+            market_id: self.market_id,
+            name: self.name.clone().into_owned(),
+            other_services: self.other_services.clone(),
+            outfitting: self
+                .outfitting
+                .as_ref()
+                .map(|xs| xs.iter().map(OutfittingRef::to_owned).collect()),
+            second_economy: self.second_economy.clone(),
+            ships: self
+                .ships
+                .as_ref()
+                .map(|xs| xs.iter().map(ShipRef::to_owned).collect()),
+            system_id: self.system_id,
+            system_id64: self.system_id64,
+            system_name: self.system_name.as_deref().map(str::to_owned),
+            typ: self.typ.clone(),
+            update_time: self.update_time.clone(),
+        }
+    }
+}
+
+impl<'a> RootEntryRef<'a> for StationRef<'a> {
+    type Owned = Station;
+
+    fn to_owned_entry(&self) -> Station {
+        self.to_owned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct CommodityRef<'a> {
+    #[serde(borrow, deserialize_with = "cow_str_opt::deserialize", default)]
+    pub id: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    // Attributes
+    pub buy_price: u64,
+    pub demand: u64,
+    pub sell_price: u64,
+    pub stock: u64,
+    pub stock_bracket: u64,
+}
+
+impl<'a> CommodityRef<'a> {
+    pub fn to_owned(&self) -> Commodity {
+        Commodity {
+            id: self.id.as_deref().map(str::to_owned),
+            name: self.name.clone().into_owned(),
+            buy_price: self.buy_price,
+            demand: self.demand,
+            sell_price: self.sell_price,
+            stock: self.stock,
+            stock_bracket: self.stock_bracket,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct OutfittingRef<'a> {
+    #[serde(borrow, deserialize_with = "cow_str_opt::deserialize", default)]
+    pub id: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+}
+
+impl<'a> OutfittingRef<'a> {
+    pub fn to_owned(&self) -> Outfitting {
+        Outfitting {
+            id: self.id.as_deref().map(str::to_owned),
+            name: self.name.clone().into_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NameRef<'a> {
+    String(#[serde(borrow)] Cow<'a, str>),
+    Number(u64),
+}
+
+impl<'a> NameRef<'a> {
+    pub fn to_owned(&self) -> Name {
+        match self {
+            NameRef::String(s) => Name::String(s.clone().into_owned()),
+            NameRef::Number(n) => Name::Number(*n),
+        }
+    }
+}
+
+impl<'a> fmt::Display for NameRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NameRef::String(s) => write!(f, "{}", s),
+            NameRef::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct ShipRef<'a> {
+    pub id: u64,
+    #[serde(borrow)]
+    pub name: NameRef<'a>,
+}
+
+impl<'a> ShipRef<'a> {
+    pub fn to_owned(&self) -> Ship {
+        Ship {
+            id: self.id,
+            name: self.name.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct StationBodyRef<'a> {
+    pub id: u64,
+    // Attributes
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+}
+
+impl<'a> StationBodyRef<'a> {
+    pub fn to_owned(&self) -> StationBody {
+        StationBody {
+            id: self.id,
+            latitude: self.latitude,
+            longitude: self.longitude,
+            name: self.name.clone().into_owned(),
+        }
+    }
+}
+
 // Filed Type
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Commodity {
-    id: Option<String>,
-    name: String,
+    pub id: Option<String>,
+    pub name: String,
     // Attributes
-    buy_price: u64,
-    demand: u64,
-    sell_price: u64,
-    stock: u64,
-    stock_bracket: u64,
+    pub buy_price: u64,
+    pub demand: u64,
+    pub sell_price: u64,
+    pub stock: u64,
+    pub stock_bracket: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum OtherService {
     #[serde(rename = "Black Market")]
@@ -109,59 +324,139 @@ pub enum OtherService {
     PioneerSupplies,
     #[serde(rename = "Vista Genomics")]
     VistaGenomics,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl OtherService {
+    pub const VARIANT_COUNT: usize = 17;
 }
 
+unknown_variant_serde!(OtherService);
 display_via_serde!(OtherService);
+json_schema_via_serde!(OtherService);
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Outfitting {
-    id: Option<String>,
-    name: String,
+    pub id: Option<String>,
+    pub name: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(untagged)]
+/// Ship name, either a string or a number.
+///
+/// Encodings that are not self-describing, such as bincode, tag the variant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Name {
     String(String),
     Number(u64),
 }
 
-impl ToString for Name {
-    fn to_string(&self) -> String {
+#[derive(Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename = "Name", untagged)]
+enum UntaggedName {
+    String(String),
+    Number(u64),
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Name")]
+enum TaggedName {
+    String(String),
+    Number(u64),
+}
+
+impl Serialize for Name {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (self, serializer.is_human_readable()) {
+            (Name::String(s), true) => serializer.serialize_str(s),
+            (Name::Number(n), true) => serializer.serialize_u64(*n),
+            (Name::String(s), false) => {
+                serializer.serialize_newtype_variant("Name", 0, "String", s)
+            }
+            (Name::Number(n), false) => {
+                serializer.serialize_newtype_variant("Name", 1, "Number", n)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Name {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Name, D::Error> {
+        if deserializer.is_human_readable() {
+            Ok(match UntaggedName::deserialize(deserializer)? {
+                UntaggedName::String(s) => Name::String(s),
+                UntaggedName::Number(n) => Name::Number(n),
+            })
+        } else {
+            Ok(match TaggedName::deserialize(deserializer)? {
+                TaggedName::String(s) => Name::String(s),
+                TaggedName::Number(n) => Name::Number(n),
+            })
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Name {
+    fn schema_name() -> String {
+        "Name".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        UntaggedName::json_schema(gen)
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Name::String(s) => s.clone(),
-            Name::Number(n) => n.to_string(),
+            Name::String(s) => write!(f, "{}", s),
+            Name::Number(n) => write!(f, "{}", n),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Ship {
-    id: u64,
-    name: Name,
+    pub id: u64,
+    pub name: Name,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct StationBody {
     pub id: u64,
     // Attributes
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub latitude: Option<f32>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub longitude: Option<f32>,
     pub name: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, VariantCount)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[cfg_attr(feature = "unknown_variants", serde(remote = "Self"))]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(deny_unknown_fields)]
 pub enum StationType {
     // Orbital Large
@@ -187,29 +482,61 @@ pub enum StationType {
     // Fleet Carrier
     #[serde(rename = "Fleet Carrier")]
     FleetCarrier,
+    #[cfg(feature = "unknown_variants")]
+    #[serde(skip)]
+    #[strum(disabled)]
+    Unknown(#[cfg_attr(feature = "arbitrary", arbitrary(with = arb::unknown_variant))] String),
+}
+
+impl StationType {
+    pub const VARIANT_COUNT: usize = 10;
 }
 
+unknown_variant_serde!(StationType);
 display_via_serde!(StationType);
+json_schema_via_serde!(StationType);
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct UpdateTime {
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub information: DateTime<Utc>,
     #[serde(with = "date_format_opt")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format_opt::json_schema")
+    )]
     #[serde(default = "option_none")]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date_opt))]
     pub market: Option<DateTime<Utc>>,
     #[serde(with = "date_format_opt")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format_opt::json_schema")
+    )]
     #[serde(default = "option_none")]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date_opt))]
     pub outfitting: Option<DateTime<Utc>>,
     #[serde(with = "date_format_opt")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format_opt::json_schema")
+    )]
     #[serde(default = "option_none")]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date_opt))]
     pub shipyard: Option<DateTime<Utc>>,
 }
 
@@ -1,15 +1,20 @@
+use std::borrow::Cow;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "arbitrary")]
+use super::arb;
 use super::dec::date_format;
-use super::RootEntry;
+use super::{RootEntry, RootEntryRef};
 
 // Main Type
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct SystemWithCoordinates {
@@ -20,7 +25,12 @@ pub struct SystemWithCoordinates {
     pub name: String,
     // Metadata
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub date: DateTime<Utc>,
 }
 
@@ -29,6 +39,10 @@ impl RootEntry for SystemWithCoordinates {
         self.id
     }
 
+    fn entry_id64(&self) -> Option<u64> {
+        self.id64
+    }
+
     fn type_name() -> &'static str {
         "system"
     }
@@ -55,6 +69,8 @@ impl System for SystemWithCoordinates {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct SystemWithoutCoordinates {
@@ -65,7 +81,12 @@ pub struct SystemWithoutCoordinates {
     pub name: String,
     // Metadata
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub date: DateTime<Utc>,
 }
 
@@ -74,6 +95,10 @@ impl RootEntry for SystemWithoutCoordinates {
         self.id
     }
 
+    fn entry_id64(&self) -> Option<u64> {
+        self.id64
+    }
+
     fn type_name() -> &'static str {
         "system_without_coordinates"
     }
@@ -105,15 +130,122 @@ pub trait System {
     fn date(&self) -> DateTime<Utc>;
 }
 
+// Borrowed Type
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct SystemWithCoordinatesRef<'a> {
+    pub id: u64,
+    // Attributes
+    pub coords: Coords,
+    pub id64: Option<u64>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    // Metadata
+    #[serde(with = "date_format")]
+    pub date: DateTime<Utc>,
+}
+
+impl<'a> SystemWithCoordinatesRef<'a> {
+    pub fn to_owned(&self) -> SystemWithCoordinates {
+        SystemWithCoordinates {
+            id: self.id,
+            coords: self.coords,
+            id64: self.id64,
+            name: self.name.clone().into_owned(),
+            date: self.date,
+        }
+    }
+}
+
+impl<'a> RootEntryRef<'a> for SystemWithCoordinatesRef<'a> {
+    type Owned = SystemWithCoordinates;
+
+    fn to_owned_entry(&self) -> SystemWithCoordinates {
+        self.to_owned()
+    }
+}
+
+impl<'a> System for SystemWithCoordinatesRef<'a> {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn id64(&self) -> Option<u64> {
+        self.id64
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn date(&self) -> DateTime<Utc> {
+        self.date
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct SystemWithoutCoordinatesRef<'a> {
+    pub id: u64,
+    // Attributes
+    pub estimated_coordinates: Option<EstimatedCoords>,
+    pub id64: Option<u64>,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    // Metadata
+    #[serde(with = "date_format")]
+    pub date: DateTime<Utc>,
+}
+
+impl<'a> SystemWithoutCoordinatesRef<'a> {
+    pub fn to_owned(&self) -> SystemWithoutCoordinates {
+        SystemWithoutCoordinates {
+            id: self.id,
+            estimated_coordinates: self.estimated_coordinates.clone(),
+            id64: self.id64,
+            name: self.name.clone().into_owned(),
+            date: self.date,
+        }
+    }
+}
+
+impl<'a> RootEntryRef<'a> for SystemWithoutCoordinatesRef<'a> {
+    type Owned = SystemWithoutCoordinates;
+
+    fn to_owned_entry(&self) -> SystemWithoutCoordinates {
+        self.to_owned()
+    }
+}
+
+impl<'a> System for SystemWithoutCoordinatesRef<'a> {
+    fn id(&self) -> u64 {
+        self.id
+    }
+    fn id64(&self) -> Option<u64> {
+        self.id64
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn date(&self) -> DateTime<Utc> {
+        self.date
+    }
+}
+
 // Field Type
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Coords {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub x: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub y: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub z: f32,
 }
 
@@ -224,11 +356,17 @@ impl SubAssign for Coords {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct EstimatedCoords {
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub x: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub y: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub z: f32,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub precision: f32,
 }
@@ -2,6 +2,8 @@ use chrono::serde::ts_seconds;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "arbitrary")]
+use super::arb;
 use super::bgs;
 use super::body;
 use super::dec::date_format;
@@ -13,6 +15,8 @@ use super::RootEntry;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct SystemPopulated {
@@ -33,7 +37,12 @@ pub struct SystemPopulated {
     pub stations: Vec<StationInPopulated>,
     // Metadata
     #[serde(with = "date_format")]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(schema_with = "date_format::json_schema")
+    )]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub date: DateTime<Utc>,
 }
 
@@ -42,6 +51,10 @@ impl RootEntry for SystemPopulated {
         self.id
     }
 
+    fn entry_id64(&self) -> Option<u64> {
+        self.id64
+    }
+
     fn type_name() -> &'static str {
         "system_populated"
     }
@@ -55,6 +68,8 @@ impl RootEntry for SystemPopulated {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct FactionInPopulated {
@@ -64,6 +79,7 @@ pub struct FactionInPopulated {
     pub allegiance: Option<bgs::Allegiance>,
     pub government: Option<bgs::Government>,
     pub happiness: Option<bgs::Happiness>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32))]
     pub influence: f32,
     pub is_player: bool,
     pub name: String,
@@ -72,12 +88,16 @@ pub struct FactionInPopulated {
     pub state: Option<bgs::State>,
     // Metadata
     #[serde(with = "ts_seconds")]
+    #[cfg_attr(feature = "schemars", schemars(with = "i64"))]
     #[cfg_attr(feature = "type_hash", type_hash(foreign_type))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::date))]
     pub last_update: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "type_hash", derive(type_hash::TypeHash))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct StationInPopulated {
@@ -86,6 +106,7 @@ pub struct StationInPopulated {
     pub allegiance: Option<bgs::Allegiance>,
     pub body: Option<station::StationBody>,
     pub controlling_faction: Option<bgs::ControllingFaction>,
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arb::f32_opt))]
     pub distance_to_arrival: Option<f32>,
     pub economy: Option<bgs::Economy>,
     pub government: Option<bgs::Government>,
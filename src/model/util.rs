@@ -1,12 +1,16 @@
 use std::fmt;
 
+#[cfg(feature = "unknown_variants")]
+use serde::Deserialize;
 use serde::Serialize;
-use serde_json::to_string;
+use serde_json::{to_value, Value};
 
 pub trait DisplayViaSerde: Serialize {
     fn display_via_serde(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let s = to_string(self).map_err(|_| fmt::Error)?;
-        write!(f, "{}", s.trim_start_matches('"').trim_end_matches('"'))
+        match to_value(self).map_err(|_| fmt::Error)? {
+            Value::String(s) => f.write_str(&s),
+            v => write!(f, "{}", v),
+        }
     }
 }
 
@@ -22,3 +26,116 @@ macro_rules! display_via_serde {
         }
     };
 }
+
+/// Implements `Serialize` and `Deserialize` for a string enum with the `unknown_variants` feature,
+/// holding the values not known to this crate in its `Unknown(String)` variant.
+///
+/// The known variants are derived with `#[serde(remote = "Self")]`.
+/// Every variant is encoded as its string value, also in formats that are not self-describing such as bincode.
+#[macro_export]
+macro_rules! unknown_variant_serde {
+    ($t:ident) => {
+        #[cfg(feature = "unknown_variants")]
+        impl serde::Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self {
+                    $t::Unknown(value) => serializer.serialize_str(value),
+                    known => {
+                        let value = $t::serialize(known, serde_json::value::Serializer);
+                        $crate::model::util::serialize_known(value, serializer)
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "unknown_variants")]
+        impl<'de> serde::Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                let value = $crate::model::util::UnknownValue::deserialize(deserializer)?.0;
+                let de = serde::de::value::StrDeserializer::<serde::de::value::Error>::new(&value);
+                Ok($t::deserialize(de).unwrap_or_else(|_| $t::Unknown(value.into_owned())))
+            }
+        }
+    };
+}
+
+/// String value of a string enum, borrowed from the input when possible.
+#[cfg(feature = "unknown_variants")]
+#[derive(Deserialize)]
+pub(crate) struct UnknownValue<'a>(#[serde(borrow)] pub(crate) std::borrow::Cow<'a, str>);
+
+/// Serializes the string value of a known variant, as serialized to JSON by its derived implementation.
+#[cfg(feature = "unknown_variants")]
+pub(crate) fn serialize_known<S: serde::Serializer>(
+    value: serde_json::Result<Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::Error;
+
+    match value.map_err(S::Error::custom)? {
+        Value::String(s) => serializer.serialize_str(&s),
+        v => Err(S::Error::custom(format!(
+            "{} is not a string enum value",
+            v
+        ))),
+    }
+}
+
+/// Implements `schemars::JsonSchema` for a string enum, listing the serde names of its variants.
+///
+/// With the `unknown_variants` feature, any string is accepted.
+#[macro_export]
+macro_rules! json_schema_via_serde {
+    ($t:ty) => {
+        #[cfg(feature = "schemars")]
+        impl schemars::JsonSchema for $t {
+            fn schema_name() -> String {
+                stringify!($t).to_owned()
+            }
+
+            fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+                $crate::model::util::string_enum_schema(<$t as strum::IntoEnumIterator>::iter())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "schemars")]
+pub(crate) fn string_enum_schema<T: Serialize>(
+    variants: impl Iterator<Item = T>,
+) -> schemars::schema::Schema {
+    use schemars::schema::{InstanceType, SchemaObject};
+
+    let enum_values = if cfg!(feature = "unknown_variants") {
+        None
+    } else {
+        Some(
+            variants
+                .map(|v| serde_json::to_value(v).expect("enum variant should serialize"))
+                .collect(),
+        )
+    };
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values,
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Converts an enum variant name such as `CarbonDioxide` to `carbon_dioxide`.
+#[cfg(any(feature = "arrow", feature = "csv"))]
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut s = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                s.push('_');
+            }
+            s.extend(c.to_lowercase());
+        } else {
+            s.push(c);
+        }
+    }
+    s
+}
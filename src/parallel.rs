@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use anyhow::Result;
+use rayon::prelude::*;
+
+use crate::model::RootEntry;
+use crate::reader::{at_position, Position, RecordLines};
+
+const DEFAULT_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Record-aligned block of a dump file.
+struct Chunk {
+    data: Vec<u8>,
+    records: Vec<(Position, Range<usize>)>,
+}
+
+impl Chunk {
+    /// Splits the chunk into the positions and bytes of its records.
+    fn records_mut(&mut self) -> Vec<(Position, &mut [u8])> {
+        let mut records = Vec::with_capacity(self.records.len());
+        let mut rest = &mut self.data[..];
+        let mut consumed = 0;
+        for (position, range) in &self.records {
+            let (_, tail) = rest.split_at_mut(range.start - consumed);
+            let (record, tail) = tail.split_at_mut(range.len());
+            records.push((*position, record));
+            rest = tail;
+            consumed = range.end;
+        }
+        records
+    }
+}
+
+fn parse<T: RootEntry>((position, bs): (Position, &mut [u8])) -> Result<T> {
+    T::parse_dump_json_mut(bs).map_err(|e| at_position(e, position))
+}
+
+/// Parallel counterpart of [`DumpReader`](crate::reader::DumpReader).
+///
+/// The input is read on the calling thread in record-aligned chunks of
+/// about `chunk_size` bytes, which are parsed on the rayon thread pool
+/// while the next chunk is being read.
+///
+/// Iterating yields entries in input order.
+/// [`for_each_unordered`](ParDumpReader::for_each_unordered) drops the
+/// ordering guarantee to avoid waiting for slow chunks.
+pub struct ParDumpReader<R, T> {
+    lines: RecordLines<R>,
+    chunk_size: usize,
+    parsed: VecDeque<Result<T>>,
+    pending: Option<Chunk>,
+    error: Option<anyhow::Error>,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead + Send, T: RootEntry> ParDumpReader<R, T> {
+    pub fn new(inner: R) -> ParDumpReader<R, T> {
+        ParDumpReader {
+            lines: RecordLines::new(inner),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            parsed: VecDeque::new(),
+            pending: None,
+            error: None,
+            _entry: PhantomData,
+        }
+    }
+
+    /// Sets the approximate size of chunks in bytes.
+    pub fn chunk_size(mut self, chunk_size: usize) -> ParDumpReader<R, T> {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Parses all entries, calling `f` from worker threads in no particular order.
+    ///
+    /// Reading stops at the first I/O error, which is passed to `f` last.
+    pub fn for_each_unordered<F>(mut self, f: F)
+    where
+        F: Fn(Result<T>) + Sync + Send,
+    {
+        for entry in self.parsed.drain(..) {
+            f(entry);
+        }
+
+        let batch_len = rayon::current_num_threads().max(1) * 2;
+        let mut batch: Vec<Chunk> = self.pending.take().into_iter().collect();
+        batch.extend(self.read_batch(batch_len));
+        while !batch.is_empty() {
+            let (_, next) = rayon::join(
+                || {
+                    batch.par_iter_mut().for_each(|chunk| {
+                        chunk
+                            .records_mut()
+                            .into_par_iter()
+                            .for_each(|record| f(parse(record)))
+                    })
+                },
+                || self.read_batch(batch_len),
+            );
+            batch = next;
+        }
+
+        if let Some(e) = self.error.take() {
+            f(Err(e));
+        }
+    }
+
+    fn read_batch(&mut self, len: usize) -> Vec<Chunk> {
+        let mut batch = Vec::with_capacity(len);
+        while batch.len() < len {
+            match self.read_chunk() {
+                Some(chunk) => batch.push(chunk),
+                None => break,
+            }
+        }
+        batch
+    }
+
+    fn read_chunk(&mut self) -> Option<Chunk> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        let mut chunk = Chunk {
+            data: Vec::with_capacity(self.chunk_size + self.chunk_size / 8),
+            records: Vec::new(),
+        };
+        while chunk.data.len() < self.chunk_size {
+            match self.lines.read_into(&mut chunk.data) {
+                Ok(Some(record)) => chunk.records.push(record),
+                Ok(None) => break,
+                Err(e) => {
+                    self.error = Some(e);
+                    break;
+                }
+            }
+        }
+
+        if chunk.records.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+impl<R: BufRead + Send, T: RootEntry> Iterator for ParDumpReader<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if let Some(entry) = self.parsed.pop_front() {
+            return Some(entry);
+        }
+
+        let mut chunk = match self.pending.take().or_else(|| self.read_chunk()) {
+            Some(chunk) => chunk,
+            None => return self.error.take().map(Err),
+        };
+
+        let (parsed, pending) = rayon::join(
+            || {
+                chunk
+                    .records_mut()
+                    .into_par_iter()
+                    .map(parse)
+                    .collect::<Vec<_>>()
+            },
+            || self.read_chunk(),
+        );
+        self.parsed.extend(parsed);
+        self.pending = pending;
+
+        self.parsed.pop_front()
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt;
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::compression::{self, io_error};
+use crate::error::ParseError;
+use crate::model::any::{AnyEntry, DumpKind};
+use crate::model::{RootEntry, RootEntryRef};
+use crate::recovery::SkipErrors;
+
+/// Location of a record in a dump file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+pub struct Position {
+    /// 1-based line number.
+    pub line: u64,
+    /// Byte offset of the start of the line.
+    pub offset: u64,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at line {} (byte offset {})", self.line, self.offset)
+    }
+}
+
+/// Iterator over the entries of an EDSM dump file.
+///
+/// EDSM dumps are a JSON array with one entry per line:
+/// a `[` line, entry lines terminated by `,`, and a `]` line.
+/// Errors are annotated with the [`Position`] of the failed line,
+/// which can be recovered with `anyhow::Error::downcast_ref`
+/// along with the [`ParseError`] of entries that fail to parse.
+pub struct DumpReader<R, T> {
+    lines: RecordLines<R>,
+    buf: Vec<u8>,
+    position: Position,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: RootEntry> DumpReader<R, T> {
+    pub fn new(inner: R) -> DumpReader<R, T> {
+        DumpReader {
+            lines: RecordLines::new(inner),
+            buf: Vec::new(),
+            position: Position::default(),
+            _entry: PhantomData,
+        }
+    }
+
+    /// Position of the last line returned by the iterator.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Reads the next entry, borrowing its strings from the reader's line buffer.
+    pub fn next_ref<'a, B>(&'a mut self) -> Option<Result<B>>
+    where
+        B: RootEntryRef<'a, Owned = T>,
+    {
+        let range = match self.read_record()? {
+            Ok(range) => range,
+            Err(e) => return Some(Err(e)),
+        };
+        let position = self.position;
+
+        Some(B::parse_dump_json(&self.buf[range]).map_err(|e| at_position(e, position)))
+    }
+
+    /// Skips entries that fail to parse, collecting them into an [`ErrorReport`](crate::recovery::ErrorReport).
+    pub fn skip_errors(self) -> SkipErrors<R, T> {
+        SkipErrors::new(self)
+    }
+
+    /// Reads the next record line into the buffer, returning its range.
+    pub(crate) fn read_record(&mut self) -> Option<Result<Range<usize>>> {
+        self.buf.clear();
+        match self.lines.read_into(&mut self.buf) {
+            Ok(Some((position, range))) => {
+                self.position = position;
+                Some(Ok(range))
+            }
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    pub(crate) fn record(&self, range: Range<usize>) -> &[u8] {
+        &self.buf[range]
+    }
+
+    pub(crate) fn record_mut(&mut self, range: Range<usize>) -> &mut [u8] {
+        &mut self.buf[range]
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.lines.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.lines.inner
+    }
+}
+
+impl<T: RootEntry> DumpReader<Box<dyn BufRead + Send>, T> {
+    /// Opens a dump file, decompressing it if it is compressed.
+    ///
+    /// See [`compression::open`] for the supported formats.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(DumpReader::new(compression::open(path)?))
+    }
+}
+
+impl<R: BufRead, T: RootEntry> Iterator for DumpReader<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        let range = match self.read_record()? {
+            Ok(range) => range,
+            Err(e) => return Some(Err(e)),
+        };
+        let position = self.position;
+
+        Some(T::parse_dump_json_mut(&mut self.buf[range]).map_err(|e| at_position(e, position)))
+    }
+}
+
+/// Iterator over the entries of a dump whose kind is known only at runtime.
+pub struct AnyDumpReader<R> {
+    kind: DumpKind,
+    lines: RecordLines<R>,
+    buf: Vec<u8>,
+    position: Position,
+}
+
+impl<R: BufRead> AnyDumpReader<R> {
+    pub fn new(kind: DumpKind, inner: R) -> AnyDumpReader<R> {
+        AnyDumpReader {
+            kind,
+            lines: RecordLines::new(inner),
+            buf: Vec::new(),
+            position: Position::default(),
+        }
+    }
+
+    pub fn kind(&self) -> DumpKind {
+        self.kind
+    }
+
+    /// Position of the last line returned by the iterator.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.lines.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.lines.inner
+    }
+}
+
+impl AnyDumpReader<Box<dyn BufRead + Send>> {
+    /// Opens a dump file, detecting its kind from the file name.
+    ///
+    /// See [`DumpKind::from_file_name`] for the recognized names.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let kind = DumpKind::from_path(path)
+            .with_context(|| format!("unknown dump file name: {}", path.display()))?;
+        Ok(AnyDumpReader::new(kind, compression::open(path)?))
+    }
+}
+
+impl<R: BufRead> Iterator for AnyDumpReader<R> {
+    type Item = Result<AnyEntry>;
+
+    fn next(&mut self) -> Option<Result<AnyEntry>> {
+        self.buf.clear();
+        let (position, range) = match self.lines.read_into(&mut self.buf) {
+            Ok(Some(x)) => x,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(e)),
+        };
+        self.position = position;
+
+        Some(
+            self.kind
+                .parse_dump_json_mut(&mut self.buf[range])
+                .map_err(|e| at_position(e, position)),
+        )
+    }
+}
+
+/// Annotates a parse error with the position of its record.
+pub(crate) fn at_position(mut e: anyhow::Error, position: Position) -> anyhow::Error {
+    if let Some(parse_error) = e.downcast_mut::<ParseError>() {
+        parse_error.position = Some(position);
+    }
+    e.context(position)
+}
+
+/// Splits a dump into record lines, tracking their positions.
+pub(crate) struct RecordLines<R> {
+    inner: R,
+    next_line: u64,
+    next_offset: u64,
+    finished: bool,
+}
+
+impl<R: BufRead> RecordLines<R> {
+    pub(crate) fn new(inner: R) -> RecordLines<R> {
+        RecordLines {
+            inner,
+            next_line: 1,
+            next_offset: 0,
+            finished: false,
+        }
+    }
+
+    #[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Appends the next record line to `buf`, returning its position and its range in `buf`.
+    pub(crate) fn read_into(
+        &mut self,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<(Position, Range<usize>)>> {
+        while !self.finished {
+            let start = buf.len();
+            let position = Position {
+                line: self.next_line,
+                offset: self.next_offset,
+            };
+
+            let n = match self.inner.read_until(b'\n', buf) {
+                Ok(n) => n,
+                Err(e) => {
+                    self.finished = true;
+                    return Err(io_error(e)).with_context(|| format!("reading dump {}", position));
+                }
+            };
+            if n == 0 {
+                self.finished = true;
+                break;
+            }
+            self.next_line += 1;
+            self.next_offset += n as u64;
+
+            let range = record_range(&buf[start..]);
+            match &buf[start..][range.clone()] {
+                b"" | b"[" => buf.truncate(start),
+                b"]" => {
+                    buf.truncate(start);
+                    self.finished = true;
+                }
+                _ => return Ok(Some((position, start + range.start..start + range.end))),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Strips surrounding whitespace and the trailing `,` from a dump line.
+pub fn trim_record(line: &[u8]) -> &[u8] {
+    &line[record_range(line)]
+}
+
+fn record_range(line: &[u8]) -> Range<usize> {
+    let range = trim_ascii(line, 0..line.len());
+    if line[range.clone()].ends_with(b",") {
+        trim_ascii(line, range.start..range.end - 1)
+    } else {
+        range
+    }
+}
+
+fn trim_ascii(bs: &[u8], range: Range<usize>) -> Range<usize> {
+    let s = &bs[range.clone()];
+    let start = s
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(s.len());
+    let end = s
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |p| p + 1);
+    range.start + start..range.start + end
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::model::RootEntry;
+use crate::reader::{DumpReader, Position};
+
+pub(crate) const DEFAULT_MAX_SAMPLES: usize = 10;
+
+/// Summary of the entries skipped by [`SkipErrors`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorReport {
+    pub type_name: &'static str,
+    /// Number of entries parsed successfully.
+    pub parsed: u64,
+    /// Number of entries skipped.
+    pub skipped: u64,
+    pub kinds: BTreeMap<ParseErrorKind, ErrorKindReport>,
+}
+
+impl ErrorReport {
+    pub(crate) fn new(type_name: &'static str) -> ErrorReport {
+        ErrorReport {
+            type_name,
+            parsed: 0,
+            skipped: 0,
+            kinds: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.skipped == 0
+    }
+
+    /// Whether a failure of `e`'s kind would still be kept as a sample.
+    #[cfg(feature = "simd-json")]
+    fn wants_sample(&self, e: &anyhow::Error, max_samples: usize) -> bool {
+        let kind = kind_of(e);
+        self.kinds.get(&kind).map_or(0, |k| k.samples.len()) < max_samples
+    }
+
+    pub(crate) fn record_error(
+        &mut self,
+        max_samples: usize,
+        position: Position,
+        raw: &[u8],
+        e: anyhow::Error,
+    ) {
+        self.skipped += 1;
+        let kind_report = self.kinds.entry(kind_of(&e)).or_default();
+        kind_report.count += 1;
+        if kind_report.samples.len() >= max_samples {
+            return;
+        }
+
+        let raw = String::from_utf8_lossy(raw).into_owned();
+        let sample = match e.downcast::<ParseError>() {
+            Ok(e) => ErrorSample {
+                position,
+                id: e.id,
+                message: e.message().to_owned(),
+                path: e.path,
+                value: e.value,
+                raw,
+            },
+            Err(e) => ErrorSample {
+                position,
+                id: None,
+                path: None,
+                value: None,
+                message: format!("{:#}", e),
+                raw,
+            },
+        };
+        kind_report.samples.push(sample);
+    }
+}
+
+fn kind_of(e: &anyhow::Error) -> ParseErrorKind {
+    e.downcast_ref::<ParseError>()
+        .map_or(ParseErrorKind::Other, |e| e.kind)
+}
+
+/// Failures of one [`ParseErrorKind`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ErrorKindReport {
+    pub count: u64,
+    /// The first failures of this kind.
+    pub samples: Vec<ErrorSample>,
+}
+
+/// A skipped entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorSample {
+    pub position: Position,
+    pub id: Option<u64>,
+    pub path: Option<String>,
+    pub value: Option<Value>,
+    pub message: String,
+    /// The record line, without the trailing `,`.
+    pub raw: String,
+}
+
+/// Iterator over the entries of a dump that skips entries failing to parse.
+///
+/// Created by [`DumpReader::skip_errors`].
+/// Only I/O errors are yielded, and end the iteration.
+pub struct SkipErrors<R, T> {
+    reader: DumpReader<R, T>,
+    max_samples: usize,
+    report: ErrorReport,
+}
+
+impl<R: BufRead, T: RootEntry> SkipErrors<R, T> {
+    pub(crate) fn new(reader: DumpReader<R, T>) -> SkipErrors<R, T> {
+        SkipErrors {
+            reader,
+            max_samples: DEFAULT_MAX_SAMPLES,
+            report: ErrorReport::new(T::type_name()),
+        }
+    }
+
+    /// Sets the number of samples kept for each error kind.
+    pub fn max_samples(mut self, max_samples: usize) -> SkipErrors<R, T> {
+        self.max_samples = max_samples;
+        self
+    }
+
+    pub fn report(&self) -> &ErrorReport {
+        &self.report
+    }
+
+    pub fn into_report(self) -> ErrorReport {
+        self.report
+    }
+}
+
+impl<R: BufRead, T: RootEntry> Iterator for SkipErrors<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            let range = match self.reader.read_record()? {
+                Ok(range) => range,
+                Err(e) => return Some(Err(e)),
+            };
+            let position = self.reader.position();
+
+            // simd-json unescapes strings in place, so keep a copy of records with escapes
+            // while samples are kept.
+            #[cfg(feature = "simd-json")]
+            let copy = {
+                let record = self.reader.record(range.clone());
+                if self.max_samples > 0 && record.contains(&b'\\') {
+                    Some(record.to_vec())
+                } else {
+                    None
+                }
+            };
+            let e = match T::parse_dump_json_mut(self.reader.record_mut(range.clone())) {
+                Ok(entry) => {
+                    self.report.parsed += 1;
+                    return Some(Ok(entry));
+                }
+                Err(e) => e,
+            };
+            #[cfg(feature = "simd-json")]
+            let raw = match &copy {
+                Some(copy) => copy.as_slice(),
+                None => self.reader.record(range),
+            };
+            #[cfg(not(feature = "simd-json"))]
+            let raw = self.reader.record(range);
+            // Parse the record again to locate the error of a sample.
+            #[cfg(feature = "simd-json")]
+            let e = if self.report.wants_sample(&e, self.max_samples) {
+                T::parse_dump_json(raw).err().unwrap_or(e)
+            } else {
+                e
+            };
+            self.report.record_error(self.max_samples, position, raw, e);
+        }
+    }
+}
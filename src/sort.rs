@@ -0,0 +1,276 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+use crate::model::RootEntry;
+
+/// Default number of entries sorted in memory at once.
+pub const DEFAULT_RUN_LEN: usize = 100_000;
+
+/// Entries an [`ExternalSort`] can sort: any [`RootEntry`], and the
+/// [`IndexEntry`](crate::index::IndexEntry)s of an index.
+///
+/// Runs hold the entries as JSON, one per line.
+pub trait SortEntry: Serialize + Sized {
+    /// Parses an entry from the JSON written to a run, which it may use as scratch space.
+    fn parse_run_json_mut(bs: &mut [u8]) -> Result<Self>;
+}
+
+impl<T: RootEntry> SortEntry for T {
+    fn parse_run_json_mut(bs: &mut [u8]) -> Result<T> {
+        T::parse_dump_json_mut(bs)
+    }
+}
+
+/// Sorter of entry streams too large for memory, such as the bodies of a full dump.
+///
+/// Entries are sorted in runs of [`run_len`](ExternalSort::run_len) entries, each written to a temporary file,
+/// and the runs are merged while iterating over the result.
+/// Input that fits in a single run is sorted in memory without temporary files.
+///
+/// The sort is stable: entries with equal keys keep their input order.
+#[derive(Debug, Clone)]
+pub struct ExternalSort {
+    run_len: usize,
+    temp_dir: PathBuf,
+}
+
+impl ExternalSort {
+    pub fn new() -> ExternalSort {
+        ExternalSort {
+            run_len: DEFAULT_RUN_LEN,
+            temp_dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Sets the number of entries held in memory per run, [`DEFAULT_RUN_LEN`] by default.
+    pub fn run_len(mut self, run_len: usize) -> ExternalSort {
+        self.run_len = run_len.max(1);
+        self
+    }
+
+    /// Sets the directory of the temporary files, [`std::env::temp_dir`] by default.
+    pub fn temp_dir<P: Into<PathBuf>>(mut self, dir: P) -> ExternalSort {
+        self.temp_dir = dir.into();
+        self
+    }
+
+    /// Sorts `entries`, such as those of a [`DumpReader`](crate::reader::DumpReader), by `key`.
+    ///
+    /// All of `entries` are read, and runs written, before this returns.
+    pub fn sort_by_key<T, K, I, F>(&self, entries: I, mut key: F) -> Result<Sorted<T, K, F>>
+    where
+        T: SortEntry,
+        K: Ord,
+        I: IntoIterator<Item = Result<T>>,
+        F: FnMut(&T) -> K,
+    {
+        let mut entries = entries.into_iter();
+        let mut runs = Vec::new();
+        loop {
+            let mut run = Vec::with_capacity(self.run_len.min(DEFAULT_RUN_LEN));
+            for entry in entries.by_ref().take(self.run_len) {
+                let entry = entry.context("reading entries to sort")?;
+                run.push((key(&entry), entry));
+            }
+            let last = run.len() < self.run_len;
+            run.sort_by(|a, b| a.0.cmp(&b.0));
+
+            if runs.is_empty() && last {
+                return Ok(Sorted {
+                    source: Source::Memory(run.into_iter()),
+                    key,
+                });
+            }
+            if !run.is_empty() {
+                runs.push(Run::write(
+                    &self.temp_dir,
+                    run.into_iter().map(|(_, entry)| entry),
+                )?);
+            }
+            if last {
+                break;
+            }
+        }
+
+        let mut heads = Vec::with_capacity(runs.len());
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (i, run) in runs.iter_mut().enumerate() {
+            let entry = run.next()?;
+            if let Some(entry) = &entry {
+                heap.push(Head {
+                    key: key(entry),
+                    run: i,
+                });
+            }
+            heads.push(entry);
+        }
+        Ok(Sorted {
+            source: Source::Runs { runs, heads, heap },
+            key,
+        })
+    }
+}
+
+impl Default for ExternalSort {
+    fn default() -> ExternalSort {
+        ExternalSort::new()
+    }
+}
+
+/// Same as [`ExternalSort::sort_by_key`] with the default settings.
+pub fn sort_by_key<T, K, I, F>(entries: I, key: F) -> Result<Sorted<T, K, F>>
+where
+    T: SortEntry,
+    K: Ord,
+    I: IntoIterator<Item = Result<T>>,
+    F: FnMut(&T) -> K,
+{
+    ExternalSort::new().sort_by_key(entries, key)
+}
+
+/// Sorted entries of an [`ExternalSort`].
+///
+/// Temporary files are removed when this is dropped.
+pub struct Sorted<T, K, F> {
+    source: Source<T, K>,
+    key: F,
+}
+
+enum Source<T, K> {
+    Memory(std::vec::IntoIter<(K, T)>),
+    Runs {
+        runs: Vec<Run>,
+        /// Next entry of each run.
+        heads: Vec<Option<T>>,
+        heap: BinaryHeap<Head<K>>,
+    },
+}
+
+impl<T, K, F> Sorted<T, K, F> {
+    /// Number of temporary files, 0 if the entries were sorted in memory.
+    pub fn run_count(&self) -> usize {
+        match &self.source {
+            Source::Memory(_) => 0,
+            Source::Runs { runs, .. } => runs.len(),
+        }
+    }
+}
+
+impl<T, K, F> Iterator for Sorted<T, K, F>
+where
+    T: SortEntry,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        match &mut self.source {
+            Source::Memory(entries) => entries.next().map(|(_, entry)| Ok(entry)),
+            Source::Runs { runs, heads, heap } => {
+                let Head { run, .. } = heap.pop()?;
+                let entry = heads[run].take()?;
+                match runs[run].next() {
+                    Ok(Some(next)) => {
+                        heap.push(Head {
+                            key: (self.key)(&next),
+                            run,
+                        });
+                        heads[run] = Some(next);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        heap.clear();
+                        return Some(Err(e));
+                    }
+                }
+                Some(Ok(entry))
+            }
+        }
+    }
+}
+
+/// Key of the next entry of a run, ordered for a min-heap on `(key, run)`.
+///
+/// Ties go to the earlier run, which keeps the sort stable.
+struct Head<K> {
+    key: K,
+    run: usize,
+}
+
+impl<K: Ord> Ord for Head<K> {
+    fn cmp(&self, other: &Head<K>) -> Ordering {
+        (&other.key, other.run).cmp(&(&self.key, self.run))
+    }
+}
+
+impl<K: Ord> PartialOrd for Head<K> {
+    fn partial_cmp(&self, other: &Head<K>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord> PartialEq for Head<K> {
+    fn eq(&self, other: &Head<K>) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord> Eq for Head<K> {}
+
+/// Temporary file holding sorted entries as JSON, one per line.
+///
+/// The file is created with a random name that must not exist yet,
+/// and removed when the run is dropped.
+struct Run {
+    file: NamedTempFile,
+    reader: BufReader<File>,
+    buf: Vec<u8>,
+}
+
+impl Run {
+    fn write<T: SortEntry>(dir: &Path, entries: impl Iterator<Item = T>) -> Result<Run> {
+        let file = tempfile::Builder::new()
+            .prefix("edsm-sort-")
+            .suffix(".json")
+            .tempfile_in(dir)
+            .with_context(|| format!("creating a run in {}", dir.display()))?;
+        let reader = (|| -> Result<File> {
+            let mut w = BufWriter::new(file.as_file());
+            for entry in entries {
+                serde_json::to_writer(&mut w, &entry)?;
+                w.write_all(b"\n")?;
+            }
+            w.flush()?;
+            Ok(file.reopen()?)
+        })()
+        .with_context(|| format!("writing {}", file.path().display()))?;
+        Ok(Run {
+            reader: BufReader::new(reader),
+            file,
+            buf: Vec::new(),
+        })
+    }
+
+    fn next<T: SortEntry>(&mut self) -> Result<Option<T>> {
+        self.buf.clear();
+        let n = self
+            .reader
+            .read_until(b'\n', &mut self.buf)
+            .with_context(|| format!("reading {}", self.file.path().display()))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        let len = self.buf.len() - 1;
+        T::parse_run_json_mut(&mut self.buf[..len])
+            .map(Some)
+            .with_context(|| format!("reading {}", self.file.path().display()))
+    }
+}
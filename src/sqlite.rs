@@ -0,0 +1,1029 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::model::bgs::ControllingFaction;
+use crate::model::body::{AtmosphereCompositionKey, Body, BodyT, MaterialsKey};
+use crate::model::dec::date_format::FORMAT as DATE_FORMAT;
+use crate::model::powerplay::PowerPlay;
+use crate::model::station::{Station, StationBody};
+use crate::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use crate::model::system_populated::{FactionInPopulated, StationInPopulated, SystemPopulated};
+use crate::model::RootEntry;
+
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+/// Relational schema created by [`SqliteLoader`].
+///
+/// Timestamps are `TEXT` in the format of dump files, which sorts chronologically
+/// and is understood by the SQLite date functions.
+/// `id64` values are stored as `INTEGER` with the same bits, so values above `i64::MAX` are negative.
+///
+/// Foreign keys are declared but not enforced by [`SqliteLoader`],
+/// since dumps refer to entries of other dumps which may not be loaded.
+pub const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS factions (
+    id INTEGER PRIMARY KEY,
+    name TEXT,
+    allegiance TEXT,
+    government TEXT,
+    is_player INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS systems (
+    id INTEGER PRIMARY KEY,
+    id64 INTEGER UNIQUE,
+    name TEXT NOT NULL,
+    x REAL,
+    y REAL,
+    z REAL,
+    -- Not NULL for estimated coordinates
+    precision REAL,
+    allegiance TEXT,
+    controlling_faction_id INTEGER REFERENCES factions (id),
+    economy TEXT,
+    government TEXT,
+    population INTEGER,
+    security TEXT,
+    state TEXT,
+    -- Time of the system in each dump, NULL if not loaded from that dump
+    coords_date TEXT,
+    estimated_coords_date TEXT,
+    populated_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS system_factions (
+    system_id INTEGER NOT NULL REFERENCES systems (id),
+    faction_id INTEGER NOT NULL REFERENCES factions (id),
+    happiness TEXT,
+    influence REAL NOT NULL,
+    state TEXT,
+    last_update TEXT NOT NULL,
+    PRIMARY KEY (system_id, faction_id)
+);
+
+CREATE TABLE IF NOT EXISTS faction_states (
+    system_id INTEGER NOT NULL,
+    faction_id INTEGER NOT NULL,
+    -- 'active', 'pending' or 'recovering'
+    kind TEXT NOT NULL,
+    state TEXT NOT NULL,
+    trend INTEGER,
+    PRIMARY KEY (system_id, faction_id, kind, state),
+    FOREIGN KEY (system_id, faction_id) REFERENCES system_factions (system_id, faction_id)
+);
+
+CREATE TABLE IF NOT EXISTS bodies (
+    id INTEGER PRIMARY KEY,
+    id64 INTEGER,
+    body_id INTEGER,
+    name TEXT NOT NULL,
+    type TEXT,
+    sub_type TEXT,
+    system_id INTEGER REFERENCES systems (id),
+    system_id64 INTEGER REFERENCES systems (id64),
+    system_name TEXT,
+    distance_to_arrival INTEGER,
+    arg_of_periapsis REAL,
+    axial_tilt REAL,
+    orbital_eccentricity REAL,
+    orbital_inclination REAL,
+    orbital_period REAL,
+    -- Such as 'Star:3;Null:1'
+    parents TEXT,
+    rotational_period REAL,
+    rotational_period_tidally_locked INTEGER,
+    semi_major_axis REAL,
+    surface_temperature INTEGER,
+    reserve_level TEXT,
+    discovery_commander TEXT,
+    discovery_date TEXT,
+    -- Planet
+    atmosphere_type TEXT,
+    earth_masses REAL,
+    gravity REAL,
+    is_landable INTEGER,
+    radius REAL,
+    solid_composition_ice REAL,
+    solid_composition_metal REAL,
+    solid_composition_rock REAL,
+    surface_pressure REAL,
+    terraforming_state TEXT,
+    volcanism_type TEXT,
+    -- Star
+    absolute_magnitude REAL,
+    age INTEGER,
+    is_main_star INTEGER,
+    is_scoopable INTEGER,
+    luminosity TEXT,
+    solar_masses REAL,
+    solar_radius REAL,
+    spectral_class TEXT,
+    update_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS bodies_system_id64 ON bodies (system_id64);
+
+CREATE TABLE IF NOT EXISTS rings (
+    body_id INTEGER NOT NULL REFERENCES bodies (id),
+    name TEXT NOT NULL,
+    type TEXT,
+    mass REAL NOT NULL,
+    inner_radius REAL NOT NULL,
+    outer_radius REAL NOT NULL,
+    PRIMARY KEY (body_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS belts (
+    body_id INTEGER NOT NULL REFERENCES bodies (id),
+    name TEXT NOT NULL,
+    type TEXT,
+    mass REAL NOT NULL,
+    inner_radius REAL NOT NULL,
+    outer_radius REAL NOT NULL,
+    PRIMARY KEY (body_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS materials (
+    body_id INTEGER NOT NULL REFERENCES bodies (id),
+    name TEXT NOT NULL,
+    percent REAL NOT NULL,
+    PRIMARY KEY (body_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS atmosphere_composition (
+    body_id INTEGER NOT NULL REFERENCES bodies (id),
+    name TEXT NOT NULL,
+    percent REAL NOT NULL,
+    PRIMARY KEY (body_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS stations (
+    id INTEGER PRIMARY KEY,
+    market_id INTEGER,
+    name TEXT NOT NULL,
+    type TEXT,
+    system_id INTEGER REFERENCES systems (id),
+    system_id64 INTEGER REFERENCES systems (id64),
+    system_name TEXT,
+    body_id INTEGER REFERENCES bodies (id),
+    body_name TEXT,
+    body_latitude REAL,
+    body_longitude REAL,
+    distance_to_arrival REAL,
+    allegiance TEXT,
+    controlling_faction_id INTEGER REFERENCES factions (id),
+    economy TEXT,
+    second_economy TEXT,
+    government TEXT,
+    have_market INTEGER NOT NULL,
+    have_outfitting INTEGER NOT NULL,
+    have_shipyard INTEGER NOT NULL,
+    update_time TEXT NOT NULL,
+    market_update_time TEXT,
+    outfitting_update_time TEXT,
+    shipyard_update_time TEXT
+);
+CREATE INDEX IF NOT EXISTS stations_system_id64 ON stations (system_id64);
+
+CREATE TABLE IF NOT EXISTS station_services (
+    station_id INTEGER NOT NULL REFERENCES stations (id),
+    service TEXT NOT NULL,
+    PRIMARY KEY (station_id, service)
+);
+
+CREATE TABLE IF NOT EXISTS commodities (
+    station_id INTEGER NOT NULL REFERENCES stations (id),
+    name TEXT NOT NULL,
+    commodity_id TEXT,
+    buy_price INTEGER NOT NULL,
+    demand INTEGER NOT NULL,
+    sell_price INTEGER NOT NULL,
+    stock INTEGER NOT NULL,
+    stock_bracket INTEGER NOT NULL,
+    PRIMARY KEY (station_id, name)
+);
+
+-- Contested systems have a row for each power
+CREATE TABLE IF NOT EXISTS powerplay (
+    id INTEGER NOT NULL REFERENCES systems (id),
+    id64 INTEGER NOT NULL REFERENCES systems (id64),
+    name TEXT NOT NULL,
+    x REAL NOT NULL,
+    y REAL NOT NULL,
+    z REAL NOT NULL,
+    allegiance TEXT,
+    government TEXT,
+    power TEXT NOT NULL,
+    power_state TEXT NOT NULL,
+    state TEXT,
+    date TEXT NOT NULL,
+    PRIMARY KEY (id, power)
+);
+"#;
+
+/// Outcome of loading an entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Upsert {
+    Inserted,
+    Updated,
+    /// The stored row is newer than the entry.
+    Skipped,
+}
+
+/// Counts of [`Upsert`] outcomes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LoadStats {
+    pub inserted: u64,
+    pub updated: u64,
+    pub skipped: u64,
+}
+
+impl LoadStats {
+    fn add(&mut self, upsert: Upsert) {
+        match upsert {
+            Upsert::Inserted => self.inserted += 1,
+            Upsert::Updated => self.updated += 1,
+            Upsert::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+/// Entry stored in the tables of [`SCHEMA`].
+pub trait SqlEntry: RootEntry {
+    /// Inserts the entry, or replaces the stored row with the same [`entry_id`](RootEntry::entry_id)
+    /// unless that row has a later [`time`](RootEntry::time).
+    fn upsert(&self, tx: &Transaction) -> Result<Upsert>;
+}
+
+/// Loader of entries into a SQLite database with the [`SCHEMA`].
+///
+/// Entries are inserted in transactions of `batch_size` entries,
+/// so that loading can be resumed by loading the same or a newer dump again.
+pub struct SqliteLoader {
+    conn: Connection,
+    batch_size: usize,
+}
+
+impl SqliteLoader {
+    /// Opens or creates the database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SqliteLoader> {
+        let path = path.as_ref();
+        let conn = Connection::open(path).with_context(|| format!("opening {}", path.display()))?;
+        SqliteLoader::new(conn)
+    }
+
+    /// Creates the tables in `conn` if they do not exist, and disables foreign key enforcement.
+    pub fn new(conn: Connection) -> Result<SqliteLoader> {
+        conn.pragma_update(None, "foreign_keys", false)?;
+        conn.execute_batch(SCHEMA)
+            .context("creating the database schema")?;
+        Ok(SqliteLoader {
+            conn,
+            batch_size: DEFAULT_BATCH_SIZE,
+        })
+    }
+
+    /// Sets the number of entries inserted per transaction.
+    pub fn batch_size(mut self, batch_size: usize) -> SqliteLoader {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Loads all `entries`, such as those of a [`DumpReader`](crate::reader::DumpReader).
+    ///
+    /// On error, the entries of the batches committed before are kept.
+    pub fn load<T, I>(&mut self, entries: I) -> Result<LoadStats>
+    where
+        T: SqlEntry,
+        I: IntoIterator<Item = Result<T>>,
+    {
+        let mut stats = LoadStats::default();
+        let mut entries = entries.into_iter().peekable();
+        while entries.peek().is_some() {
+            let tx = self.conn.transaction()?;
+            for entry in entries.by_ref().take(self.batch_size) {
+                let entry = entry?;
+                let upsert = entry.upsert(&tx).with_context(|| {
+                    format!("loading {} entry {}", T::type_name(), entry.entry_id())
+                })?;
+                stats.add(upsert);
+            }
+            tx.commit()?;
+        }
+        Ok(stats)
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub fn into_inner(self) -> Connection {
+        self.conn
+    }
+}
+
+/// Stores `id64` values bit for bit, as SQLite has no unsigned integers.
+fn id64(x: u64) -> i64 {
+    x as i64
+}
+
+fn date(x: DateTime<Utc>) -> String {
+    x.format(DATE_FORMAT).to_string()
+}
+
+fn text<E: Display>(x: Option<&E>) -> Option<String> {
+    x.map(ToString::to_string)
+}
+
+/// Compares `time` with the time of the stored row of `id` in `table`.
+fn check_time(
+    tx: &Transaction,
+    table: &str,
+    time_column: &str,
+    id: u64,
+    time: &str,
+) -> Result<Upsert> {
+    let sql = format!("SELECT {} FROM {} WHERE id = ?1", time_column, table);
+    // The time is NULL for rows only loaded from other dumps
+    let stored: Option<Option<String>> = tx
+        .prepare_cached(&sql)?
+        .query_row(params![id], |row| row.get(0))
+        .optional()?;
+    Ok(compare_time(stored, time))
+}
+
+fn compare_time(stored: Option<Option<String>>, time: &str) -> Upsert {
+    match stored {
+        None => Upsert::Inserted,
+        Some(Some(stored)) if stored.as_str() > time => Upsert::Skipped,
+        Some(_) => Upsert::Updated,
+    }
+}
+
+fn upsert_faction(tx: &Transaction, f: &ControllingFaction) -> Result<()> {
+    let id = match f.id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    tx.prepare_cached(
+        "INSERT INTO factions (id, name, allegiance, government, is_player)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (id) DO UPDATE SET
+            name = coalesce(excluded.name, name),
+            allegiance = coalesce(excluded.allegiance, allegiance),
+            government = coalesce(excluded.government, government),
+            is_player = coalesce(excluded.is_player, is_player)",
+    )?
+    .execute(params![
+        id,
+        f.name,
+        text(f.allegiance.as_ref()),
+        text(f.government.as_ref()),
+        f.is_player,
+    ])?;
+    Ok(())
+}
+
+fn upsert_station_body(
+    tx: &Transaction,
+    station_id: u64,
+    body: Option<&StationBody>,
+) -> Result<()> {
+    tx.prepare_cached(
+        "UPDATE stations SET body_id = ?2, body_name = ?3, body_latitude = ?4, body_longitude = ?5
+        WHERE id = ?1",
+    )?
+    .execute(params![
+        station_id,
+        body.map(|b| b.id),
+        body.map(|b| b.name.as_str()),
+        body.and_then(|b| b.latitude),
+        body.and_then(|b| b.longitude),
+    ])?;
+    Ok(())
+}
+
+fn replace_station_services<'a, E: Display + 'a>(
+    tx: &Transaction,
+    station_id: u64,
+    services: impl IntoIterator<Item = &'a E>,
+) -> Result<()> {
+    tx.prepare_cached("DELETE FROM station_services WHERE station_id = ?1")?
+        .execute(params![station_id])?;
+    let mut insert = tx.prepare_cached(
+        "INSERT OR IGNORE INTO station_services (station_id, service) VALUES (?1, ?2)",
+    )?;
+    for service in services {
+        insert.execute(params![station_id, service.to_string()])?;
+    }
+    Ok(())
+}
+
+// Main Type
+
+impl SqlEntry for Body {
+    fn upsert(&self, tx: &Transaction) -> Result<Upsert> {
+        let id = self.id();
+        let update_time = date(self.update_time());
+        let upsert = check_time(tx, "bodies", "update_time", id, &update_time)?;
+        if upsert == Upsert::Skipped {
+            return Ok(upsert);
+        }
+
+        let (planet, star) = match self {
+            Body::Planet(x) => (Some(x), None),
+            Body::Star(x) => (None, Some(x)),
+            Body::Unknown(_) => (None, None),
+        };
+        let typ = match self {
+            Body::Planet(_) => Some("Planet"),
+            Body::Star(_) => Some("Star"),
+            Body::Unknown(_) => None,
+        };
+        let sub_type = match self {
+            Body::Planet(x) => Some(x.sub_type.to_string()),
+            Body::Star(x) => Some(x.sub_type.to_string()),
+            Body::Unknown(_) => None,
+        };
+        let arg_of_periapsis = planet
+            .and_then(|x| x.arg_of_periapsis)
+            .or_else(|| star.and_then(|x| x.arg_of_periapsis));
+        let reserve_level = planet
+            .and_then(|x| x.reserve_level.as_ref())
+            .or_else(|| star.and_then(|x| x.reserve_level.as_ref()));
+        let discovery = planet
+            .and_then(|x| x.discovery.as_ref())
+            .or_else(|| star.and_then(|x| x.discovery.as_ref()));
+        let parents = self.parents().map(|ps| {
+            ps.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(";")
+        });
+        let solid_composition = planet.and_then(|x| x.solid_composition.as_ref());
+
+        tx.prepare_cached(
+            "INSERT OR REPLACE INTO bodies (
+                id, id64, body_id, name, type, sub_type, system_id, system_id64, system_name,
+                distance_to_arrival, arg_of_periapsis, axial_tilt, orbital_eccentricity,
+                orbital_inclination, orbital_period, parents, rotational_period,
+                rotational_period_tidally_locked, semi_major_axis, surface_temperature,
+                reserve_level, discovery_commander, discovery_date,
+                atmosphere_type, earth_masses, gravity, is_landable, radius,
+                solid_composition_ice, solid_composition_metal, solid_composition_rock,
+                surface_pressure, terraforming_state, volcanism_type,
+                absolute_magnitude, age, is_main_star, is_scoopable, luminosity,
+                solar_masses, solar_radius, spectral_class,
+                update_time
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9,
+                ?10, ?11, ?12, ?13,
+                ?14, ?15, ?16, ?17,
+                ?18, ?19, ?20,
+                ?21, ?22, ?23,
+                ?24, ?25, ?26, ?27, ?28,
+                ?29, ?30, ?31,
+                ?32, ?33, ?34,
+                ?35, ?36, ?37, ?38, ?39,
+                ?40, ?41, ?42,
+                ?43
+            )",
+        )?
+        .execute(params![
+            id,
+            self.id64().map(id64),
+            self.body_id(),
+            self.name(),
+            typ,
+            sub_type,
+            self.system_id(),
+            self.system_id64().map(id64),
+            self.system_name(),
+            self.distance_to_arrival(),
+            arg_of_periapsis,
+            self.axial_tilt(),
+            self.orbital_eccentricity(),
+            self.orbital_inclination(),
+            self.orbital_period(),
+            parents,
+            self.rotational_period(),
+            self.rotational_period_tidally_locked(),
+            self.semi_major_axis(),
+            self.surface_temperature(),
+            text(reserve_level),
+            discovery.map(|d| d.commander.as_str()),
+            discovery.map(|d| date(d.date)),
+            text(planet.and_then(|x| x.atmosphere_type.as_ref())),
+            planet.map(|x| x.earth_masses),
+            planet.and_then(|x| x.gravity),
+            planet.map(|x| x.is_landable),
+            planet.map(|x| x.radius),
+            solid_composition.map(|c| c.ice),
+            solid_composition.map(|c| c.metal),
+            solid_composition.map(|c| c.rock),
+            planet.and_then(|x| x.surface_pressure),
+            text(planet.and_then(|x| x.terraforming_state.as_ref())),
+            text(planet.and_then(|x| x.volcanism_type.as_ref())),
+            star.and_then(|x| x.absolute_magnitude),
+            star.map(|x| x.age),
+            star.map(|x| x.is_main_star),
+            star.map(|x| x.is_scoopable),
+            text(star.and_then(|x| x.luminosity.as_ref())),
+            star.map(|x| x.solar_masses),
+            star.map(|x| x.solar_radius),
+            text(star.and_then(|x| x.spectral_class.as_ref())),
+            update_time,
+        ])?;
+
+        for table in ["rings", "belts", "materials", "atmosphere_composition"] {
+            tx.prepare_cached(&format!("DELETE FROM {} WHERE body_id = ?1", table))?
+                .execute(params![id])?;
+        }
+        let rings = planet
+            .and_then(|x| x.rings.as_deref())
+            .or_else(|| star.and_then(|x| x.rings.as_deref()))
+            .unwrap_or_default()
+            .iter()
+            .map(|r| (&r.name, &r.typ, r.mass, r.inner_radius, r.outer_radius));
+        let belts = planet
+            .and_then(|x| x.belts.as_deref())
+            .or_else(|| star.and_then(|x| x.belts.as_deref()))
+            .unwrap_or_default()
+            .iter()
+            .map(|b| (&b.name, &b.typ, b.mass, b.inner_radius, b.outer_radius));
+        for (table, rows) in [
+            ("rings", rings.collect::<Vec<_>>()),
+            ("belts", belts.collect()),
+        ] {
+            let mut insert = tx.prepare_cached(&format!(
+                "INSERT OR REPLACE INTO {} (body_id, name, type, mass, inner_radius, outer_radius)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                table
+            ))?;
+            for (name, typ, mass, inner_radius, outer_radius) in rows {
+                insert.execute(params![
+                    id,
+                    name,
+                    text(typ.as_ref()),
+                    mass,
+                    inner_radius,
+                    outer_radius,
+                ])?;
+            }
+        }
+
+        if let Some(materials) = planet.and_then(|x| x.materials.as_ref()) {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO materials (body_id, name, percent) VALUES (?1, ?2, ?3)",
+            )?;
+            for key in <MaterialsKey as strum::IntoEnumIterator>::iter() {
+                if let Some(percent) = materials.get(key) {
+                    insert.execute(params![id, key.to_string(), percent])?;
+                }
+            }
+        }
+        if let Some(composition) = planet.and_then(|x| x.atmosphere_composition.as_ref()) {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO atmosphere_composition (body_id, name, percent) VALUES (?1, ?2, ?3)",
+            )?;
+            for key in <AtmosphereCompositionKey as strum::IntoEnumIterator>::iter() {
+                if let Some(percent) = composition.get(key) {
+                    insert.execute(params![id, key.to_string(), percent])?;
+                }
+            }
+        }
+
+        Ok(upsert)
+    }
+}
+
+impl SqlEntry for PowerPlay {
+    fn upsert(&self, tx: &Transaction) -> Result<Upsert> {
+        let time = date(self.date);
+        let power = self.power.to_string();
+        let stored = tx
+            .prepare_cached("SELECT date FROM powerplay WHERE id = ?1 AND power = ?2")?
+            .query_row(params![self.id, power], |row| row.get(0))
+            .optional()?;
+        let upsert = compare_time(stored, &time);
+        if upsert == Upsert::Skipped {
+            return Ok(upsert);
+        }
+        tx.prepare_cached(
+            "INSERT OR REPLACE INTO powerplay (
+                id, id64, name, x, y, z, allegiance, government, power, power_state, state, date
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        )?
+        .execute(params![
+            self.id,
+            id64(self.id64),
+            self.name,
+            self.coords.x,
+            self.coords.y,
+            self.coords.z,
+            text(self.allegiance.as_ref()),
+            text(self.government.as_ref()),
+            power,
+            self.power_state.to_string(),
+            text(self.state.as_ref()),
+            time,
+        ])?;
+        Ok(upsert)
+    }
+}
+
+impl SqlEntry for Station {
+    fn upsert(&self, tx: &Transaction) -> Result<Upsert> {
+        let time = date(self.update_time.information);
+        let upsert = check_time(tx, "stations", "update_time", self.id, &time)?;
+        if upsert == Upsert::Skipped {
+            return Ok(upsert);
+        }
+        if let Some(f) = &self.controlling_faction {
+            upsert_faction(tx, f)?;
+        }
+        tx.prepare_cached(
+            "INSERT INTO stations (
+                id, market_id, name, type, system_id, system_id64, system_name,
+                distance_to_arrival, allegiance, controlling_faction_id, economy, second_economy,
+                government, have_market, have_outfitting, have_shipyard,
+                update_time, market_update_time, outfitting_update_time, shipyard_update_time
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+                ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20
+            )
+            ON CONFLICT (id) DO UPDATE SET
+                market_id = excluded.market_id,
+                name = excluded.name,
+                type = excluded.type,
+                system_id = excluded.system_id,
+                system_id64 = excluded.system_id64,
+                system_name = excluded.system_name,
+                distance_to_arrival = excluded.distance_to_arrival,
+                allegiance = excluded.allegiance,
+                controlling_faction_id = excluded.controlling_faction_id,
+                economy = excluded.economy,
+                second_economy = excluded.second_economy,
+                government = excluded.government,
+                have_market = excluded.have_market,
+                have_outfitting = excluded.have_outfitting,
+                have_shipyard = excluded.have_shipyard,
+                update_time = excluded.update_time,
+                market_update_time = excluded.market_update_time,
+                outfitting_update_time = excluded.outfitting_update_time,
+                shipyard_update_time = excluded.shipyard_update_time",
+        )?
+        .execute(params![
+            self.id,
+            self.market_id,
+            self.name,
+            text(self.typ.as_ref()),
+            self.system_id,
+            self.system_id64.map(id64),
+            self.system_name,
+            self.distance_to_arrival,
+            text(self.allegiance.as_ref()),
+            self.controlling_faction.as_ref().and_then(|f| f.id),
+            text(self.economy.as_ref()),
+            text(self.second_economy.as_ref()),
+            text(self.government.as_ref()),
+            self.have_market,
+            self.have_outfitting,
+            self.have_shipyard,
+            time,
+            self.update_time.market.map(date),
+            self.update_time.outfitting.map(date),
+            self.update_time.shipyard.map(date),
+        ])?;
+        upsert_station_body(tx, self.id, self.body.as_ref())?;
+        replace_station_services(tx, self.id, &self.other_services)?;
+
+        tx.prepare_cached("DELETE FROM commodities WHERE station_id = ?1")?
+            .execute(params![self.id])?;
+        let mut insert = tx.prepare_cached(
+            "INSERT OR REPLACE INTO commodities (
+                station_id, name, commodity_id, buy_price, demand, sell_price, stock, stock_bracket
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        for c in self.commodities.iter().flatten() {
+            insert.execute(params![
+                self.id,
+                c.name,
+                c.id,
+                c.buy_price,
+                c.demand,
+                c.sell_price,
+                c.stock,
+                c.stock_bracket,
+            ])?;
+        }
+        Ok(upsert)
+    }
+}
+
+/// Loads a station of a populated system, keeping the columns it does not have.
+fn upsert_station_in_populated(
+    tx: &Transaction,
+    system: &SystemPopulated,
+    station: &StationInPopulated,
+) -> Result<()> {
+    let time = date(station.update_time.information);
+    if check_time(tx, "stations", "update_time", station.id, &time)? == Upsert::Skipped {
+        return Ok(());
+    }
+    if let Some(f) = &station.controlling_faction {
+        upsert_faction(tx, f)?;
+    }
+    tx.prepare_cached(
+        "INSERT INTO stations (
+            id, market_id, name, type, system_id, system_id64, system_name,
+            distance_to_arrival, allegiance, controlling_faction_id, economy, second_economy,
+            government, have_market, have_outfitting, have_shipyard,
+            update_time, market_update_time, outfitting_update_time, shipyard_update_time
+        ) VALUES (
+            ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+            ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20
+        )
+        ON CONFLICT (id) DO UPDATE SET
+            market_id = excluded.market_id,
+            name = excluded.name,
+            type = excluded.type,
+            system_id = excluded.system_id,
+            system_id64 = coalesce(excluded.system_id64, system_id64),
+            system_name = excluded.system_name,
+            distance_to_arrival = excluded.distance_to_arrival,
+            allegiance = excluded.allegiance,
+            controlling_faction_id = excluded.controlling_faction_id,
+            economy = excluded.economy,
+            second_economy = excluded.second_economy,
+            government = excluded.government,
+            have_market = excluded.have_market,
+            have_outfitting = excluded.have_outfitting,
+            have_shipyard = excluded.have_shipyard,
+            update_time = excluded.update_time,
+            market_update_time = excluded.market_update_time,
+            outfitting_update_time = excluded.outfitting_update_time,
+            shipyard_update_time = excluded.shipyard_update_time",
+    )?
+    .execute(params![
+        station.id,
+        station.market_id,
+        station.name,
+        text(station.st_type.as_ref()),
+        system.id,
+        system.id64.map(id64),
+        system.name,
+        station.distance_to_arrival,
+        text(station.allegiance.as_ref()),
+        station.controlling_faction.as_ref().and_then(|f| f.id),
+        text(station.economy.as_ref()),
+        text(station.second_economy.as_ref()),
+        text(station.government.as_ref()),
+        station.have_market,
+        station.have_outfitting,
+        station.have_shipyard,
+        time,
+        station.update_time.market.map(date),
+        station.update_time.outfitting.map(date),
+        station.update_time.shipyard.map(date),
+    ])?;
+    upsert_station_body(tx, station.id, station.body.as_ref())?;
+    replace_station_services(tx, station.id, &station.other_services)?;
+    Ok(())
+}
+
+impl SqlEntry for SystemWithCoordinates {
+    fn upsert(&self, tx: &Transaction) -> Result<Upsert> {
+        let time = date(self.date);
+        let upsert = check_time(tx, "systems", "coords_date", self.id, &time)?;
+        if upsert == Upsert::Skipped {
+            return Ok(upsert);
+        }
+        tx.prepare_cached(
+            "INSERT INTO systems (id, id64, name, x, y, z, precision, coords_date)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7)
+            ON CONFLICT (id) DO UPDATE SET
+                id64 = excluded.id64,
+                name = excluded.name,
+                x = excluded.x,
+                y = excluded.y,
+                z = excluded.z,
+                precision = NULL,
+                coords_date = excluded.coords_date",
+        )?
+        .execute(params![
+            self.id,
+            self.id64.map(id64),
+            self.name,
+            self.coords.x,
+            self.coords.y,
+            self.coords.z,
+            time,
+        ])?;
+        Ok(upsert)
+    }
+}
+
+/// Estimated coordinates are only stored for systems not loaded from the dumps with coordinates.
+impl SqlEntry for SystemWithoutCoordinates {
+    fn upsert(&self, tx: &Transaction) -> Result<Upsert> {
+        let time = date(self.date);
+        let upsert = check_time(tx, "systems", "estimated_coords_date", self.id, &time)?;
+        if upsert == Upsert::Skipped {
+            return Ok(upsert);
+        }
+        let coords = self.estimated_coordinates.as_ref();
+        tx.prepare_cached(
+            "INSERT INTO systems (id, id64, name, x, y, z, precision, estimated_coords_date)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT (id) DO UPDATE SET
+                id64 = excluded.id64,
+                name = excluded.name,
+                x = iif(coords_date IS NULL AND populated_date IS NULL, excluded.x, x),
+                y = iif(coords_date IS NULL AND populated_date IS NULL, excluded.y, y),
+                z = iif(coords_date IS NULL AND populated_date IS NULL, excluded.z, z),
+                precision = iif(
+                    coords_date IS NULL AND populated_date IS NULL,
+                    excluded.precision,
+                    precision
+                ),
+                estimated_coords_date = excluded.estimated_coords_date",
+        )?
+        .execute(params![
+            self.id,
+            self.id64.map(id64),
+            self.name,
+            coords.map(|c| c.x),
+            coords.map(|c| c.y),
+            coords.map(|c| c.z),
+            coords.map(|c| c.precision),
+            time,
+        ])?;
+        Ok(upsert)
+    }
+}
+
+/// Factions, bodies and stations are loaded by their own times, also when the system is skipped.
+impl SqlEntry for SystemPopulated {
+    fn upsert(&self, tx: &Transaction) -> Result<Upsert> {
+        let time = date(self.date);
+        let upsert = check_time(tx, "systems", "populated_date", self.id, &time)?;
+        if upsert != Upsert::Skipped {
+            self.upsert_system(tx, &time)?;
+        }
+        for f in self.factions.iter().flatten() {
+            upsert_system_faction(tx, self.id, f)?;
+        }
+        for body in &self.bodies {
+            body.upsert(tx)?;
+        }
+        for station in &self.stations {
+            upsert_station_in_populated(tx, self, station)?;
+        }
+        Ok(upsert)
+    }
+}
+
+impl SystemPopulated {
+    /// Updates the row of the system, and removes the factions no longer in it.
+    fn upsert_system(&self, tx: &Transaction, time: &str) -> Result<()> {
+        upsert_faction(tx, &self.controlling_faction)?;
+        tx.prepare_cached(
+            "INSERT INTO systems (
+                id, id64, name, x, y, z, precision, allegiance, controlling_faction_id,
+                economy, government, population, security, state, populated_date
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            ON CONFLICT (id) DO UPDATE SET
+                id64 = excluded.id64,
+                name = excluded.name,
+                x = excluded.x,
+                y = excluded.y,
+                z = excluded.z,
+                precision = NULL,
+                allegiance = excluded.allegiance,
+                controlling_faction_id = excluded.controlling_faction_id,
+                economy = excluded.economy,
+                government = excluded.government,
+                population = excluded.population,
+                security = excluded.security,
+                state = excluded.state,
+                populated_date = excluded.populated_date",
+        )?
+        .execute(params![
+            self.id,
+            self.id64.map(id64),
+            self.name,
+            self.coords.x,
+            self.coords.y,
+            self.coords.z,
+            text(self.allegiance.as_ref()),
+            self.controlling_faction.id,
+            text(self.economy.as_ref()),
+            text(self.government.as_ref()),
+            self.population,
+            self.security.to_string(),
+            text(self.state.as_ref()),
+            time,
+        ])?;
+
+        let stored = tx
+            .prepare_cached("SELECT faction_id FROM system_factions WHERE system_id = ?1")?
+            .query_map(params![self.id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<u64>>>()?;
+        for faction_id in stored {
+            if self.factions.iter().flatten().any(|f| f.id == faction_id) {
+                continue;
+            }
+            for table in ["faction_states", "system_factions"] {
+                tx.prepare_cached(&format!(
+                    "DELETE FROM {} WHERE system_id = ?1 AND faction_id = ?2",
+                    table
+                ))?
+                .execute(params![self.id, faction_id])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Loads a faction of a populated system, unless the stored one has a later `last_update`.
+fn upsert_system_faction(tx: &Transaction, system_id: u64, f: &FactionInPopulated) -> Result<()> {
+    let last_update = date(f.last_update);
+    let stored = tx
+        .prepare_cached(
+            "SELECT last_update FROM system_factions WHERE system_id = ?1 AND faction_id = ?2",
+        )?
+        .query_row(params![system_id, f.id], |row| row.get(0))
+        .optional()?;
+    if compare_time(stored, &last_update) == Upsert::Skipped {
+        return Ok(());
+    }
+
+    tx.prepare_cached(
+        "INSERT INTO factions (id, name, allegiance, government, is_player)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (id) DO UPDATE SET
+            name = excluded.name,
+            allegiance = excluded.allegiance,
+            government = excluded.government,
+            is_player = excluded.is_player",
+    )?
+    .execute(params![
+        f.id,
+        f.name,
+        text(f.allegiance.as_ref()),
+        text(f.government.as_ref()),
+        f.is_player,
+    ])?;
+    tx.prepare_cached(
+        "INSERT OR REPLACE INTO system_factions (
+            system_id, faction_id, happiness, influence, state, last_update
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?
+    .execute(params![
+        system_id,
+        f.id,
+        text(f.happiness.as_ref()),
+        f.influence,
+        text(f.state.as_ref()),
+        last_update,
+    ])?;
+
+    tx.prepare_cached("DELETE FROM faction_states WHERE system_id = ?1 AND faction_id = ?2")?
+        .execute(params![system_id, f.id])?;
+    let mut insert = tx.prepare_cached(
+        "INSERT OR REPLACE INTO faction_states (system_id, faction_id, kind, state, trend)
+        VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for s in &f.active_states {
+        insert.execute(params![
+            system_id,
+            f.id,
+            "active",
+            s.state.to_string(),
+            None::<u8>
+        ])?;
+    }
+    for s in &f.pending_states {
+        insert.execute(params![
+            system_id,
+            f.id,
+            "pending",
+            s.state.to_string(),
+            s.trend
+        ])?;
+    }
+    for s in &f.recovering_states {
+        insert.execute(params![
+            system_id,
+            f.id,
+            "recovering",
+            s.state.to_string(),
+            s.trend
+        ])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::ser::{CompactFormatter, Formatter};
+use serde_json::Value;
+
+use crate::model::body::Body;
+use crate::model::RootEntry;
+
+/// Writer of dump files in the EDSM layout.
+///
+/// Entries are written as EDSM does, so the output can be read by [`DumpReader`](crate::reader::DumpReader)
+/// and other EDSM consumers:
+///
+/// * a `[` line, one entry per line indented by 4 spaces and terminated by `,` except the last, and a `]` line
+/// * object keys in alphabetical order
+/// * absent values as `null`
+/// * integral numbers without a fractional part, e.g. `0` rather than `0.0`
+/// * `"type":null` for [`Body::Unknown`](crate::model::body::Body::Unknown)
+pub struct DumpWriter<W, T> {
+    inner: W,
+    count: u64,
+    buf: Vec<u8>,
+    _entry: PhantomData<fn(T)>,
+}
+
+impl<W: Write, T: RootEntry> DumpWriter<W, T> {
+    pub fn new(inner: W) -> DumpWriter<W, T> {
+        DumpWriter {
+            inner,
+            count: 0,
+            buf: Vec::new(),
+            _entry: PhantomData,
+        }
+    }
+
+    pub fn write(&mut self, entry: &T) -> Result<()> {
+        // Going through text keeps the shortest representation of `f32` values,
+        // which `serde_json::to_value` would widen to `f64`.
+        self.buf.clear();
+        serde_json::to_writer(&mut self.buf, entry)
+            .with_context(|| format!("serializing {} entry", T::type_name()))?;
+        let mut value: Value = serde_json::from_slice(&self.buf)?;
+        if T::type_name() == Body::type_name() {
+            null_unknown_type(&mut value);
+        }
+
+        self.buf.clear();
+        self.buf.extend_from_slice(if self.count == 0 {
+            b"[\n    "
+        } else {
+            b",\n    "
+        });
+        let mut ser = serde_json::Serializer::with_formatter(&mut self.buf, EdsmFormatter);
+        Sorted(&value).serialize(&mut ser)?;
+
+        self.inner.write_all(&self.buf)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Number of entries written.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Writes the closing `]` and flushes the output.
+    pub fn finish(mut self) -> Result<W> {
+        let end: &[u8] = if self.count == 0 { b"[\n]" } else { b"\n]" };
+        self.inner.write_all(end)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<T: RootEntry> DumpWriter<BufWriter<File>, T> {
+    /// Creates an uncompressed dump file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<DumpWriter<BufWriter<File>, T>> {
+        let path = path.as_ref();
+        let f = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        Ok(DumpWriter::new(BufWriter::new(f)))
+    }
+}
+
+/// Replaces the `"type":"null"` tag of an unknown body with the `null` of EDSM.
+fn null_unknown_type(body: &mut Value) {
+    if let Some(typ) = body.get_mut("type") {
+        if typ.as_str() == Some("null") {
+            *typ = Value::Null;
+        }
+    }
+}
+
+/// Serializes a value with the keys of objects sorted, regardless of `serde_json/preserve_order`.
+struct Sorted<'a>(&'a Value);
+
+impl<'a> Serialize for Sorted<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Object(m) => {
+                let mut entries: Vec<_> = m.iter().collect();
+                entries.sort_by_key(|(k, _)| *k);
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (k, v) in entries {
+                    map.serialize_entry(k, &Sorted(v))?;
+                }
+                map.end()
+            }
+            Value::Array(vs) => serializer.collect_seq(vs.iter().map(Sorted)),
+            v => v.serialize(serializer),
+        }
+    }
+}
+
+/// Largest magnitude written as an integer; larger numbers keep the exponent notation.
+const MAX_INTEGRAL: f64 = 1e16;
+
+struct EdsmFormatter;
+
+impl Formatter for EdsmFormatter {
+    fn write_f64<W: ?Sized + Write>(&mut self, writer: &mut W, value: f64) -> io::Result<()> {
+        if value.fract() == 0.0 && value.abs() < MAX_INTEGRAL {
+            write!(writer, "{}", value as i64)
+        } else {
+            CompactFormatter.write_f64(writer, value)
+        }
+    }
+}
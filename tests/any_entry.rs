@@ -0,0 +1,99 @@
+use std::io::Cursor;
+
+use anyhow::Result;
+use strum::IntoEnumIterator;
+
+use edsm_dumps_model::model::any::{AnyEntry, DumpKind};
+use edsm_dumps_model::reader::AnyDumpReader;
+
+#[test]
+fn kind_from_file_name() {
+    let cases = [
+        ("bodies7days.json", Some(DumpKind::Body)),
+        ("bodies.json", Some(DumpKind::Body)),
+        ("powerPlay.json", Some(DumpKind::PowerPlay)),
+        ("stations.json.gz", Some(DumpKind::Station)),
+        ("systemsPopulated.json.bz2", Some(DumpKind::SystemPopulated)),
+        (
+            "systemsWithCoordinates7days.json.zst",
+            Some(DumpKind::SystemWithCoordinates),
+        ),
+        (
+            "systemsWithoutCoordinates.json",
+            Some(DumpKind::SystemWithoutCoordinates),
+        ),
+        ("codex.json", None),
+        ("stations.csv", None),
+    ];
+    for (name, expected) in cases.iter() {
+        assert_eq!(DumpKind::from_file_name(name), *expected, "{}", name);
+    }
+
+    assert_eq!(
+        DumpKind::from_path("/data/edsm/systemsWithCoordinates.json"),
+        Some(DumpKind::SystemWithCoordinates)
+    );
+    assert_eq!(
+        DumpKind::from_file_name(DumpKind::Station.file_name()),
+        Some(DumpKind::Station)
+    );
+}
+
+#[test]
+fn kind_file_names() {
+    assert_eq!(DumpKind::Body.file_name(), "bodies.json");
+    assert_eq!(
+        DumpKind::Body.seven_days_file_name(),
+        Some("bodies7days.json")
+    );
+    assert_eq!(DumpKind::PowerPlay.seven_days_file_name(), None);
+    for kind in DumpKind::iter() {
+        assert_eq!(DumpKind::from_file_name(kind.file_name()), Some(kind));
+        if let Some(name) = kind.seven_days_file_name() {
+            assert_eq!(DumpKind::from_file_name(name), Some(kind));
+        }
+    }
+}
+
+#[test]
+fn read_any_dump() -> Result<()> {
+    let samples: [(DumpKind, &[u8], usize); 3] = [
+        (
+            DumpKind::Body,
+            include_bytes!("./sample_json/body.json"),
+            343,
+        ),
+        (
+            DumpKind::PowerPlay,
+            include_bytes!("./sample_json/powerPlay.json"),
+            52,
+        ),
+        (
+            DumpKind::SystemWithCoordinates,
+            include_bytes!("./sample_json/systemWithCoordinates.json"),
+            11,
+        ),
+    ];
+
+    for (kind, bs, len) in samples.iter() {
+        let entries = AnyDumpReader::new(*kind, Cursor::new(bs)).collect::<Result<Vec<_>>>()?;
+        assert_eq!(entries.len(), *len);
+        for entry in &entries {
+            assert_eq!(entry.kind(), *kind);
+            assert_eq!(entry.type_name(), kind.type_name());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn shared_accessors() -> Result<()> {
+    let line = br#"{"allegiance":"Federation","coords":{"x":-5.5625,"y":-0.90625,"z":110.125},"date":"2020-11-17 23:55:47","government":"Corporate","id":995,"id64":2869709317585,"name":"Wulganda","power":"Edmund Mahon","powerState":"Exploited","state":"Boom"}"#;
+    let entry = DumpKind::PowerPlay.parse_dump_json(line)?;
+
+    assert!(matches!(entry, AnyEntry::PowerPlay(_)));
+    assert_eq!(entry.entry_id(), 995);
+    assert_eq!(entry.time().to_string(), "2020-11-17 23:55:47 UTC");
+    assert!(DumpKind::Body.parse_dump_json(line).is_err());
+    Ok(())
+}
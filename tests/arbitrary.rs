@@ -0,0 +1,137 @@
+#![cfg(feature = "arbitrary")]
+
+use std::fmt::{Debug, Display};
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use strum::IntoEnumIterator;
+
+use edsm_dumps_model::model::bgs::{Allegiance, Economy, Government, Happiness, Security, State};
+use edsm_dumps_model::model::body::{
+    AsteroidType, AtmosphereCompositionKey, AtmosphereType, Body, BodyS, BodyT, Luminosity,
+    MaterialsKey, PlanetSubType, ReserveLevel, SpectralClass, StarSubType, TerraformingState,
+    VolcanismType,
+};
+use edsm_dumps_model::model::powerplay::{Power, PowerPlay, PowerState};
+use edsm_dumps_model::model::station::{OtherService, Station, StationType};
+use edsm_dumps_model::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model::model::RootEntry;
+
+fn bytes() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..4096)
+}
+
+fn generate<T: for<'a> Arbitrary<'a>>(bytes: &[u8]) -> Result<T, TestCaseError> {
+    T::arbitrary(&mut Unstructured::new(bytes)).map_err(|e| TestCaseError::reject(e.to_string()))
+}
+
+/// Writes `entry` as JSON and parses it back as a dump record.
+fn json_round_trip<T>(bytes: &[u8]) -> Result<(), TestCaseError>
+where
+    T: for<'a> Arbitrary<'a> + RootEntry + Debug + PartialEq,
+{
+    let entry = generate::<T>(bytes)?;
+    let json = serde_json::to_vec(&entry).unwrap();
+    let parsed = T::parse_dump_json(&json).map_err(|e| {
+        TestCaseError::fail(format!("{:#} in {}", e, String::from_utf8_lossy(&json)))
+    })?;
+    prop_assert_eq!(parsed, entry);
+    Ok(())
+}
+
+/// Checks that `Display` writes the serde name of each variant.
+fn display_is_serde<T>(bytes: &[u8]) -> Result<(), TestCaseError>
+where
+    T: for<'a> Arbitrary<'a> + IntoEnumIterator + Display + Serialize + DeserializeOwned,
+    T: Debug + PartialEq,
+{
+    let mut u = Unstructured::new(bytes);
+    let variants = T::iter()
+        .map(Ok)
+        .chain(std::iter::from_fn(|| Some(T::arbitrary(&mut u))).take(4));
+    for x in variants {
+        let x = x.map_err(|e| TestCaseError::reject(e.to_string()))?;
+        let s = x.to_string();
+        prop_assert_eq!(serde_json::to_value(&x).unwrap(), Value::String(s.clone()));
+        prop_assert_eq!(serde_json::from_value::<T>(Value::String(s)).unwrap(), x);
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn body_json(bytes in bytes()) {
+        json_round_trip::<Body>(&bytes)?;
+    }
+
+    #[test]
+    fn power_play_json(bytes in bytes()) {
+        json_round_trip::<PowerPlay>(&bytes)?;
+    }
+
+    #[test]
+    fn station_json(bytes in bytes()) {
+        json_round_trip::<Station>(&bytes)?;
+    }
+
+    #[test]
+    fn system_with_coordinates_json(bytes in bytes()) {
+        json_round_trip::<SystemWithCoordinates>(&bytes)?;
+    }
+
+    #[test]
+    fn system_without_coordinates_json(bytes in bytes()) {
+        json_round_trip::<SystemWithoutCoordinates>(&bytes)?;
+    }
+
+    #[test]
+    fn system_populated_json(bytes in bytes()) {
+        json_round_trip::<SystemPopulated>(&bytes)?;
+    }
+
+    #[test]
+    fn body_s_conversion(bytes in bytes()) {
+        let body = generate::<Body>(&bytes)?;
+        let body_s = BodyS::from(body.clone());
+        prop_assert_eq!(body_s.id(), body.id());
+        prop_assert_eq!(body_s.name(), body.name());
+        prop_assert_eq!(body_s.update_time(), body.update_time());
+        prop_assert_eq!(body_s.parents(), body.parents());
+
+        let json = serde_json::to_vec(&body_s).unwrap();
+        let parsed: BodyS = serde_json::from_slice(&json).unwrap();
+        prop_assert_eq!(&parsed, &body_s);
+        prop_assert_eq!(Body::from(parsed), body);
+    }
+
+    #[test]
+    fn enum_display(bytes in bytes()) {
+        display_is_serde::<Allegiance>(&bytes)?;
+        display_is_serde::<Economy>(&bytes)?;
+        display_is_serde::<Government>(&bytes)?;
+        display_is_serde::<Happiness>(&bytes)?;
+        display_is_serde::<Security>(&bytes)?;
+        display_is_serde::<State>(&bytes)?;
+        display_is_serde::<AsteroidType>(&bytes)?;
+        display_is_serde::<AtmosphereCompositionKey>(&bytes)?;
+        display_is_serde::<AtmosphereType>(&bytes)?;
+        display_is_serde::<Luminosity>(&bytes)?;
+        display_is_serde::<MaterialsKey>(&bytes)?;
+        display_is_serde::<PlanetSubType>(&bytes)?;
+        display_is_serde::<ReserveLevel>(&bytes)?;
+        display_is_serde::<SpectralClass>(&bytes)?;
+        display_is_serde::<StarSubType>(&bytes)?;
+        display_is_serde::<TerraformingState>(&bytes)?;
+        display_is_serde::<VolcanismType>(&bytes)?;
+        display_is_serde::<Power>(&bytes)?;
+        display_is_serde::<PowerState>(&bytes)?;
+        display_is_serde::<OtherService>(&bytes)?;
+        display_is_serde::<StationType>(&bytes)?;
+    }
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "arrow")]
+
+mod common;
+
+use std::fs::File;
+
+use anyhow::Result;
+use arrow_schema::DataType;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use edsm_dumps_model::arrow::{ArrowEntry, ParquetWriter};
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+
+/// Writes `entries` to a Parquet file, returning the number of rows of each row group.
+fn write_parquet<T: ArrowEntry>(
+    name: &str,
+    entries: Vec<T>,
+    row_group_size: usize,
+) -> Result<Vec<i64>> {
+    let dir = common::temp_dir();
+    let path = dir.path().join(name);
+    let mut w = ParquetWriter::<_, T>::create(&path)?.row_group_size(row_group_size);
+    for entry in entries {
+        w.write(entry)?;
+    }
+    w.finish()?;
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(&path)?)?;
+    assert_eq!(builder.schema().fields(), T::schema().fields());
+    let row_groups: Vec<i64> = builder
+        .metadata()
+        .row_groups()
+        .iter()
+        .map(|g| g.num_rows())
+        .collect();
+    let rows: usize = builder
+        .build()?
+        .map(|batch| batch.map(|b| b.num_rows()))
+        .sum::<Result<usize, _>>()?;
+    assert_eq!(rows as i64, row_groups.iter().sum::<i64>());
+
+    Ok(row_groups)
+}
+
+#[test]
+fn schema_flattens_coords_and_encodes_enums() {
+    let schema = PowerPlay::schema();
+    for name in ["coords_x", "coords_y", "coords_z"] {
+        let field = schema.field_with_name(name).unwrap();
+        assert_eq!(field.data_type(), &DataType::Float32);
+        assert!(!field.is_nullable());
+    }
+    assert!(schema.field_with_name("coords").is_err());
+
+    let power = schema.field_with_name("power").unwrap();
+    assert!(matches!(power.data_type(), DataType::Dictionary(_, v) if **v == DataType::Utf8));
+    assert!(!power.is_nullable());
+    assert!(schema.field_with_name("allegiance").unwrap().is_nullable());
+}
+
+#[test]
+fn record_batch_values() -> Result<()> {
+    let entries = common::read::<SystemWithCoordinates>(include_bytes!(
+        "./sample_json/systemWithCoordinates.json"
+    ))?;
+    let batch = SystemWithCoordinates::to_record_batch(&entries)?;
+    assert_eq!(batch.num_rows(), entries.len());
+
+    let x = batch
+        .column_by_name("coords_x")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow_array::Float32Array>()
+        .unwrap();
+    for (i, entry) in entries.iter().enumerate() {
+        assert_eq!(x.value(i), entry.coords.x);
+    }
+    Ok(())
+}
+
+#[test]
+fn powerplay_row_groups() -> Result<()> {
+    let entries = common::read::<PowerPlay>(include_bytes!("./sample_json/powerPlay.json"))?;
+    assert_eq!(entries.len(), 52);
+    let row_groups = write_parquet("powerPlay.parquet", entries, 20)?;
+    assert_eq!(row_groups, vec![20, 20, 12]);
+    Ok(())
+}
+
+#[test]
+fn bodies() -> Result<()> {
+    let bodies = common::read::<Body>(include_bytes!("./sample_json/body.json"))?;
+    let mut planets = Vec::new();
+    let mut stars = Vec::new();
+    for body in bodies {
+        match body {
+            Body::Planet(x) => planets.push(x),
+            Body::Star(x) => stars.push(x),
+            Body::Unknown(_) => {}
+        }
+    }
+    assert!(!planets.is_empty());
+    assert!(!stars.is_empty());
+
+    let n = planets.len() as i64;
+    assert_eq!(
+        write_parquet("planets.parquet", planets, 100)?
+            .iter()
+            .sum::<i64>(),
+        n
+    );
+    let n = stars.len() as i64;
+    assert_eq!(
+        write_parquet("stars.parquet", stars, 100)?
+            .iter()
+            .sum::<i64>(),
+        n
+    );
+    Ok(())
+}
+
+#[test]
+fn stations_and_populated_systems() -> Result<()> {
+    let stations = common::read::<Station>(include_bytes!("./sample_json/station.json"))?;
+    let n = stations.len() as i64;
+    assert_eq!(
+        write_parquet("stations.parquet", stations, 100)?
+            .iter()
+            .sum::<i64>(),
+        n
+    );
+
+    let systems =
+        common::read::<SystemPopulated>(include_bytes!("./sample_json/systemPopulated.json"))?;
+    let n = systems.len() as i64;
+    assert_eq!(
+        write_parquet("systemPopulated.parquet", systems, 1)?
+            .iter()
+            .sum::<i64>(),
+        n
+    );
+    Ok(())
+}
+
+#[test]
+fn empty_file_has_schema() -> Result<()> {
+    let row_groups = write_parquet::<Station>("empty.parquet", Vec::new(), 10)?;
+    assert!(row_groups.is_empty());
+    Ok(())
+}
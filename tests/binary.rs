@@ -0,0 +1,73 @@
+use edsm_dumps_model::binary::{BinaryReader, BinaryWriter, Encoding};
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+
+#[cfg(any(feature = "bincode", feature = "msgpack"))]
+mod round_trip {
+    use std::fmt::Debug;
+
+    use anyhow::Result;
+
+    use edsm_dumps_model::binary::{BinaryReader, BinaryWriter, Encoding};
+    use edsm_dumps_model::model::body::Body;
+    use edsm_dumps_model::model::lenient::Lenient;
+    use edsm_dumps_model::model::station::Station;
+    use edsm_dumps_model::model::system_populated::SystemPopulated;
+    use edsm_dumps_model::model::RootEntry;
+    use edsm_dumps_model::reader::DumpReader;
+
+    fn round_trip<T: RootEntry + PartialEq + Debug>(
+        encoding: Encoding,
+        sample: &[u8],
+    ) -> Result<()> {
+        let entries = DumpReader::<_, T>::new(sample).collect::<Result<Vec<_>>>()?;
+
+        let mut w = BinaryWriter::new(encoding, Vec::new())?;
+        for entry in &entries {
+            w.write(entry)?;
+        }
+        assert_eq!(w.count(), entries.len() as u64);
+        let encoded = w.finish()?;
+        assert!(encoded.len() < sample.len());
+
+        let decoded =
+            BinaryReader::<_, T>::new(encoding, &encoded[..])?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(decoded, entries);
+        Ok(())
+    }
+
+    fn round_trip_samples(encoding: Encoding) -> Result<()> {
+        let body = include_bytes!("./sample_json/body.json");
+        round_trip::<Body>(encoding, body)?;
+        round_trip::<Lenient<Body>>(encoding, body)?;
+        round_trip::<Station>(encoding, include_bytes!("./sample_json/station.json"))?;
+        round_trip::<SystemPopulated>(
+            encoding,
+            include_bytes!("./sample_json/systemPopulated.json"),
+        )
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode() -> Result<()> {
+        round_trip_samples(Encoding::Bincode)
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack() -> Result<()> {
+        round_trip_samples(Encoding::MessagePack)
+    }
+}
+
+#[test]
+fn requires_feature() {
+    for encoding in [Encoding::Bincode, Encoding::MessagePack] {
+        let result = BinaryWriter::<_, SystemPopulated>::new(encoding, Vec::new());
+        assert_eq!(result.is_ok(), encoding.is_supported());
+        if let Err(e) = result {
+            assert!(e.to_string().contains(encoding.feature()));
+        }
+        let result = BinaryReader::<_, SystemPopulated>::new(encoding, &b""[..]);
+        assert_eq!(result.is_ok(), encoding.is_supported());
+    }
+}
@@ -0,0 +1,80 @@
+use std::borrow::Cow;
+
+use anyhow::Result;
+
+use edsm_dumps_model::model::body::{Body, BodyRef, BodyT};
+use edsm_dumps_model::model::station::{Station, StationRef};
+use edsm_dumps_model::model::system::{System, SystemWithCoordinates, SystemWithCoordinatesRef};
+use edsm_dumps_model::model::RootEntryRef;
+use edsm_dumps_model::reader::DumpReader;
+
+macro_rules! check_matches_owned {
+    ($t:ty, $b:ident, $bs:expr) => {{
+        let bs: &[u8] = $bs;
+        let owned = DumpReader::<_, $t>::new(bs).collect::<Result<Vec<_>>>()?;
+
+        let mut r = DumpReader::<_, $t>::new(bs);
+        let mut borrowed = Vec::new();
+        while let Some(entry) = r.next_ref::<$b>() {
+            borrowed.push(entry?.to_owned());
+        }
+
+        assert_eq!(owned, borrowed);
+        Ok(())
+    }};
+}
+
+#[test]
+fn body_ref_matches_owned() -> Result<()> {
+    check_matches_owned!(Body, BodyRef, include_bytes!("./sample_json/body.json"))
+}
+
+#[test]
+fn station_ref_matches_owned() -> Result<()> {
+    check_matches_owned!(
+        Station,
+        StationRef,
+        include_bytes!("./sample_json/station.json")
+    )
+}
+
+#[test]
+fn system_ref_matches_owned() -> Result<()> {
+    check_matches_owned!(
+        SystemWithCoordinates,
+        SystemWithCoordinatesRef,
+        include_bytes!("./sample_json/systemWithCoordinates.json")
+    )
+}
+
+#[test]
+fn body_ref_borrows_names() -> Result<()> {
+    let bs = include_bytes!("./sample_json/body.json");
+    let mut r = DumpReader::<_, Body>::new(&bs[..]);
+
+    let mut n = 0;
+    while let Some(body) = r.next_ref::<BodyRef>() {
+        let body = body?;
+        let (name, system_name) = match &body {
+            BodyRef::Planet(x) => (&x.name, x.system_name.as_ref()),
+            BodyRef::Star(x) => (&x.name, x.system_name.as_ref()),
+            BodyRef::Unknown(x) => (&x.name, x.system_name.as_ref()),
+        };
+        assert!(matches!(name, Cow::Borrowed(_)), "{}", body.name());
+        assert!(matches!(system_name, None | Some(Cow::Borrowed(_))));
+        n += 1;
+    }
+    assert_eq!(n, 343);
+
+    Ok(())
+}
+
+#[test]
+fn escaped_name_is_owned() -> Result<()> {
+    let line = br#"{"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":1,"id64":2,"name":"Col \"285\""}"#;
+    let system = SystemWithCoordinatesRef::parse_dump_json(line)?;
+
+    assert!(matches!(system.name, Cow::Owned(_)));
+    assert_eq!(system.name(), r#"Col "285""#);
+    Ok(())
+}
@@ -0,0 +1,134 @@
+#![cfg(all(feature = "type_hash", feature = "msgpack"))]
+
+mod common;
+
+use std::cell::Cell;
+use std::fs::File;
+use std::io::Write;
+
+use anyhow::Result;
+
+use edsm_dumps_model::binary::Encoding;
+use edsm_dumps_model::cache::{
+    CacheHeader, CacheReader, CacheWriter, IncompatibleCache, InvalidCacheHeader,
+};
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::reader::DumpReader;
+
+const SAMPLE: &[u8] = include_bytes!("./sample_json/powerPlay.json");
+
+fn entries() -> Result<Vec<PowerPlay>> {
+    DumpReader::<_, PowerPlay>::new(SAMPLE).collect()
+}
+
+fn write_cache(entries: &[PowerPlay]) -> Result<Vec<u8>> {
+    let mut w = CacheWriter::new(Encoding::MessagePack, Vec::new())?;
+    for entry in entries {
+        w.write(entry)?;
+    }
+    assert_eq!(w.count(), entries.len() as u64);
+    w.finish()
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    let entries = entries()?;
+    let cache = write_cache(&entries)?;
+
+    let r = CacheReader::<_, PowerPlay>::new(&cache[..])?;
+    assert_eq!(
+        r.header(),
+        &CacheHeader::current::<PowerPlay>(Encoding::MessagePack)
+    );
+    assert_eq!(r.collect::<Result<Vec<_>>>()?, entries);
+    Ok(())
+}
+
+#[test]
+fn refuse_other_type() -> Result<()> {
+    let cache = write_cache(&entries()?)?;
+    let err = CacheReader::<_, Station>::new(&cache[..]).err().unwrap();
+    let err = err.downcast_ref::<IncompatibleCache>().unwrap();
+    assert_eq!(err.type_name, "station");
+    assert_eq!(err.found.version, err.expected.version);
+    assert_ne!(err.found.type_hash, err.expected.type_hash);
+    Ok(())
+}
+
+#[test]
+fn refuse_other_version() -> Result<()> {
+    let mut header = CacheHeader::current::<PowerPlay>(Encoding::MessagePack);
+    header.version = "0.1.0".to_owned();
+    let mut cache = Vec::new();
+    header.write_to(&mut cache)?;
+
+    let err = CacheReader::<_, PowerPlay>::new(&cache[..]).err().unwrap();
+    let err = err.downcast_ref::<IncompatibleCache>().unwrap();
+    assert_eq!(err.found, header);
+    assert!(err.to_string().contains("0.1.0"));
+
+    for bs in [&b"{}"[..], &cache[..10]] {
+        let err = CacheReader::<_, PowerPlay>::new(bs).err().unwrap();
+        assert!(
+            err.downcast_ref::<InvalidCacheHeader>().is_some(),
+            "{}",
+            err
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn open_or_rebuild() -> Result<()> {
+    let dir = common::temp_dir();
+    let path = dir.path().join("powerPlay.cache");
+    let rebuilds = Cell::new(0);
+    let open = || {
+        CacheReader::<_, PowerPlay>::open_or_rebuild(Encoding::MessagePack, &path, || {
+            rebuilds.set(rebuilds.get() + 1);
+            Ok(DumpReader::<_, PowerPlay>::new(SAMPLE))
+        })?
+        .collect::<Result<Vec<_>>>()
+    };
+    let entries = entries()?;
+
+    assert_eq!(open()?, entries);
+    assert_eq!(rebuilds.get(), 1);
+    assert_eq!(open()?, entries);
+    assert_eq!(rebuilds.get(), 1);
+
+    // Cache of an older version
+    let mut header = CacheHeader::current::<PowerPlay>(Encoding::MessagePack);
+    header.version = "0.1.0".to_owned();
+    let mut file = File::create(&path)?;
+    header.write_to(&mut file)?;
+    file.write_all(b"garbage")?;
+    drop(file);
+    assert_eq!(open()?, entries);
+    assert_eq!(rebuilds.get(), 2);
+
+    // Truncated cache and other files
+    for bs in [&b""[..], &b"EDSM"[..], &b"[{\"id\":1}]"[..]] {
+        std::fs::write(&path, bs)?;
+        assert_eq!(open()?, entries);
+    }
+    assert_eq!(rebuilds.get(), 5);
+    Ok(())
+}
+
+#[test]
+fn failed_rebuild() -> Result<()> {
+    let dir = common::temp_dir();
+    let path = dir.path().join("powerPlay.cache");
+    let result = CacheReader::<_, PowerPlay>::open_or_rebuild(Encoding::MessagePack, &path, || {
+        Ok(entries()?
+            .into_iter()
+            .map(Ok)
+            .chain(std::iter::once(Err(anyhow::anyhow!("broken dump")))))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 0);
+    Ok(())
+}
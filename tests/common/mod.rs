@@ -0,0 +1,34 @@
+//! Helpers shared by the integration tests.
+
+// Each test binary uses only some of the helpers.
+#![allow(dead_code)]
+
+use anyhow::Result;
+use tempfile::TempDir;
+
+use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::reader::DumpReader;
+
+/// Creates a directory for the files of a test, removed with its contents when dropped, also when the test fails.
+pub fn temp_dir() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("edsm-dumps-model-")
+        .tempdir()
+        .expect("creating a temporary directory")
+}
+
+/// Records of a dump file, one per line between the brackets.
+pub fn records(bs: &[u8]) -> Vec<&[u8]> {
+    std::str::from_utf8(bs)
+        .unwrap()
+        .lines()
+        .map(|line| line.trim().trim_end_matches(','))
+        .filter(|line| !line.is_empty() && *line != "[" && *line != "]")
+        .map(str::as_bytes)
+        .collect()
+}
+
+/// Entries of a dump.
+pub fn read<T: RootEntry>(dump: &[u8]) -> Result<Vec<T>> {
+    DumpReader::<_, T>::new(dump).collect()
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+
+use edsm_dumps_model::compression::{decompress, Compression};
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::reader::DumpReader;
+
+const SAMPLE: &[u8] = include_bytes!("./sample_json/powerPlay.json");
+
+#[test]
+fn detect_magic_bytes() {
+    assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+    assert_eq!(Compression::detect(b"BZh91AY&SY"), Compression::Bzip2);
+    assert_eq!(
+        Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+        Compression::Zstd
+    );
+    assert_eq!(Compression::detect(b"[\n"), Compression::None);
+    assert_eq!(Compression::detect(b""), Compression::None);
+}
+
+#[test]
+fn uncompressed_passthrough() -> Result<()> {
+    let n = read_all(SAMPLE.to_vec())?;
+    assert_eq!(n, 52);
+    Ok(())
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzip_requires_feature() {
+    let err = decompress(&[0x1f, 0x8b, 0x08, 0x00][..]).err().unwrap();
+    assert!(err.to_string().contains("`gzip` feature"));
+}
+
+#[cfg(feature = "gzip")]
+mod gzip {
+    use std::io::Write;
+
+    use super::*;
+
+    fn compress(bs: &[u8]) -> Vec<u8> {
+        let mut enc = ::flate2::write::GzEncoder::new(Vec::new(), ::flate2::Compression::default());
+        enc.write_all(bs).unwrap();
+        enc.finish().unwrap()
+    }
+
+    #[test]
+    fn read_gzip() -> Result<()> {
+        assert_eq!(read_all(compress(SAMPLE))?, 52);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_gzip() {
+        let mut bs = compress(SAMPLE);
+        bs.truncate(bs.len() / 2);
+        assert_truncated(bs, Compression::Gzip);
+    }
+}
+
+#[cfg(feature = "bzip2")]
+mod bzip2 {
+    use std::io::Write;
+
+    use super::*;
+
+    fn compress(bs: &[u8]) -> Vec<u8> {
+        let mut enc = ::bzip2::write::BzEncoder::new(Vec::new(), ::bzip2::Compression::default());
+        enc.write_all(bs).unwrap();
+        enc.finish().unwrap()
+    }
+
+    #[test]
+    fn read_bzip2() -> Result<()> {
+        assert_eq!(read_all(compress(SAMPLE))?, 52);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_bzip2() {
+        let mut bs = compress(SAMPLE);
+        bs.truncate(bs.len() - 8);
+        assert_truncated(bs, Compression::Bzip2);
+    }
+}
+
+#[cfg(feature = "zstd")]
+mod zstd {
+    use super::*;
+
+    fn compress(bs: &[u8]) -> Vec<u8> {
+        ::zstd::encode_all(bs, 3).unwrap()
+    }
+
+    #[test]
+    fn read_zstd() -> Result<()> {
+        assert_eq!(read_all(compress(SAMPLE))?, 52);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_zstd() {
+        let mut bs = compress(SAMPLE);
+        bs.truncate(bs.len() / 2);
+        assert_truncated(bs, Compression::Zstd);
+    }
+}
+
+fn read_all(bs: Vec<u8>) -> Result<usize> {
+    let r = DumpReader::<_, PowerPlay>::new(decompress(std::io::Cursor::new(bs))?);
+    let mut n = 0;
+    for entry in r {
+        entry?;
+        n += 1;
+    }
+    Ok(n)
+}
+
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+fn assert_truncated(bs: Vec<u8>, compression: Compression) {
+    use edsm_dumps_model::compression::TruncatedStream;
+
+    let err = read_all(bs).unwrap_err();
+    let truncated = err
+        .downcast_ref::<TruncatedStream>()
+        .unwrap_or_else(|| panic!("should be reported as truncated stream: {:?}", err));
+    assert_eq!(truncated.compression, compression);
+}
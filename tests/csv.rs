@@ -0,0 +1,121 @@
+#![cfg(feature = "csv")]
+
+use anyhow::Result;
+
+use edsm_dumps_model::csv::{CsvEntry, CsvWriter};
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model::reader::DumpReader;
+
+fn export<T: CsvEntry>(sample: &[u8], columns: Option<&[&str]>) -> Result<Vec<Vec<String>>> {
+    let mut w = match columns {
+        Some(columns) => CsvWriter::<_, T>::with_columns(Vec::new(), columns)?,
+        None => CsvWriter::<_, T>::new(Vec::new())?,
+    };
+    for entry in DumpReader::<_, T>::new(sample) {
+        w.write(&entry?)?;
+    }
+    let n = w.count();
+    let written = w.finish()?;
+
+    let records = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(&written[..])
+        .records()
+        .map(|r| r.map(|r| r.iter().map(str::to_owned).collect()))
+        .collect::<Result<Vec<Vec<String>>, _>>()?;
+    assert_eq!(records.len() as u64, n + 1);
+    Ok(records)
+}
+
+fn check_all_columns<T: CsvEntry>(sample: &[u8]) -> Result<()> {
+    let records = export::<T>(sample, None)?;
+    assert_eq!(records[0], T::column_names());
+    let ids = DumpReader::<_, T>::new(sample)
+        .map(|e| e.map(|e| e.entry_id().to_string()))
+        .collect::<Result<Vec<_>>>()?;
+    let exported: Vec<_> = records[1..].iter().map(|r| r[0].clone()).collect();
+    assert_eq!(exported, ids);
+    Ok(())
+}
+
+#[test]
+fn all_types() -> Result<()> {
+    check_all_columns::<Body>(include_bytes!("./sample_json/body.json"))?;
+    check_all_columns::<PowerPlay>(include_bytes!("./sample_json/powerPlay.json"))?;
+    check_all_columns::<Station>(include_bytes!("./sample_json/station.json"))?;
+    check_all_columns::<SystemWithCoordinates>(include_bytes!(
+        "./sample_json/systemWithCoordinates.json"
+    ))?;
+    check_all_columns::<SystemWithoutCoordinates>(include_bytes!(
+        "./sample_json/systemWithoutCoordinates.json"
+    ))?;
+    check_all_columns::<SystemPopulated>(include_bytes!("./sample_json/systemPopulated.json"))?;
+    Ok(())
+}
+
+#[test]
+fn flattened_columns() {
+    let names = Body::column_names();
+    for name in [
+        "parents",
+        "solid_composition_ice",
+        "solid_composition_metal",
+        "solid_composition_rock",
+        "atmosphere_composition_carbon_dioxide",
+        "materials_iron",
+        "materials_zirconium",
+    ] {
+        assert!(names.iter().any(|n| n == name), "missing {}", name);
+    }
+    assert!(SystemWithCoordinates::column_names().contains(&"coords_x".to_owned()));
+    assert!(SystemWithoutCoordinates::column_names()
+        .contains(&"estimated_coordinates_precision".to_owned()));
+}
+
+#[test]
+fn selected_columns() -> Result<()> {
+    let records = export::<Body>(
+        include_bytes!("./sample_json/body.json"),
+        Some(&[
+            "name",
+            "parents",
+            "materials_iron",
+            "solid_composition_rock",
+        ]),
+    )?;
+    assert_eq!(
+        records[0],
+        vec![
+            "name",
+            "parents",
+            "materials_iron",
+            "solid_composition_rock"
+        ]
+    );
+    assert_eq!(
+        records[1],
+        vec![
+            "Pru Euq PC-D d12-36 B 1",
+            "Star:3;Null:1;Null:0",
+            "21.76",
+            "66.87"
+        ]
+    );
+    // Star without parents
+    assert_eq!(records[2], vec!["Phreia Flyou DZ-S d3-1", "", "", ""]);
+    Ok(())
+}
+
+#[test]
+fn unknown_column() {
+    let result = CsvWriter::<_, PowerPlay>::with_columns(Vec::new(), &["id", "nope"]);
+    let err = result.err().unwrap();
+    assert!(err.to_string().contains("nope"));
+
+    let result = CsvWriter::<_, PowerPlay>::with_columns(Vec::new(), &["id", "id"]);
+    assert!(result.is_err());
+}
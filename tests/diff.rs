@@ -0,0 +1,148 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+
+use edsm_dumps_model::diff::{diff, diff_indexed, Change};
+use edsm_dumps_model::index::{build_index, index_path, IndexedDump};
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::reader::DumpReader;
+
+const SAMPLE: &[u8] = include_bytes!("./sample_json/station.json");
+
+fn write_dump(path: &Path, entries: &[Station]) -> Result<()> {
+    let lines = entries
+        .iter()
+        .map(|entry| Ok(format!("    {}", serde_json::to_string(entry)?)))
+        .collect::<Result<Vec<_>>>()?;
+    fs::write(path, format!("[\n{}\n]", lines.join(",\n")))?;
+    Ok(())
+}
+
+fn dumps() -> Result<(Vec<Station>, Vec<Station>)> {
+    let old: Vec<Station> = DumpReader::new(SAMPLE).collect::<Result<_>>()?;
+
+    let mut new = old.clone();
+    new[0].name = "Renamed".to_owned();
+    new[0].have_market = !new[0].have_market;
+    new.remove(1);
+    let mut added = old[1].clone();
+    added.id = u64::MAX;
+    new.insert(0, added);
+
+    Ok((old, new))
+}
+
+fn check(old: &[Station], new: &[Station], changes: &[Change<Station>]) {
+    assert_eq!(changes.len(), 3);
+    let modified = changes
+        .iter()
+        .find(|c| matches!(c, Change::Modified { .. }))
+        .unwrap();
+    assert_eq!(
+        modified,
+        &Change::Modified {
+            old: old[0].clone(),
+            new: new[1].clone(),
+            fields: vec!["haveMarket".to_owned(), "name".to_owned()],
+        }
+    );
+    assert!(changes.contains(&Change::Removed(old[1].clone())));
+    assert!(changes.contains(&Change::Added(new[0].clone())));
+    assert_eq!(changes.last(), Some(&Change::Added(new[0].clone())));
+    assert!(changes
+        .windows(2)
+        .all(|w| w[0].entry_id() <= w[1].entry_id()));
+}
+
+#[test]
+fn diff_in_memory() -> Result<()> {
+    let (old, new) = dumps()?;
+    let changes = diff(old.iter().cloned().map(Ok), new.iter().cloned().map(Ok))?;
+    check(&old, &new, &changes);
+
+    assert!(diff(old.iter().cloned().map(Ok), old.iter().cloned().map(Ok))?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn diff_out_of_core() -> Result<()> {
+    let (old, new) = dumps()?;
+    let dir = common::temp_dir();
+    let old_path = dir.path().join("old.json");
+    let new_path = dir.path().join("new.json");
+    write_dump(&old_path, &old)?;
+    write_dump(&new_path, &new)?;
+    build_index::<Station, _, _>(&old_path, index_path(&old_path))?;
+    build_index::<Station, _, _>(&new_path, index_path(&new_path))?;
+
+    let changes = diff_indexed(
+        IndexedDump::<Station>::open(&old_path, index_path(&old_path))?,
+        IndexedDump::<Station>::open(&new_path, index_path(&new_path))?,
+    )?
+    .collect::<Result<Vec<_>>>()?;
+    check(&old, &new, &changes);
+    Ok(())
+}
+
+/// Compresses each dump in `members` gzip members, split at lines.
+#[cfg(feature = "gzip")]
+fn gzip_dumps(
+    dir: &Path,
+    members: usize,
+    dumps: [(&str, &[Station]); 2],
+) -> Result<Vec<IndexedDump<Station>>> {
+    use std::io::Write;
+
+    let mut indexed = Vec::new();
+    for (name, entries) in dumps {
+        let json_path = dir.join("dump.json");
+        write_dump(&json_path, entries)?;
+        let json = fs::read(&json_path)?;
+        let lines = json.split_inclusive(|&b| b == b'\n').collect::<Vec<_>>();
+        let mut bs = Vec::new();
+        for block in lines.chunks(lines.len().div_ceil(members)) {
+            let mut enc =
+                ::flate2::write::GzEncoder::new(Vec::new(), ::flate2::Compression::default());
+            enc.write_all(&block.concat())?;
+            bs.extend(enc.finish()?);
+        }
+        let path = dir.join(name);
+        fs::write(&path, bs)?;
+        build_index::<Station, _, _>(&path, index_path(&path))?;
+        let dump = IndexedDump::<Station>::open(&path, index_path(&path))?;
+        assert_eq!(dump.seek_points().len(), members);
+        indexed.push(dump);
+    }
+    Ok(indexed)
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn diff_gzip_sorted() -> Result<()> {
+    use edsm_dumps_model::diff::diff_indexed_with;
+    use edsm_dumps_model::sort::ExternalSort;
+
+    let (old, new) = dumps()?;
+    let dir = common::temp_dir();
+    let sort_dir = common::temp_dir();
+    for members in [1, 2] {
+        let mut dumps = gzip_dumps(
+            dir.path(),
+            members,
+            [("old.json.gz", &old), ("new.json.gz", &new)],
+        )?;
+        let new_dump = dumps.pop().unwrap();
+        let old_dump = dumps.pop().unwrap();
+        let sort = ExternalSort::new().run_len(1).temp_dir(sort_dir.path());
+        let changes = diff_indexed_with(&sort, old_dump, new_dump)?;
+        // Sorted runs are kept until the diff is dropped
+        assert!(fs::read_dir(sort_dir.path())?.count() > 0);
+        let changes = changes.collect::<Result<Vec<_>>>()?;
+        check(&old, &new, &changes);
+        assert_eq!(fs::read_dir(sort_dir.path())?.count(), 0);
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::reader::{DumpReader, Position};
+
+#[test]
+fn read_whole_dump() -> Result<()> {
+    let bs = include_bytes!("./sample_json/systemWithCoordinates.json");
+    let systems =
+        DumpReader::<_, SystemWithCoordinates>::new(&bs[..]).collect::<Result<Vec<_>>>()?;
+
+    assert_eq!(systems.len(), 11);
+    assert_eq!(systems[0].name, "HIP 73368");
+    Ok(())
+}
+
+#[test]
+fn error_has_position() {
+    let bs = concat!(
+        "[\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":1,"id64":10,"name":"A"},"#,
+        "\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"broken","id":2,"id64":20,"name":"B"}"#,
+        "\n",
+        "]\n",
+    );
+    let mut r = DumpReader::<_, SystemWithCoordinates>::new(bs.as_bytes());
+
+    assert!(r.next().unwrap().is_ok());
+    let err = r.next().unwrap().unwrap_err();
+    let pos = err
+        .downcast_ref::<Position>()
+        .expect("error should carry position");
+    assert_eq!(pos.line, 3);
+    assert_eq!(pos.offset, 95);
+    assert!(r.next().is_none());
+}
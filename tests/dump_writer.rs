@@ -0,0 +1,129 @@
+use anyhow::Result;
+
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::lenient::Lenient;
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::reader::DumpReader;
+use edsm_dumps_model::writer::DumpWriter;
+
+fn rewrite<T: RootEntry>(dump: &[u8]) -> Result<Vec<u8>> {
+    let mut w = DumpWriter::<_, T>::new(Vec::new());
+    for entry in DumpReader::<_, T>::new(dump) {
+        w.write(&entry?)?;
+    }
+    w.finish()
+}
+
+fn check_rewrite<T: RootEntry + PartialEq + std::fmt::Debug>(sample: &[u8]) -> Result<Vec<u8>> {
+    let written = rewrite::<T>(sample)?;
+
+    let s = std::str::from_utf8(&written)?;
+    let lines: Vec<&str> = s.split('\n').collect();
+    assert_eq!(lines.first(), Some(&"["));
+    assert_eq!(lines.last(), Some(&"]"));
+    let records = &lines[1..lines.len() - 1];
+    for (i, line) in records.iter().enumerate() {
+        assert!(line.starts_with("    {"));
+        assert_eq!(line.ends_with(','), i + 1 < records.len());
+    }
+    serde_json::from_slice::<serde_json::Value>(&written)?;
+
+    let expected = DumpReader::<_, T>::new(sample).collect::<Result<Vec<_>>>()?;
+    let parsed = DumpReader::<_, T>::new(&written[..]).collect::<Result<Vec<_>>>()?;
+    assert_eq!(parsed, expected);
+    assert_eq!(rewrite::<T>(&written)?, written);
+
+    Ok(written)
+}
+
+#[test]
+fn body() -> Result<()> {
+    let written = check_rewrite::<Body>(include_bytes!("./sample_json/body.json"))?;
+    let s = String::from_utf8(written)?;
+    let unknown = s.lines().filter(|l| l.contains(r#""type":null"#));
+    assert!(unknown.count() > 0);
+    assert!(!s.contains(r#""type":"null""#));
+    Ok(())
+}
+
+#[test]
+fn nested_null_string_kept() -> Result<()> {
+    let sample = String::from_utf8(include_bytes!("./sample_json/body.json").to_vec())?;
+    let line = sample
+        .lines()
+        .find(|l| l.contains(r#""type":"Planet""#))
+        .unwrap();
+    let line = line
+        .trim()
+        .trim_end_matches(',')
+        .replacen('{', r#"{"extra":{"type":"null"},"#, 1);
+    let body = Lenient::<Body>::parse_dump_json(line.as_bytes())?;
+
+    let mut w = DumpWriter::new(Vec::new());
+    w.write(&body)?;
+    let s = String::from_utf8(w.finish()?)?;
+    assert!(s.contains(r#""extra":{"type":"null"}"#), "{}", s);
+    Ok(())
+}
+
+#[test]
+fn powerplay() -> Result<()> {
+    let sample = include_bytes!("./sample_json/powerPlay.json");
+    let written = check_rewrite::<PowerPlay>(sample)?;
+    assert_eq!(
+        String::from_utf8_lossy(&written),
+        String::from_utf8_lossy(sample)
+    );
+    Ok(())
+}
+
+#[test]
+fn station() -> Result<()> {
+    check_rewrite::<Station>(include_bytes!("./sample_json/station.json"))?;
+    Ok(())
+}
+
+#[test]
+fn system_populated() -> Result<()> {
+    check_rewrite::<SystemPopulated>(include_bytes!("./sample_json/systemPopulated.json"))?;
+    Ok(())
+}
+
+#[test]
+fn system_with_coordinates() -> Result<()> {
+    let written = check_rewrite::<SystemWithCoordinates>(include_bytes!(
+        "./sample_json/systemWithCoordinates.json"
+    ))?;
+    let first = String::from_utf8(written)?
+        .lines()
+        .nth(1)
+        .unwrap()
+        .to_owned();
+    assert_eq!(
+        first,
+        r#"    {"coords":{"x":-198.0625,"y":276.03125,"z":0.1875},"date":"2015-05-12 15:29:33","id":4766,"id64":194580063412,"name":"HIP 73368"},"#
+    );
+    Ok(())
+}
+
+#[test]
+fn system_without_coordinates() -> Result<()> {
+    let written = check_rewrite::<SystemWithoutCoordinates>(include_bytes!(
+        "./sample_json/systemWithoutCoordinates.json"
+    ))?;
+    // Integral coordinates are written without a fractional part.
+    assert!(String::from_utf8(written)?.contains(r#""x":-725,"y":-125,"z":21235"#));
+    Ok(())
+}
+
+#[test]
+fn empty_dump() -> Result<()> {
+    let written = DumpWriter::<_, Body>::new(Vec::new()).finish()?;
+    assert_eq!(written, b"[\n]");
+    assert_eq!(DumpReader::<_, Body>::new(&written[..]).count(), 0);
+    Ok(())
+}
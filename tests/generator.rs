@@ -0,0 +1,196 @@
+#![cfg(feature = "generator")]
+
+mod common;
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+
+use edsm_dumps_model::generator::{DumpSet, DumpSetWriter, GeneratedSystem, Generator};
+use edsm_dumps_model::model::body::{Body, BodyT, Parent};
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model::reader::DumpReader;
+
+const SYSTEMS: usize = 200;
+
+fn generate(seed: u64) -> Vec<GeneratedSystem> {
+    Generator::new(seed)
+        .populated_ratio(0.2)
+        .take(SYSTEMS)
+        .collect()
+}
+
+fn write(systems: &[GeneratedSystem]) -> Result<DumpSet<Vec<u8>>> {
+    let mut w = DumpSetWriter::new(DumpSet::default());
+    for system in systems {
+        w.write(system)?;
+    }
+    w.finish()
+}
+
+#[test]
+fn deterministic() -> Result<()> {
+    let dumps = write(&generate(1))?;
+    assert_eq!(write(&generate(1))?, dumps);
+    assert_ne!(
+        write(&generate(2))?.systems_with_coordinates,
+        dumps.systems_with_coordinates
+    );
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    let systems = generate(3);
+    let dumps = write(&systems)?;
+
+    let expected_systems: Vec<SystemWithCoordinates> =
+        systems.iter().map(|s| s.system.clone()).collect();
+    let expected_bodies: Vec<Body> = systems.iter().flat_map(|s| s.bodies.clone()).collect();
+    let expected_stations: Vec<Station> = systems.iter().flat_map(|s| s.stations.clone()).collect();
+    let expected_populated: Vec<SystemPopulated> =
+        systems.iter().filter_map(|s| s.populated.clone()).collect();
+    let expected_power_play: Vec<PowerPlay> =
+        systems.iter().flat_map(|s| s.power_play.clone()).collect();
+    assert!(!expected_populated.is_empty());
+    assert!(!expected_power_play.is_empty());
+
+    assert_eq!(
+        common::read::<SystemWithCoordinates>(&dumps.systems_with_coordinates)?,
+        expected_systems
+    );
+    assert_eq!(common::read::<Body>(&dumps.bodies)?, expected_bodies);
+    assert_eq!(common::read::<Station>(&dumps.stations)?, expected_stations);
+    assert_eq!(
+        common::read::<SystemPopulated>(&dumps.systems_populated)?,
+        expected_populated
+    );
+    assert_eq!(
+        common::read::<PowerPlay>(&dumps.power_play)?,
+        expected_power_play
+    );
+    Ok(())
+}
+
+#[test]
+fn references_resolve() {
+    let systems = generate(4);
+    let mut id64s = HashSet::new();
+    let mut body_ids = HashSet::new();
+    let mut station_ids = HashSet::new();
+
+    for generated in &systems {
+        let system = &generated.system;
+        let id64 = system.id64.unwrap();
+        assert!(id64s.insert(id64), "duplicate id64 {}", id64);
+        for x in [system.coords.x, system.coords.y, system.coords.z] {
+            assert_eq!((x * 32.0).fract(), 0.0);
+        }
+
+        let bodies: HashMap<u64, &Body> =
+            generated.bodies.iter().map(|b| (body_id(b), b)).collect();
+        assert_eq!(bodies.len(), generated.bodies.len());
+        for body in &generated.bodies {
+            assert!(body_ids.insert(body.id()));
+            let (system_id, system_id64, body_id64) = match body {
+                Body::Planet(p) => (p.system_id, p.system_id64, p.id64),
+                Body::Star(s) => (s.system_id, s.system_id64, s.id64),
+                Body::Unknown(_) => panic!("unknown body"),
+            };
+            assert_eq!(system_id, Some(system.id));
+            assert_eq!(system_id64, Some(id64));
+            assert_eq!(body_id64, Some(id64 | body_id(body) << 55));
+
+            // Each parent is the first parent of the next one, down to the root
+            let parents = body.parents().unwrap_or_default();
+            for (i, parent) in parents.iter().enumerate() {
+                let rest = &parents[i + 1..];
+                let parent = match parent {
+                    Parent::Null(0) => {
+                        assert!(rest.is_empty());
+                        continue;
+                    }
+                    Parent::Star(id) => {
+                        assert!(matches!(bodies[id], Body::Star(_)));
+                        bodies[id]
+                    }
+                    Parent::Planet(id) => {
+                        assert!(matches!(bodies[id], Body::Planet(_)));
+                        bodies[id]
+                    }
+                    p => panic!("unexpected parent {:?}", p),
+                };
+                assert_eq!(parent.parents().unwrap_or_default(), rest);
+            }
+        }
+
+        for station in &generated.stations {
+            assert!(station_ids.insert(station.id));
+            assert_eq!(station.system_id, Some(system.id));
+            assert_eq!(station.system_id64, Some(id64));
+            if let Some(body) = &station.body {
+                let found = generated.bodies.iter().find(|b| b.id() == body.id).unwrap();
+                assert_eq!(found.name(), body.name);
+            }
+        }
+
+        if let Some(populated) = &generated.populated {
+            assert_eq!(populated.id, system.id);
+            assert_eq!(populated.id64, system.id64);
+            assert_eq!(populated.bodies, generated.bodies);
+            assert_eq!(populated.stations.len(), generated.stations.len());
+
+            let factions = populated.factions.as_ref().unwrap();
+            let faction_ids: HashSet<u64> = factions.iter().map(|f| f.id).collect();
+            assert_eq!(faction_ids.len(), factions.len());
+            assert_eq!(populated.controlling_faction.id, Some(factions[0].id));
+            let influence: f32 = factions.iter().map(|f| f.influence).sum();
+            assert!((influence - 1.0).abs() < 0.01, "{}", influence);
+            for station in &generated.stations {
+                let faction = station.controlling_faction.as_ref().unwrap();
+                if let Some(id) = faction.id {
+                    assert!(faction_ids.contains(&id));
+                }
+            }
+        } else {
+            assert!(generated.power_play.is_empty());
+        }
+
+        for power_play in &generated.power_play {
+            assert_eq!(power_play.id, system.id);
+            assert_eq!(power_play.id64, id64);
+            assert_eq!(power_play.name, system.name);
+        }
+    }
+}
+
+#[test]
+fn create_files() -> Result<()> {
+    let tmp = common::temp_dir();
+    let dir = tmp.path();
+    let mut w = DumpSetWriter::create(dir)?;
+    for system in Generator::new(5).take(50) {
+        w.write(&system)?;
+    }
+    let count = w.count();
+    w.finish()?;
+
+    assert_eq!(count.systems_with_coordinates, 50);
+    let bodies = DumpReader::<_, Body>::open(dir.join("bodies.json"))?.count();
+    assert_eq!(bodies as u64, count.bodies);
+    let systems =
+        DumpReader::<_, SystemWithCoordinates>::open(dir.join("systemsWithCoordinates.json"))?;
+    assert_eq!(systems.collect::<Result<Vec<_>>>()?.len(), 50);
+    Ok(())
+}
+
+fn body_id(body: &Body) -> u64 {
+    match body {
+        Body::Planet(p) => p.body_id.unwrap(),
+        Body::Star(s) => s.body_id.unwrap(),
+        Body::Unknown(_) => panic!("unknown body"),
+    }
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+
+use edsm_dumps_model::index::{build_index, index_path, IndexBuilder, IndexedDump};
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::reader::DumpReader;
+use edsm_dumps_model::sort::ExternalSort;
+
+const SAMPLE: &[u8] = include_bytes!("./sample_json/powerPlay.json");
+
+fn check_lookups(dump: &Path) -> Result<usize> {
+    let index = index_path(dump);
+    assert_eq!(build_index::<PowerPlay, _, _>(dump, &index)?, 52);
+
+    let expected = DumpReader::<_, PowerPlay>::new(SAMPLE).collect::<Result<Vec<_>>>()?;
+    let mut indexed = IndexedDump::<PowerPlay>::open(dump, &index)?;
+    assert_eq!(indexed.len(), 52);
+    for entry in expected.iter().rev() {
+        // Systems with several powers appear more than once.
+        let first = expected.iter().find(|x| x.id == entry.id);
+        assert_eq!(indexed.get(entry.entry_id())?.as_ref(), first);
+        assert_eq!(indexed.get_by_id64(entry.id64)?.as_ref(), first);
+    }
+    assert_eq!(indexed.get(u64::MAX)?, None);
+    assert_eq!(indexed.get_by_id64(0)?, None);
+    assert!(IndexedDump::<SystemWithCoordinates>::open(dump, &index).is_err());
+
+    Ok(indexed.seek_points().len())
+}
+
+#[test]
+fn index_uncompressed() -> Result<()> {
+    let dir = common::temp_dir();
+    let dump = dir.path().join("powerPlay.json");
+    fs::write(&dump, SAMPLE)?;
+    assert_eq!(check_lookups(&dump)?, 0);
+    Ok(())
+}
+
+#[test]
+fn index_sorted_in_runs() -> Result<()> {
+    let dir = common::temp_dir();
+    let runs = common::temp_dir();
+    let dump = dir.path().join("powerPlay.json");
+    fs::write(&dump, SAMPLE)?;
+    let report = IndexBuilder::new()
+        .sort(ExternalSort::new().run_len(8).temp_dir(runs.path()))
+        .build::<PowerPlay, _, _>(&dump, index_path(&dump))?;
+    assert_eq!(report.parsed, 52);
+    assert_eq!(fs::read_dir(runs.path())?.count(), 0);
+
+    // Same index as sorted in memory
+    let in_memory = dir.path().join("powerPlay.json.idx2");
+    build_index::<PowerPlay, _, _>(&dump, &in_memory)?;
+    assert_eq!(fs::read(index_path(&dump))?, fs::read(in_memory)?);
+    Ok(())
+}
+
+#[test]
+fn index_skip_errors() -> Result<()> {
+    let dir = common::temp_dir();
+    let dump = dir.path().join("powerPlay.json");
+    let sample = std::str::from_utf8(SAMPLE)?;
+    let broken = sample.replacen(r#"{"allegiance""#, r#"{"id":"broken","allegiance""#, 1);
+    fs::write(&dump, broken)?;
+    let index = index_path(&dump);
+
+    assert!(build_index::<PowerPlay, _, _>(&dump, &index).is_err());
+    let report = IndexBuilder::new()
+        .skip_errors(true)
+        .build::<PowerPlay, _, _>(&dump, &index)?;
+    assert_eq!((report.parsed, report.skipped), (51, 1));
+    assert_eq!(IndexedDump::<PowerPlay>::open(&dump, &index)?.len(), 51);
+    Ok(())
+}
+
+#[test]
+fn outdated_index() -> Result<()> {
+    let dir = common::temp_dir();
+    let dump = dir.path().join("powerPlay.json");
+    fs::write(&dump, SAMPLE)?;
+    let index = index_path(&dump);
+    build_index::<PowerPlay, _, _>(&dump, &index)?;
+
+    // Same length, different content
+    let changed = std::str::from_utf8(SAMPLE)?.replacen("Federation", "Federatiox", 1);
+    fs::write(&dump, changed)?;
+    let err = IndexedDump::<PowerPlay>::open(&dump, &index).err().unwrap();
+    assert!(err.to_string().contains("outdated"), "{}", err);
+    Ok(())
+}
+
+/// Splits the sample into blocks of 10 lines, compressed separately.
+#[cfg(any(feature = "gzip", feature = "bzip2", feature = "zstd"))]
+fn compress_blocks(compress: impl Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    let lines: Vec<&[u8]> = SAMPLE.split_inclusive(|b| *b == b'\n').collect();
+    lines
+        .chunks(10)
+        .flat_map(|block| compress(&block.concat()))
+        .collect()
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn index_gzip_members() -> Result<()> {
+    use std::io::Write;
+
+    let bs = compress_blocks(|block| {
+        let mut enc = ::flate2::write::GzEncoder::new(Vec::new(), ::flate2::Compression::default());
+        enc.write_all(block).unwrap();
+        enc.finish().unwrap()
+    });
+    let dir = common::temp_dir();
+    let dump = dir.path().join("powerPlay.json.gz");
+    fs::write(&dump, bs)?;
+    assert_eq!(check_lookups(&dump)?, 6);
+    Ok(())
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn index_bzip2_streams() -> Result<()> {
+    use std::io::Write;
+
+    let bs = compress_blocks(|block| {
+        let mut enc = ::bzip2::write::BzEncoder::new(Vec::new(), ::bzip2::Compression::default());
+        enc.write_all(block).unwrap();
+        enc.finish().unwrap()
+    });
+    let dir = common::temp_dir();
+    let dump = dir.path().join("powerPlay.json.bz2");
+    fs::write(&dump, bs)?;
+    assert_eq!(check_lookups(&dump)?, 6);
+    Ok(())
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn index_zstd_frames() -> Result<()> {
+    let bs = compress_blocks(|block| ::zstd::encode_all(block, 0).unwrap());
+    let dir = common::temp_dir();
+    let dump = dir.path().join("powerPlay.json.zst");
+    fs::write(&dump, bs)?;
+    assert_eq!(check_lookups(&dump)?, 6);
+    Ok(())
+}
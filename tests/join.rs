@@ -0,0 +1,127 @@
+mod common;
+
+use anyhow::Result;
+
+use edsm_dumps_model::join::{join_systems, merge_join, SystemKey};
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::projection::SystemBrief;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::sort::ExternalSort;
+
+#[test]
+fn merge() -> Result<()> {
+    let left = vec![(None, 'a'), (Some(1), 'b'), (Some(3), 'c'), (Some(3), 'd')];
+    let right = vec![
+        (None, 1),
+        (Some(1), 2),
+        (Some(1), 3),
+        (Some(2), 4),
+        (Some(3), 5),
+    ];
+    let mut joined = merge_join(
+        left.into_iter().map(Ok),
+        |l: &(Option<u8>, char)| l.0,
+        right.into_iter().map(Ok),
+        |r: &(Option<u8>, u8)| r.0,
+    );
+    let pairs: Vec<(char, Vec<u8>)> = joined
+        .by_ref()
+        .map(|p| p.map(|(l, r)| (l.1, r.into_iter().map(|r| r.1).collect())))
+        .collect::<Result<_>>()?;
+    assert_eq!(
+        pairs,
+        vec![
+            ('a', vec![]),
+            ('b', vec![2, 3]),
+            ('c', vec![5]),
+            ('d', vec![])
+        ]
+    );
+    assert_eq!(joined.unmatched(), 2);
+    Ok(())
+}
+
+#[test]
+fn unsorted() {
+    let key = |x: &u8| Some(*x);
+    let joined = merge_join(vec![2, 1].into_iter().map(Ok), key, Vec::new(), key);
+    assert!(joined.collect::<Result<Vec<_>>>().is_err());
+
+    let joined = merge_join(
+        vec![1, 3].into_iter().map(Ok),
+        key,
+        vec![2, 1, 3].into_iter().map(Ok),
+        key,
+    );
+    let err = joined.collect::<Result<Vec<_>>>().unwrap_err();
+    assert_eq!(err.to_string(), "right entries are not sorted by key");
+}
+
+#[test]
+fn systems() -> Result<()> {
+    let systems =
+        common::read::<SystemBrief>(include_bytes!("./sample_json/systemWithCoordinates.json"))?;
+    let bodies = common::read::<Body>(include_bytes!("./sample_json/body.json"))?;
+    let stations = common::read::<Station>(include_bytes!("./sample_json/station.json"))?;
+
+    // Take the systems of the bodies and stations, in a different order
+    let mut wanted: Vec<SystemBrief> = bodies
+        .iter()
+        .map(|b| b.system_key())
+        .chain(stations.iter().map(|s| s.system_key()))
+        .flatten()
+        .chain(systems.iter().filter_map(|s| s.id64))
+        .map(|id64| SystemBrief {
+            id64: Some(id64),
+            ..systems[0].clone()
+        })
+        .collect();
+    wanted.sort_by_key(|s| s.id64);
+    wanted.dedup_by_key(|s| s.id64);
+    wanted.reverse();
+
+    let sort = ExternalSort::new().run_len(16);
+    let joined = join_systems(
+        sort.sort_by_key(wanted.iter().cloned().map(Ok), SystemKey::system_key)?,
+        sort.sort_by_key(bodies.iter().cloned().map(Ok), SystemKey::system_key)?,
+        sort.sort_by_key(stations.iter().cloned().map(Ok), SystemKey::system_key)?,
+    );
+    let joined = joined.collect::<Result<Vec<_>>>()?;
+    assert_eq!(joined.len(), wanted.len());
+
+    for entries in &joined {
+        let id64 = entries.system.id64;
+        let expected: Vec<&Body> = bodies.iter().filter(|b| b.system_key() == id64).collect();
+        assert_eq!(entries.bodies.iter().collect::<Vec<_>>(), expected);
+        let expected: Vec<&Station> = stations.iter().filter(|s| s.system_key() == id64).collect();
+        assert_eq!(entries.stations.iter().collect::<Vec<_>>(), expected);
+    }
+    assert!(joined.iter().any(|e| e.bodies.len() > 1));
+    assert!(joined.iter().any(|e| !e.stations.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn unmatched_systems() -> Result<()> {
+    let systems =
+        common::read::<SystemBrief>(include_bytes!("./sample_json/systemWithCoordinates.json"))?;
+    let bodies = common::read::<Body>(include_bytes!("./sample_json/body.json"))?;
+    let stations = common::read::<Station>(include_bytes!("./sample_json/station.json"))?;
+    let sort = ExternalSort::new();
+
+    let mut joined = join_systems(
+        sort.sort_by_key(systems.into_iter().map(Ok), SystemKey::system_key)?,
+        sort.sort_by_key(bodies.iter().cloned().map(Ok), SystemKey::system_key)?,
+        sort.sort_by_key(stations.iter().cloned().map(Ok), SystemKey::system_key)?,
+    );
+    let matched: usize = joined
+        .by_ref()
+        .map(|e| e.map(|e| e.bodies.len()))
+        .sum::<Result<usize>>()?;
+    assert_eq!(
+        matched as u64 + joined.unmatched_bodies(),
+        bodies.len() as u64
+    );
+    assert!(joined.unmatched_stations() <= stations.len() as u64);
+    Ok(())
+}
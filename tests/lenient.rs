@@ -0,0 +1,78 @@
+use anyhow::Result;
+use serde_json::{json, to_value};
+
+use edsm_dumps_model::error::{ParseError, ParseErrorKind};
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::lenient::Lenient;
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::reader::DumpReader;
+
+const STAR: &[u8] = br#"{"absoluteMagnitude":4.755188,"age":11314,"argOfPeriapsis":null,"axialTilt":0,"bodyId":0,"distanceToArrival":0,"id":255264742,"id64":41833730723,"isMainStar":true,"isScoopable":false,"luminosity":"VII","name":"Phreia Flyou DZ-S d3-1","orbitalEccentricity":null,"orbitalInclination":null,"orbitalPeriod":null,"parents":null,"rings":[{"innerRadius":1,"mass":2,"name":"A Ring","outerRadius":3,"type":"Icy","density":0.5}],"rotationalPeriod":0.000020310914351851852,"rotationalPeriodTidallyLocked":false,"semiMajorAxis":null,"solarMasses":0.742188,"solarRadius":0.000015933905463695184,"spectralClass":null,"subType":"Neutron Star","surfaceTemperature":6089449,"systemId":58698280,"systemId64":41833730723,"systemName":"Phreia Flyou DZ-S d3-1","type":"Star","updateTime":"2020-11-18 08:26:07","magneticField":12.5}"#;
+
+const POWER_PLAY: &[u8] = br#"{"allegiance":"Federation","coords":{"x":-5.5625,"y":-0.90625,"z":110.125},"date":"2020-11-17 23:55:47","government":"Corporate","id":995,"id64":2869709317585,"name":"Wulganda","power":"Edmund Mahon","powerState":"Exploited","state":"Boom"}"#;
+
+#[test]
+fn capture_unknown_fields() -> Result<()> {
+    let (body, extra) = Body::parse_dump_json_lenient(STAR)?;
+
+    assert!(matches!(body, Body::Star(_)), "{:?}", body);
+    assert_eq!(extra.get("magneticField"), Some(&json!(12.5)));
+    assert_eq!(extra.get("rings[0].density"), Some(&json!(0.5)));
+    assert_eq!(extra.len(), 2);
+
+    // Strict parsing does not take the record as a star
+    assert!(matches!(Body::parse_dump_json(STAR)?, Body::Unknown(_)));
+    Ok(())
+}
+
+#[test]
+fn write_back() -> Result<()> {
+    let lenient = Lenient::<Body>::parse_dump_json(STAR)?;
+    assert_eq!(
+        lenient.unknown_fields().collect::<Vec<_>>(),
+        vec!["magneticField", "rings[0].density"]
+    );
+
+    let v = to_value(&lenient)?;
+    assert_eq!(v["magneticField"], json!(12.5));
+    assert_eq!(v["rings"][0]["density"], json!(0.5));
+    assert_eq!(
+        Lenient::<Body>::parse_dump_json(&serde_json::to_vec(&v)?)?,
+        lenient
+    );
+    Ok(())
+}
+
+#[test]
+fn known_fields_only() -> Result<()> {
+    let pp = Lenient::<PowerPlay>::parse_dump_json(POWER_PLAY)?;
+
+    assert!(pp.extra.is_empty());
+    assert_eq!(pp.entry, PowerPlay::parse_dump_json(POWER_PLAY)?);
+    Ok(())
+}
+
+#[test]
+fn other_errors() {
+    let line = String::from_utf8(POWER_PLAY.to_vec())
+        .unwrap()
+        .replace(r#""id":995,"#, r#""color":"red","#);
+    let err = PowerPlay::parse_dump_json_lenient(line.as_bytes()).unwrap_err();
+    let err = err.downcast_ref::<ParseError>().unwrap();
+    assert_eq!(err.kind, ParseErrorKind::MissingField);
+}
+
+#[test]
+fn read_dump() -> Result<()> {
+    let sample = include_bytes!("./sample_json/body.json");
+    let strict = DumpReader::<_, Body>::new(&sample[..]).collect::<Result<Vec<_>>>()?;
+    let lenient = DumpReader::<_, Lenient<Body>>::new(&sample[..]).collect::<Result<Vec<_>>>()?;
+
+    assert!(lenient.iter().all(|b| b.extra.is_empty()));
+    assert_eq!(
+        lenient.into_iter().map(|b| b.entry).collect::<Vec<_>>(),
+        strict
+    );
+    Ok(())
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use anyhow::Result;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use edsm_dumps_model::error::ParseError;
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model::model::RootEntry;
+
+type Parse = fn(&[u8]) -> Result<()>;
+
+const SAMPLES: &[(&[u8], Parse)] = &[
+    (include_bytes!("./sample_json/body.json"), parse::<Body>),
+    (
+        include_bytes!("./sample_json/powerPlay.json"),
+        parse::<PowerPlay>,
+    ),
+    (
+        include_bytes!("./sample_json/station.json"),
+        parse::<Station>,
+    ),
+    (
+        include_bytes!("./sample_json/systemPopulated.json"),
+        parse::<SystemPopulated>,
+    ),
+    (
+        include_bytes!("./sample_json/systemWithCoordinates.json"),
+        parse::<SystemWithCoordinates>,
+    ),
+    (
+        include_bytes!("./sample_json/systemWithoutCoordinates.json"),
+        parse::<SystemWithoutCoordinates>,
+    ),
+];
+
+/// Parses `bs` with both entry points, which must agree.
+fn parse<T: RootEntry>(bs: &[u8]) -> Result<()> {
+    let res = T::parse_dump_json(bs).map(drop);
+    let res_mut = T::parse_dump_json_mut(&mut bs.to_vec()).map(drop);
+    assert_eq!(
+        res.is_ok(),
+        res_mut.is_ok(),
+        "{:?}",
+        String::from_utf8_lossy(bs)
+    );
+    res
+}
+
+#[test]
+fn truncated_records() {
+    for (sample, parse) in SAMPLES {
+        for record in common::records(sample).into_iter().take(10) {
+            parse(record).unwrap();
+            for len in 0..record.len() {
+                let err = parse(&record[..len]).err().unwrap();
+                assert!(err.downcast_ref::<ParseError>().is_some(), "{:#}", err);
+            }
+        }
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn pre_filter_truncated() {
+    for record in common::records(SAMPLES[0].0).into_iter().take(10) {
+        let record = std::str::from_utf8(record).unwrap();
+        for len in (0..=record.len()).filter(|&len| record.is_char_boundary(len)) {
+            let s = Body::pre_filter(&record[..len]);
+            let _ = serde_json::from_str::<Body>(&s);
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes(bs in vec(any::<u8>(), 0..256)) {
+        for (_, parse) in SAMPLES {
+            prop_assert!(parse(&bs).is_err());
+        }
+    }
+
+    #[test]
+    fn mutated_records(
+        sample in 0..SAMPLES.len(),
+        record in any::<prop::sample::Index>(),
+        edits in vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+    ) {
+        let (bs, parse) = SAMPLES[sample];
+        let records = common::records(bs);
+        let mut record = record.get(&records).to_vec();
+        for (pos, byte) in edits {
+            let pos = pos.index(record.len());
+            record[pos] = byte;
+        }
+        let _ = parse(&record);
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use std::fs;
+
+use anyhow::Result;
+use chrono::Duration;
+
+use edsm_dumps_model::merge::{merge, merge_files, MergeStats};
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::reader::DumpReader;
+
+const SAMPLE: &[u8] = include_bytes!("./sample_json/systemWithCoordinates.json");
+
+fn base() -> Result<Vec<SystemWithCoordinates>> {
+    DumpReader::new(SAMPLE).collect()
+}
+
+fn deltas(base: &[SystemWithCoordinates]) -> Vec<SystemWithCoordinates> {
+    let mut newer = base[0].clone();
+    newer.date += Duration::days(1);
+    newer.name = "newer".to_owned();
+
+    let mut older = base[1].clone();
+    older.date -= Duration::days(1);
+    older.name = "older".to_owned();
+
+    let mut first = base[2].clone();
+    first.date += Duration::days(2);
+    first.name = "first".to_owned();
+    let mut second = base[2].clone();
+    second.date += Duration::days(1);
+    second.name = "second".to_owned();
+
+    let mut inserted = base[3].clone();
+    inserted.id = 1;
+    inserted.name = "inserted".to_owned();
+
+    vec![newer, older, first, second, inserted]
+}
+
+fn check(base: &[SystemWithCoordinates], deltas: &[SystemWithCoordinates], merged: &[u8]) {
+    let merged = DumpReader::<_, SystemWithCoordinates>::new(merged)
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(merged.len(), base.len() + 1);
+    assert_eq!(merged[0], deltas[0]);
+    assert_eq!(merged[1], base[1]);
+    assert_eq!(merged[2], deltas[2]);
+    assert_eq!(&merged[3..base.len()], &base[3..]);
+    assert_eq!(merged[base.len()], deltas[4]);
+}
+
+#[test]
+fn merge_stats() -> Result<()> {
+    let base = base()?;
+    let deltas = deltas(&base);
+
+    let mut out = Vec::new();
+    let stats = merge(
+        base.iter().cloned().map(Ok),
+        deltas.iter().cloned().map(Ok),
+        &mut out,
+    )?;
+    assert_eq!(
+        stats,
+        MergeStats {
+            kept: base.len() as u64 - 2,
+            inserted: 1,
+            replaced: 2,
+            skipped: 2,
+        }
+    );
+    check(&base, &deltas, &out);
+    Ok(())
+}
+
+#[test]
+fn merge_empty() -> Result<()> {
+    let mut out = Vec::new();
+    let stats = merge::<SystemWithCoordinates, _, _, _>(Vec::new(), Vec::new(), &mut out)?;
+    assert_eq!(stats, MergeStats::default());
+    assert_eq!(
+        DumpReader::<_, SystemWithCoordinates>::new(&out[..]).count(),
+        0
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_files_roundtrip() -> Result<()> {
+    let base = base()?;
+    let deltas = deltas(&base);
+
+    let dir = common::temp_dir();
+    let base_path = dir.path().join("base.json");
+    let delta_paths = [
+        dir.path().join("delta-1.json"),
+        dir.path().join("delta-2.json"),
+    ];
+    let out_path = dir.path().join("out.json");
+
+    fs::write(&base_path, SAMPLE)?;
+    for (path, entries) in delta_paths.iter().zip(deltas.chunks(3)) {
+        let mut out = Vec::new();
+        merge(Vec::new(), entries.iter().cloned().map(Ok), &mut out)?;
+        fs::write(path, out)?;
+    }
+
+    let stats = merge_files::<SystemWithCoordinates, _, _, _>(&base_path, &delta_paths, &out_path)?;
+    assert_eq!(stats.replaced, 2);
+    assert_eq!(stats.inserted, 1);
+    check(&base, &deltas, &fs::read(&out_path)?);
+    Ok(())
+}
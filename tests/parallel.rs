@@ -0,0 +1,64 @@
+#![cfg(feature = "rayon")]
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+
+use edsm_dumps_model::model::body::{Body, BodyT};
+use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::parallel::ParDumpReader;
+use edsm_dumps_model::reader::{DumpReader, Position};
+
+const SAMPLE: &[u8] = include_bytes!("./sample_json/body.json");
+
+#[test]
+fn ordered_matches_sequential() -> Result<()> {
+    let sequential = DumpReader::<_, Body>::new(SAMPLE).collect::<Result<Vec<_>>>()?;
+    let parallel = ParDumpReader::<_, Body>::new(SAMPLE)
+        .chunk_size(4096)
+        .collect::<Result<Vec<_>>>()?;
+
+    assert_eq!(sequential, parallel);
+    Ok(())
+}
+
+#[test]
+fn unordered_yields_every_entry() -> Result<()> {
+    let mut expected = DumpReader::<_, Body>::new(SAMPLE)
+        .map(|b| b.map(|b| b.entry_id()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let ids = Mutex::new(Vec::new());
+    ParDumpReader::<_, Body>::new(SAMPLE)
+        .chunk_size(4096)
+        .for_each_unordered(|b| ids.lock().unwrap().push(b.map(|b| b.id())));
+    let mut ids = ids
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+    expected.sort_unstable();
+    ids.sort_unstable();
+    assert_eq!(expected, ids);
+    Ok(())
+}
+
+#[test]
+fn error_has_position() {
+    let bs = b"[\n    {\"id\":1},\n    {\"id\":2}\n]\n";
+    let errs = ParDumpReader::<_, Body>::new(&bs[..])
+        .map(|r| r.unwrap_err().downcast_ref::<Position>().copied())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        errs,
+        vec![
+            Some(Position { line: 2, offset: 2 }),
+            Some(Position {
+                line: 3,
+                offset: 16
+            }),
+        ]
+    );
+}
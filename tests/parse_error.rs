@@ -0,0 +1,107 @@
+use serde_json::json;
+
+use edsm_dumps_model::error::{ParseError, ParseErrorKind};
+use edsm_dumps_model::model::body::{Body, BodyRef};
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::model::{RootEntry, RootEntryRef};
+use edsm_dumps_model::reader::{DumpReader, Position};
+
+// Lacks `updateTime`, so that it does not fall back to `Body::Unknown`.
+const STAR: &str = r#"{"absoluteMagnitude":4.755188,"age":11314,"argOfPeriapsis":null,"axialTilt":0,"bodyId":0,"distanceToArrival":0,"id":255264742,"id64":41833730723,"isMainStar":true,"isScoopable":false,"luminosity":"VII","name":"Phreia Flyou DZ-S d3-1","orbitalEccentricity":null,"orbitalInclination":null,"orbitalPeriod":null,"parents":null,"rings":[{"innerRadius":1,"mass":2,"name":"A Ring","outerRadius":3,"type":"Icy"},{"innerRadius":4,"mass":"heavy","name":"B Ring","outerRadius":6,"type":"Icy"}],"rotationalPeriod":0.000020310914351851852,"rotationalPeriodTidallyLocked":false,"semiMajorAxis":null,"solarMasses":0.742188,"solarRadius":0.000015933905463695184,"spectralClass":null,"subType":"Neutron Star","surfaceTemperature":6089449,"systemId":58698280,"systemId64":41833730723,"systemName":"Phreia Flyou DZ-S d3-1","type":"Star"}"#;
+
+fn parse_error<T: RootEntry>(line: &str) -> ParseError {
+    let err = T::parse_dump_json(line.as_bytes())
+        .err()
+        .expect("parsing should fail");
+    err.downcast_ref::<ParseError>()
+        .expect("error should be ParseError")
+        .clone()
+}
+
+#[test]
+fn invalid_type_in_nested_field() {
+    let err = parse_error::<Body>(STAR);
+
+    assert_eq!(err.kind, ParseErrorKind::InvalidType);
+    assert_eq!(err.type_name, "body");
+    assert_eq!(err.id, Some(255264742));
+    assert_eq!(err.path.as_deref(), Some("rings[1].mass"));
+    assert_eq!(err.value, Some(json!("heavy")));
+    assert!(err
+        .to_string()
+        .starts_with("parsing body entry 255264742 at `rings[1].mass`: "));
+
+    let borrowed = BodyRef::parse_dump_json(STAR.as_bytes()).unwrap_err();
+    assert_eq!(borrowed.downcast_ref::<ParseError>(), Some(&err));
+}
+
+#[test]
+fn invalid_value() {
+    let line = r#"{"coords":{"x":0,"y":0,"z":0},"date":"broken","id":2,"id64":20,"name":"B"}"#;
+    let err = parse_error::<SystemWithCoordinates>(line);
+
+    assert_eq!(err.kind, ParseErrorKind::InvalidValue);
+    assert_eq!(err.type_name, "system");
+    assert_eq!(err.id, Some(2));
+    assert_eq!(err.path.as_deref(), Some("date"));
+    assert_eq!(err.value, Some(json!("broken")));
+    assert_eq!(err.to_string().matches("parsing system entry").count(), 1);
+}
+
+#[test]
+fn missing_field() {
+    let line =
+        r#"{"coords":{"x":0,"z":0},"date":"2015-05-12 15:29:33","id":2,"id64":20,"name":"B"}"#;
+    let err = parse_error::<SystemWithCoordinates>(line);
+
+    assert_eq!(err.kind, ParseErrorKind::MissingField);
+    assert_eq!(err.path.as_deref(), Some("coords"));
+    assert_eq!(err.value, Some(json!({"x": 0, "z": 0})));
+}
+
+#[test]
+fn unknown_field() {
+    let line = r#"{"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":2,"id64":20,"name":"B","color":"red"}"#;
+    let err = parse_error::<SystemWithCoordinates>(line);
+
+    assert_eq!(err.kind, ParseErrorKind::UnknownField);
+    assert_eq!(err.id, Some(2));
+}
+
+#[test]
+fn syntax_error() {
+    let err = parse_error::<SystemWithCoordinates>(r#"{"coords":{"x":0,"#);
+
+    assert_eq!(err.kind, ParseErrorKind::Syntax);
+    assert_eq!(err.id, None);
+    assert_eq!(err.path, None);
+}
+
+#[test]
+fn reader_sets_position() {
+    let bs = concat!(
+        "[\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":1,"id64":10,"name":"A"},"#,
+        "\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":"2","id64":20,"name":"B"}"#,
+        "\n",
+        "]\n",
+    );
+    let mut r = DumpReader::<_, SystemWithCoordinates>::new(bs.as_bytes());
+
+    assert!(r.next().unwrap().is_ok());
+    let err = r.next().unwrap().unwrap_err();
+    let parse_error = err
+        .downcast_ref::<ParseError>()
+        .expect("error should be ParseError");
+    let position = Position {
+        line: 3,
+        offset: 95,
+    };
+    assert_eq!(parse_error.position, Some(position));
+    assert_eq!(parse_error.kind, ParseErrorKind::InvalidType);
+    if !cfg!(feature = "simd-json") {
+        assert_eq!(parse_error.path.as_deref(), Some("id"));
+    }
+    assert_eq!(err.downcast_ref::<Position>(), Some(&position));
+}
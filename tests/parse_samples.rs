@@ -1,14 +1,15 @@
-use std::io::{BufRead, BufReader};
-
 use anyhow::{Context, Result};
 use serde_json::{from_slice, to_vec};
 
+#[cfg(any(feature = "bincode", feature = "msgpack"))]
+use edsm_dumps_model::binary::Encoding;
 use edsm_dumps_model::model::body::Body;
 use edsm_dumps_model::model::powerplay::PowerPlay;
 use edsm_dumps_model::model::station::Station;
 use edsm_dumps_model::model::system::{SystemWithCoordinates, SystemWithoutCoordinates};
 use edsm_dumps_model::model::system_populated::SystemPopulated;
 use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::reader::DumpReader;
 
 #[test]
 fn parse_body() -> Result<()> {
@@ -47,28 +48,17 @@ fn parse_system_populated() -> Result<()> {
 }
 
 fn try_parse<T: RootEntry + std::fmt::Debug + PartialEq>(bs: &[u8]) -> Result<()> {
-    let r = BufReader::new(bs);
-
-    for (line_num, line) in r.lines().enumerate() {
-        let line = line?;
-        let line = line.trim().trim_end_matches(',');
-
-        if line == "[" {
-            continue;
-        }
-        if line == "]" {
-            break;
-        }
+    let mut r = DumpReader::<_, T>::new(bs);
 
-        try_round_trip::<T>(line).with_context(|| format!("failed at line {}", line_num + 1))?;
+    while let Some(entry) = r.next() {
+        let entry = entry?;
+        try_round_trip(entry).with_context(|| format!("failed {}", r.position()))?;
     }
 
     Ok(())
 }
 
-fn try_round_trip<T: RootEntry + std::fmt::Debug + PartialEq>(line: &str) -> Result<()> {
-    let decoded = T::parse_dump_json(line.as_bytes()).context("parsing sample JSON")?;
-
+fn try_round_trip<T: RootEntry + std::fmt::Debug + PartialEq>(decoded: T) -> Result<()> {
     let encoded = to_vec(&decoded).context("encoding decoded valuew to JSON")?;
 
     let re_decoded: T = from_slice(&encoded).context("parsing encoded value")?;
@@ -78,5 +68,40 @@ fn try_round_trip<T: RootEntry + std::fmt::Debug + PartialEq>(line: &str) -> Res
         "parsed value and re-parsed value should matches"
     );
 
+    let re_parsed = T::parse_dump_json(&encoded).context("parsing encoded value as dump entry")?;
+
+    assert_eq!(
+        decoded, re_parsed,
+        "parsed value and re-parsed dump entry should matches"
+    );
+
+    #[cfg(feature = "bincode")]
+    try_binary_round_trip(Encoding::Bincode, &decoded)?;
+    #[cfg(feature = "msgpack")]
+    try_binary_round_trip(Encoding::MessagePack, &decoded)?;
+
+    Ok(())
+}
+
+#[cfg(any(feature = "bincode", feature = "msgpack"))]
+fn try_binary_round_trip<T: RootEntry + std::fmt::Debug + PartialEq>(
+    encoding: Encoding,
+    decoded: &T,
+) -> Result<()> {
+    let mut encoded = Vec::new();
+    encoding
+        .encode(&mut encoded, decoded)
+        .with_context(|| format!("encoding decoded value to {}", encoding))?;
+
+    let re_decoded: T = encoding
+        .decode(&mut &encoded[..])
+        .with_context(|| format!("decoding {} encoded value", encoding))?;
+
+    assert_eq!(
+        decoded, &re_decoded,
+        "parsed value and {} decoded value should matches",
+        encoding
+    );
+
     Ok(())
 }
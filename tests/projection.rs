@@ -0,0 +1,116 @@
+mod common;
+
+use anyhow::Result;
+
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::projection::{BodyBrief, SystemBrief};
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::writer::DumpWriter;
+
+/// Checks that reading `dump` as `P` gives the projections of the full entries.
+fn check_projection<T, P>(dump: &[u8]) -> Result<Vec<P>>
+where
+    T: RootEntry,
+    P: RootEntry + for<'a> From<&'a T> + PartialEq + std::fmt::Debug,
+{
+    let expected: Vec<P> = common::read::<T>(dump)?.iter().map(P::from).collect();
+    let projected = common::read::<P>(dump)?;
+    assert_eq!(projected, expected);
+    Ok(projected)
+}
+
+#[test]
+fn system() -> Result<()> {
+    check_projection::<SystemWithCoordinates, SystemBrief>(include_bytes!(
+        "./sample_json/systemWithCoordinates.json"
+    ))?;
+    check_projection::<SystemPopulated, SystemBrief>(include_bytes!(
+        "./sample_json/systemPopulated.json"
+    ))?;
+    check_projection::<PowerPlay, SystemBrief>(include_bytes!("./sample_json/powerPlay.json"))?;
+    Ok(())
+}
+
+#[test]
+fn body() -> Result<()> {
+    let bodies = check_projection::<Body, BodyBrief>(include_bytes!("./sample_json/body.json"))?;
+    let unknown = bodies.iter().filter(|b| matches!(b, BodyBrief::Unknown(_)));
+    assert!(unknown.count() > 0);
+    assert!(bodies.iter().any(|b| matches!(b, BodyBrief::Planet(_))));
+    assert!(bodies.iter().any(|b| matches!(b, BodyBrief::Star(_))));
+    Ok(())
+}
+
+#[test]
+fn body_fallback() -> Result<()> {
+    let planet = r#"{"id":1,"id64":2,"name":"A 1","type":"Planet","subType":"Icy body","distanceToArrival":10,"systemId64":2,"updateTime":"2020-01-01 00:00:00"}"#;
+    assert!(matches!(
+        BodyBrief::parse_dump_json(planet.as_bytes())?,
+        BodyBrief::Planet(_)
+    ));
+
+    // Fields out of the projection are not parsed
+    let extra = planet.replace(r#""name":"A 1""#, r#""name":"A 1","rings":[{"a":[1,{}]}]"#);
+    assert!(matches!(
+        BodyBrief::parse_dump_json(extra.as_bytes())?,
+        BodyBrief::Planet(_)
+    ));
+
+    // Same fallback as `Body` when the record does not match its `type`
+    let missing = planet.replace(r#""distanceToArrival":10,"#, "");
+    assert!(matches!(
+        BodyBrief::parse_dump_json(missing.as_bytes())?,
+        BodyBrief::Unknown(_)
+    ));
+    let barycentre = planet.replace(r#""Planet""#, r#""Barycentre""#);
+    assert!(matches!(
+        BodyBrief::parse_dump_json(barycentre.as_bytes())?,
+        BodyBrief::Unknown(_)
+    ));
+    assert!(matches!(
+        Body::parse_dump_json(barycentre.as_bytes())?,
+        Body::Unknown(_)
+    ));
+
+    let err = BodyBrief::parse_dump_json(br#"{"id":1,"type":"Planet"}"#).unwrap_err();
+    assert!(
+        err.to_string().starts_with("parsing body entry 1"),
+        "{}",
+        err
+    );
+    Ok(())
+}
+
+#[test]
+fn write_projection() -> Result<()> {
+    let sample = include_bytes!("./sample_json/body.json");
+    let bodies = common::read::<BodyBrief>(sample)?;
+    let mut w = DumpWriter::new(Vec::new());
+    for body in &bodies {
+        w.write(body)?;
+    }
+    let written = w.finish()?;
+    assert!(String::from_utf8(written.clone())?.contains(r#""type":null"#));
+    assert_eq!(common::read::<BodyBrief>(&written)?, bodies);
+    Ok(())
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn binary() -> Result<()> {
+    use edsm_dumps_model::binary::{BinaryReader, BinaryWriter, Encoding};
+
+    let bodies = common::read::<BodyBrief>(include_bytes!("./sample_json/body.json"))?;
+    let mut w = BinaryWriter::new(Encoding::MessagePack, Vec::new())?;
+    for body in &bodies {
+        w.write(body)?;
+    }
+    let encoded = w.finish()?;
+    let decoded = BinaryReader::<_, BodyBrief>::new(Encoding::MessagePack, &encoded[..])?
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(decoded, bodies);
+    Ok(())
+}
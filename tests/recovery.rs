@@ -0,0 +1,81 @@
+use anyhow::Result;
+use serde_json::{json, to_value};
+
+use edsm_dumps_model::error::ParseErrorKind;
+use edsm_dumps_model::model::system::SystemWithCoordinates;
+use edsm_dumps_model::reader::DumpReader;
+
+#[test]
+fn skip_bad_records() -> Result<()> {
+    let bs = concat!(
+        "[\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":1,"id64":10,"name":"A"},"#,
+        "\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"broken","id":2,"id64":20,"name":"B"},"#,
+        "\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":3,"id64":30,"#,
+        "\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"also broken","id":4,"id64":40,"name":"D"},"#,
+        "\n",
+        r#"    {"coords":{"x":0,"y":0,"z":0},"date":"2015-05-12 15:29:33","id":5,"id64":50,"name":"E"}"#,
+        "\n",
+        "]\n",
+    );
+    let mut entries = DumpReader::<_, SystemWithCoordinates>::new(bs.as_bytes())
+        .skip_errors()
+        .max_samples(1);
+
+    let ids = entries
+        .by_ref()
+        .map(|r| r.map(|s| s.id))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(ids, vec![1, 5]);
+
+    let report = entries.into_report();
+    assert_eq!(report.parsed, 2);
+    assert_eq!(report.skipped, 3);
+
+    let invalid = &report.kinds[&ParseErrorKind::InvalidValue];
+    assert_eq!(invalid.count, 2);
+    assert_eq!(invalid.samples.len(), 1);
+    assert_eq!(invalid.samples[0].position.line, 3);
+    assert_eq!(invalid.samples[0].id, Some(2));
+    assert_eq!(invalid.samples[0].path.as_deref(), Some("date"));
+    assert!(invalid.samples[0].raw.ends_with(r#""name":"B"}"#));
+
+    let syntax = &report.kinds[&ParseErrorKind::Syntax];
+    assert_eq!(syntax.count, 1);
+    assert_eq!(syntax.samples[0].position.line, 4);
+
+    let v = to_value(&report)?;
+    assert_eq!(v["type_name"], json!("system"));
+    assert_eq!(v["kinds"]["InvalidValue"]["count"], json!(2));
+    assert_eq!(
+        v["kinds"]["Syntax"]["samples"][0]["position"]["line"],
+        json!(4)
+    );
+    Ok(())
+}
+
+#[test]
+fn sample_raw_keeps_escapes() -> Result<()> {
+    // simd-json unescapes strings in place while parsing
+    let bad = r#"{"name":"B \"quoted\" caf\u00e9","coords":{"x":0,"y":0,"z":0},"date":"broken","id":2,"id64":20}"#;
+    let bs = format!(
+        "[\n    {},\n    {}\n]\n",
+        bad,
+        bad.replace("broken", "also broken")
+    );
+    let mut entries = DumpReader::<_, SystemWithCoordinates>::new(bs.as_bytes())
+        .skip_errors()
+        .max_samples(1);
+    assert_eq!(entries.by_ref().count(), 0);
+
+    let report = entries.into_report();
+    let invalid = &report.kinds[&ParseErrorKind::InvalidValue];
+    assert_eq!(invalid.count, 2);
+    assert_eq!(invalid.samples[0].raw, bad);
+    assert_eq!(invalid.samples[0].id, Some(2));
+    assert_eq!(invalid.samples[0].path.as_deref(), Some("date"));
+    Ok(())
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "schemars")]
+
+mod common;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use edsm_dumps_model::model::any::DumpKind;
+
+fn sample(kind: DumpKind) -> &'static [u8] {
+    match kind {
+        DumpKind::Body => include_bytes!("./sample_json/body.json"),
+        DumpKind::PowerPlay => include_bytes!("./sample_json/powerPlay.json"),
+        DumpKind::Station => include_bytes!("./sample_json/station.json"),
+        DumpKind::SystemPopulated => include_bytes!("./sample_json/systemPopulated.json"),
+        DumpKind::SystemWithCoordinates => {
+            include_bytes!("./sample_json/systemWithCoordinates.json")
+        }
+        DumpKind::SystemWithoutCoordinates => {
+            include_bytes!("./sample_json/systemWithoutCoordinates.json")
+        }
+    }
+}
+
+fn records(bs: &[u8]) -> Result<Vec<Value>> {
+    common::records(bs)
+        .into_iter()
+        .map(|record| Ok(serde_json::from_slice(record)?))
+        .collect()
+}
+
+fn validator(kind: DumpKind) -> jsonschema::Validator {
+    let schema = serde_json::to_value(kind.json_schema()).unwrap();
+    jsonschema::validator_for(&schema).unwrap()
+}
+
+#[test]
+fn samples_validate() -> Result<()> {
+    for kind in [
+        DumpKind::Body,
+        DumpKind::PowerPlay,
+        DumpKind::Station,
+        DumpKind::SystemPopulated,
+        DumpKind::SystemWithCoordinates,
+        DumpKind::SystemWithoutCoordinates,
+    ] {
+        let validator = validator(kind);
+        let records = records(sample(kind))?;
+        assert!(!records.is_empty());
+        for record in records {
+            let errors: Vec<String> = validator
+                .iter_errors(&record)
+                .map(|e| format!("{} at {}", e, e.instance_path))
+                .collect();
+            assert!(
+                errors.is_empty(),
+                "{} record {} is invalid: {:?}",
+                kind,
+                record["id"],
+                errors
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn date_pattern() -> Result<()> {
+    let validator = validator(DumpKind::PowerPlay);
+    let mut record = records(sample(DumpKind::PowerPlay))?.remove(0);
+    record["date"] = json!("2020-11-17T23:55:47Z");
+    assert!(!validator.is_valid(&record));
+    Ok(())
+}
+
+#[test]
+fn body_is_tagged() -> Result<()> {
+    let validator = validator(DumpKind::Body);
+    let records = records(sample(DumpKind::Body))?;
+    let mut planet = records.into_iter().find(|r| r["type"] == "Planet").unwrap();
+    planet["type"] = json!("Star");
+    assert!(!validator.is_valid(&planet));
+    Ok(())
+}
+
+#[cfg(not(feature = "unknown_variants"))]
+#[test]
+fn enum_display_strings() -> Result<()> {
+    let schema = serde_json::to_value(DumpKind::Body.json_schema())?;
+    let values = schema["definitions"]["AtmosphereType"]["enum"]
+        .as_array()
+        .unwrap();
+    assert!(values.contains(&json!("Hot thick Carbon dioxide-rich")));
+
+    let validator = validator(DumpKind::Body);
+    let mut planet = records(sample(DumpKind::Body))?
+        .into_iter()
+        .find(|r| r["type"] == "Planet")
+        .unwrap();
+    planet["atmosphereType"] = json!("HotThickCarbonDioxideRich");
+    assert!(!validator.is_valid(&planet));
+    Ok(())
+}
+
+#[test]
+fn deny_unknown_fields() -> Result<()> {
+    let mut station = records(sample(DumpKind::Station))?.remove(0);
+    station["newField"] = json!(1);
+    assert!(!validator(DumpKind::Station).is_valid(&station));
+
+    let mut planet = records(sample(DumpKind::Body))?
+        .into_iter()
+        .find(|r| r["type"] == "Planet" && r["materials"].is_object())
+        .unwrap();
+    planet["materials"]["Unobtainium"] = json!(1.0);
+    assert!(!validator(DumpKind::Body).is_valid(&planet));
+    Ok(())
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use edsm_dumps_model::model::body::{Body, BodyT};
+use edsm_dumps_model::model::projection::BodyBrief;
+use edsm_dumps_model::reader::DumpReader;
+use edsm_dumps_model::sort::ExternalSort;
+
+fn temp_files(dir: &Path) -> Result<usize> {
+    Ok(std::fs::read_dir(dir)?.count())
+}
+
+#[test]
+fn sort_in_runs() -> Result<()> {
+    let sample = include_bytes!("./sample_json/body.json");
+    let bodies = common::read::<Body>(sample)?;
+    let mut expected = bodies.clone();
+    expected.sort_by_key(|b| b.system_id64());
+
+    let tmp = common::temp_dir();
+    let dir = tmp.path();
+    let sorted = ExternalSort::new()
+        .run_len(16)
+        .temp_dir(dir)
+        .sort_by_key(DumpReader::<_, Body>::new(&sample[..]), |b| b.system_id64())?;
+    assert_eq!(sorted.run_count(), bodies.len().div_ceil(16));
+    assert_eq!(temp_files(dir)?, sorted.run_count());
+    assert_eq!(sorted.collect::<Result<Vec<_>>>()?, expected);
+    assert_eq!(temp_files(dir)?, 0);
+
+    // Dropped before the end
+    let mut sorted = ExternalSort::new()
+        .run_len(16)
+        .temp_dir(dir)
+        .sort_by_key(DumpReader::<_, BodyBrief>::new(&sample[..]), |b| b.id())?;
+    assert!(sorted.next().is_some());
+    drop(sorted);
+    assert_eq!(temp_files(dir)?, 0);
+    Ok(())
+}
+
+#[test]
+fn sort_in_memory() -> Result<()> {
+    let sample = include_bytes!("./sample_json/body.json");
+    let mut expected = common::read::<BodyBrief>(sample)?;
+    expected.sort_by_key(|b| std::cmp::Reverse(b.id()));
+
+    let sorted =
+        edsm_dumps_model::sort::sort_by_key(DumpReader::<_, BodyBrief>::new(&sample[..]), |b| {
+            std::cmp::Reverse(b.id())
+        })?;
+    assert_eq!(sorted.run_count(), 0);
+    assert_eq!(sorted.collect::<Result<Vec<_>>>()?, expected);
+    Ok(())
+}
+
+#[test]
+fn sort_error() -> Result<()> {
+    let tmp = common::temp_dir();
+    let dir = tmp.path();
+    // Runs are written for the entries before the invalid one
+    let sample = String::from_utf8(include_bytes!("./sample_json/body.json").to_vec())?;
+    let mut lines: Vec<&str> = sample.lines().take(40).collect();
+    lines.push(r#"    {"id":1,"type":"Planet"},"#);
+    lines.push("]");
+    let dump = lines.join("\n");
+    let result = ExternalSort::new()
+        .run_len(16)
+        .temp_dir(dir)
+        .sort_by_key(DumpReader::<_, Body>::new(dump.as_bytes()), |b| b.id());
+    assert!(result.is_err());
+    assert_eq!(temp_files(dir)?, 0);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn sort_keeps_existing_files() -> Result<()> {
+    let sample = include_bytes!("./sample_json/body.json");
+    let tmp = common::temp_dir();
+    let dir = tmp.path();
+    let target = common::temp_dir();
+    let victim = target.path().join("victim");
+    std::fs::write(&victim, "victim")?;
+    // Links at the names runs used to get are neither followed nor removed
+    for sort in 0..8 {
+        for run in 0..8 {
+            let link = dir.join(format!(
+                "edsm-sort-{}-{}-{}.json",
+                std::process::id(),
+                sort,
+                run
+            ));
+            std::os::unix::fs::symlink(&victim, link)?;
+        }
+    }
+
+    let sorted = ExternalSort::new()
+        .run_len(16)
+        .temp_dir(dir)
+        .sort_by_key(DumpReader::<_, BodyBrief>::new(&sample[..]), |b| b.id())?;
+    assert!(sorted.run_count() > 1);
+    assert_eq!(temp_files(dir)?, 64 + sorted.run_count());
+    assert_eq!(sorted.count(), common::read::<BodyBrief>(sample)?.len());
+    assert_eq!(temp_files(dir)?, 64);
+    assert_eq!(std::fs::read_to_string(&victim)?, "victim");
+    Ok(())
+}
@@ -0,0 +1,270 @@
+#![cfg(feature = "sqlite")]
+
+use anyhow::Result;
+use chrono::Duration;
+use rusqlite::Connection;
+
+use edsm_dumps_model::model::body::Body;
+use edsm_dumps_model::model::powerplay::PowerPlay;
+use edsm_dumps_model::model::station::Station;
+use edsm_dumps_model::model::system::{
+    EstimatedCoords, SystemWithCoordinates, SystemWithoutCoordinates,
+};
+use edsm_dumps_model::model::system_populated::SystemPopulated;
+use edsm_dumps_model::model::RootEntry;
+use edsm_dumps_model::reader::DumpReader;
+use edsm_dumps_model::sqlite::{LoadStats, SqlEntry, SqliteLoader};
+
+fn load<T: SqlEntry>(loader: &mut SqliteLoader, sample: &[u8]) -> Result<LoadStats> {
+    loader.load(DumpReader::<_, T>::new(sample))
+}
+
+fn count(conn: &Connection, sql: &str) -> Result<u64> {
+    Ok(conn.query_row(sql, [], |row| row.get(0))?)
+}
+
+fn load_all() -> Result<SqliteLoader> {
+    let mut loader = SqliteLoader::new(Connection::open_in_memory()?)?.batch_size(7);
+    load::<SystemWithCoordinates>(
+        &mut loader,
+        include_bytes!("./sample_json/systemWithCoordinates.json"),
+    )?;
+    load::<SystemWithoutCoordinates>(
+        &mut loader,
+        include_bytes!("./sample_json/systemWithoutCoordinates.json"),
+    )?;
+    load::<SystemPopulated>(
+        &mut loader,
+        include_bytes!("./sample_json/systemPopulated.json"),
+    )?;
+    load::<Body>(&mut loader, include_bytes!("./sample_json/body.json"))?;
+    load::<Station>(&mut loader, include_bytes!("./sample_json/station.json"))?;
+    load::<PowerPlay>(&mut loader, include_bytes!("./sample_json/powerPlay.json"))?;
+    Ok(loader)
+}
+
+#[test]
+fn load_samples() -> Result<()> {
+    let loader = load_all()?;
+    let conn = loader.connection();
+
+    let stations = DumpReader::<_, Station>::new(&include_bytes!("./sample_json/station.json")[..])
+        .collect::<Result<Vec<_>>>()?;
+    assert!(count(conn, "SELECT count(*) FROM stations")? >= stations.len() as u64);
+    let commodities: usize = stations
+        .iter()
+        .map(|s| s.commodities.as_ref().map_or(0, Vec::len))
+        .sum();
+    assert_eq!(
+        count(conn, "SELECT count(*) FROM commodities")?,
+        commodities as u64
+    );
+
+    let powerplay = count(conn, "SELECT count(*) FROM powerplay")?;
+    assert_eq!(powerplay, 52);
+    assert!(count(conn, "SELECT count(*) FROM bodies")? > 0);
+    assert!(count(conn, "SELECT count(*) FROM materials")? > 0);
+    assert!(count(conn, "SELECT count(*) FROM rings")? > 0);
+    assert!(count(conn, "SELECT count(*) FROM station_services")? > 0);
+    assert!(count(conn, "SELECT count(*) FROM faction_states")? > 0);
+
+    // Populated systems reference their factions
+    assert_eq!(
+        count(
+            conn,
+            "SELECT count(*) FROM system_factions sf
+            LEFT JOIN factions f ON f.id = sf.faction_id
+            WHERE f.id IS NULL"
+        )?,
+        0
+    );
+    assert!(
+        count(
+            conn,
+            "SELECT count(*) FROM systems s
+            JOIN factions f ON f.id = s.controlling_faction_id"
+        )? > 0
+    );
+    Ok(())
+}
+
+#[test]
+fn upsert_by_time() -> Result<()> {
+    let sample = &include_bytes!("./sample_json/powerPlay.json")[..];
+    let entries = DumpReader::<_, PowerPlay>::new(sample).collect::<Result<Vec<_>>>()?;
+    let mut loader = SqliteLoader::new(Connection::open_in_memory()?)?;
+    let stats = loader.load(entries.iter().cloned().map(Ok))?;
+    assert_eq!(stats.inserted, entries.len() as u64);
+
+    let mut older = entries[0].clone();
+    older.date -= Duration::days(1);
+    older.name = "Older".to_owned();
+    let mut newer = entries[1].clone();
+    newer.date += Duration::days(1);
+    newer.name = "Newer".to_owned();
+    let stats = loader.load(vec![Ok(older), Ok(newer), Ok(entries[2].clone())])?;
+    assert_eq!(
+        stats,
+        LoadStats {
+            inserted: 0,
+            updated: 2,
+            skipped: 1,
+        }
+    );
+
+    let conn = loader.connection();
+    let name = |id: u64| -> Result<String> {
+        Ok(
+            conn.query_row("SELECT name FROM powerplay WHERE id = ?1", [id], |row| {
+                row.get(0)
+            })?,
+        )
+    };
+    assert_eq!(name(entries[0].entry_id())?, entries[0].name);
+    assert_eq!(name(entries[1].entry_id())?, "Newer");
+    Ok(())
+}
+
+fn populated_sample() -> Result<SystemPopulated> {
+    let sample = &include_bytes!("./sample_json/systemPopulated.json")[..];
+    DumpReader::<_, SystemPopulated>::new(sample)
+        .find(|s| {
+            s.as_ref().map_or(true, |s| {
+                !s.bodies.is_empty()
+                    && !s.stations.is_empty()
+                    && s.factions.as_ref().is_some_and(|f| !f.is_empty())
+            })
+        })
+        .expect("a populated system with bodies, stations and factions")
+}
+
+fn system_count(conn: &Connection, table: &str, column: &str, id: u64) -> Result<u64> {
+    Ok(conn.query_row(
+        &format!("SELECT count(*) FROM {} WHERE {} = ?1", table, column),
+        [id],
+        |row| row.get(0),
+    )?)
+}
+
+#[test]
+fn systems_keep_columns_of_other_dumps() -> Result<()> {
+    let system = populated_sample()?;
+    let newer_coords = SystemWithCoordinates {
+        id: system.id,
+        coords: system.coords,
+        id64: system.id64,
+        name: system.name.clone(),
+        date: system.date + Duration::days(1),
+    };
+    let newer_estimate = SystemWithoutCoordinates {
+        id: system.id,
+        estimated_coordinates: Some(EstimatedCoords {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            precision: 100.0,
+        }),
+        id64: system.id64,
+        name: system.name.clone(),
+        date: system.date + Duration::days(2),
+    };
+
+    let mut loader = SqliteLoader::new(Connection::open_in_memory()?)?;
+    assert_eq!(loader.load(vec![Ok(newer_coords)])?.inserted, 1);
+    assert_eq!(loader.load(vec![Ok(newer_estimate)])?.updated, 1);
+    // The populated entry is older than the coordinates, but not than its own dump
+    assert_eq!(loader.load(vec![Ok(system.clone())])?.updated, 1);
+
+    let conn = loader.connection();
+    let (x, precision, population, coords_date, populated_date): (
+        f64,
+        Option<f64>,
+        Option<u64>,
+        Option<String>,
+        Option<String>,
+    ) = conn.query_row(
+        "SELECT x, precision, population, coords_date, populated_date
+        FROM systems WHERE id = ?1",
+        [system.id],
+        |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        },
+    )?;
+    assert_eq!(x, f64::from(system.coords.x));
+    assert_eq!(precision, None);
+    assert_eq!(population, system.population);
+    assert!(coords_date.is_some());
+    assert!(populated_date.is_some());
+
+    let factions = system.factions.as_ref().map_or(0, Vec::len) as u64;
+    assert_eq!(
+        system_count(conn, "system_factions", "system_id", system.id)?,
+        factions
+    );
+    assert!(system_count(conn, "faction_states", "system_id", system.id)? > 0);
+    assert_eq!(
+        system_count(conn, "bodies", "system_id", system.id)?,
+        system.bodies.len() as u64
+    );
+    assert_eq!(
+        system_count(conn, "stations", "system_id", system.id)?,
+        system.stations.len() as u64
+    );
+    Ok(())
+}
+
+#[test]
+fn skipped_systems_load_nested_entries() -> Result<()> {
+    let system = populated_sample()?;
+    let mut newer = system.clone();
+    newer.date += Duration::days(1);
+    newer.population = Some(1);
+    newer.bodies.clear();
+    newer.stations.clear();
+    newer.factions = Some(Vec::new());
+
+    let mut loader = SqliteLoader::new(Connection::open_in_memory()?)?;
+    loader.load(vec![Ok(newer)])?;
+    assert_eq!(loader.load(vec![Ok(system.clone())])?.skipped, 1);
+
+    let conn = loader.connection();
+    assert_eq!(
+        count(
+            conn,
+            &format!("SELECT population FROM systems WHERE id = {}", system.id)
+        )?,
+        1
+    );
+    assert!(system_count(conn, "system_factions", "system_id", system.id)? > 0);
+    assert_eq!(
+        system_count(conn, "bodies", "system_id", system.id)?,
+        system.bodies.len() as u64
+    );
+    assert_eq!(
+        system_count(conn, "stations", "system_id", system.id)?,
+        system.stations.len() as u64
+    );
+    Ok(())
+}
+
+#[test]
+fn sample_bodies_match_their_systems() -> Result<()> {
+    let loader = load_all()?;
+    let conn = loader.connection();
+    assert_eq!(
+        count(
+            conn,
+            "SELECT count(*) FROM bodies b
+            JOIN systems s ON s.id64 = b.system_id64
+            WHERE s.id != b.system_id"
+        )?,
+        0
+    );
+    Ok(())
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "unknown_variants")]
+
+use anyhow::Result;
+use serde_json::{from_str, to_string};
+use strum::IntoEnumIterator;
+
+use edsm_dumps_model::model::bgs::{Allegiance, Economy, Government, Happiness, Security, State};
+use edsm_dumps_model::model::body::{
+    AsteroidType, AtmosphereType, Body, ReserveLevel, TerraformingState, VolcanismType,
+};
+use edsm_dumps_model::model::powerplay::{Power, PowerPlay, PowerState};
+use edsm_dumps_model::model::station::{OtherService, StationType};
+use edsm_dumps_model::model::RootEntry;
+
+#[test]
+fn known_values_are_not_unknown() -> Result<()> {
+    assert_eq!(from_str::<State>(r#""Civil war""#)?, State::CivilWar);
+    assert_eq!(from_str::<Economy>(r#""High Tech""#)?, Economy::HighTech);
+    assert_eq!(
+        from_str::<AtmosphereType>(r#""Thin Water""#)?,
+        AtmosphereType::ThinWater
+    );
+    assert_eq!(to_string(&State::CivilWar)?, r#""Civil war""#);
+    Ok(())
+}
+
+#[test]
+fn unknown_value_round_trip() -> Result<()> {
+    let json = r#""Space Pirates""#;
+    let state: State = from_str(json)?;
+    assert_eq!(state, State::Unknown("Space Pirates".to_owned()));
+    assert_eq!(to_string(&state)?, json);
+    assert_eq!(state.to_string(), "Space Pirates");
+
+    let json = r#""Brand new atmosphere""#;
+    let typ: AtmosphereType = from_str(json)?;
+    assert_eq!(
+        typ,
+        AtmosphereType::Unknown("Brand new atmosphere".to_owned())
+    );
+    assert_eq!(to_string(&typ)?, json);
+    assert_eq!(typ.to_string(), "Brand new atmosphere");
+
+    let typ: StationType = from_str(r#""Colony Ship""#)?;
+    assert_eq!(typ, StationType::Unknown("Colony Ship".to_owned()));
+    assert_eq!(typ.to_string(), "Colony Ship");
+    Ok(())
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn unknown_value_bincode() -> Result<()> {
+    let values = vec![State::Boom, State::Unknown("Space Pirates".to_owned())];
+    let encoded = bincode::serialize(&values)?;
+    assert_eq!(bincode::deserialize::<Vec<State>>(&encoded)?, values);
+    Ok(())
+}
+
+#[test]
+fn parse_entry_with_unknown_values() -> Result<()> {
+    let line = br#"{"allegiance":"Federation","coords":{"x":-5.5625,"y":-0.90625,"z":110.125},"date":"2020-11-17 23:55:47","government":"Corporate","id":995,"id64":2869709317585,"name":"Wulganda","power":"Jerome Archer","powerState":"Exploited","state":"Boom"}"#;
+    let pp = PowerPlay::parse_dump_json(line)?;
+    assert_eq!(pp.power, Power::Unknown("Jerome Archer".to_owned()));
+    assert_eq!(
+        serde_json::to_value(&pp)?,
+        serde_json::from_slice::<serde_json::Value>(line)?
+    );
+
+    let line = br#"{"argOfPeriapsis":null,"atmosphereComposition":null,"atmosphereType":"Thin Unobtainium","axialTilt":null,"belts":null,"bodyId":5,"distanceToArrival":1234,"earthMasses":0.1,"gravity":0.2,"id":1,"id64":null,"isLandable":true,"materials":null,"name":"Test 1","orbitalEccentricity":null,"orbitalInclination":null,"orbitalPeriod":null,"parents":null,"radius":1000,"reserveLevel":null,"rings":null,"rotationalPeriod":null,"rotationalPeriodTidallyLocked":false,"semiMajorAxis":null,"solidComposition":null,"subType":"Rocky body","surfacePressure":null,"surfaceTemperature":200,"systemId":null,"systemId64":null,"systemName":null,"terraformingState":null,"type":"Planet","updateTime":"2020-11-18 08:26:07","volcanismType":"Unobtainium Geysers"}"#;
+    let body = Body::parse_dump_json(line)?;
+    let planet = match &body {
+        Body::Planet(x) => x,
+        _ => panic!("should be parsed as planet: {:?}", body),
+    };
+    assert_eq!(
+        planet.atmosphere_type,
+        Some(AtmosphereType::Unknown("Thin Unobtainium".to_owned()))
+    );
+    assert_eq!(
+        planet.volcanism_type,
+        Some(VolcanismType::Unknown("Unobtainium Geysers".to_owned()))
+    );
+    Ok(())
+}
+
+#[test]
+fn variant_counts_skip_unknown() {
+    assert!(State::iter().all(|x| !matches!(x, State::Unknown(_))));
+    assert_eq!(Allegiance::iter().count(), Allegiance::VARIANT_COUNT);
+    assert_eq!(Economy::iter().count(), Economy::VARIANT_COUNT);
+    assert_eq!(Government::iter().count(), Government::VARIANT_COUNT);
+    assert_eq!(Happiness::iter().count(), Happiness::VARIANT_COUNT);
+    assert_eq!(Security::iter().count(), Security::VARIANT_COUNT);
+    assert_eq!(State::iter().count(), State::VARIANT_COUNT);
+    assert_eq!(AsteroidType::iter().count(), AsteroidType::VARIANT_COUNT);
+    assert_eq!(AtmosphereType::iter().count(), AtmosphereType::VARIANTS);
+    assert_eq!(
+        AtmosphereType::iter().count(),
+        AtmosphereType::VARIANT_COUNT
+    );
+    assert_eq!(ReserveLevel::iter().count(), ReserveLevel::VARIANT_COUNT);
+    assert_eq!(
+        TerraformingState::iter().count(),
+        TerraformingState::VARIANT_COUNT
+    );
+    assert_eq!(VolcanismType::iter().count(), VolcanismType::VARIANTS);
+    assert_eq!(VolcanismType::iter().count(), VolcanismType::VARIANT_COUNT);
+    assert_eq!(Power::iter().count(), Power::VARIANT_COUNT);
+    assert_eq!(PowerState::iter().count(), PowerState::VARIANT_COUNT);
+    assert_eq!(OtherService::iter().count(), OtherService::VARIANT_COUNT);
+    assert_eq!(StationType::iter().count(), StationType::VARIANT_COUNT);
+}